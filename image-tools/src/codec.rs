@@ -11,6 +11,76 @@ use simulations::BitGrid;
 pub const MAGIC: [u8; 12] = *b"BITVIDEO\xF0\x9F\x8D\x8E";
 pub const VERSION: u32 = 2;
 
+/// The most bytes a 1-bit frame can take up uncompressed
+///
+/// Every frame has to fit in a chunk uncompressed, since that's what the encoder falls back to when RLE doesn't help.
+pub const MAX_FRAME_BYTES: usize = u16::MAX as usize;
+
+/// The most pixels a frame can have on each side
+///
+/// Sides have to fit in the header's `u16`s, and decoders need to hold frames in a [`BitGrid`], which is smaller.
+pub const MAX_FRAME_SIDE: u32 = if (u16::MAX as usize) < BitGrid::MAX_SIDE {
+    u16::MAX as u32
+} else {
+    BitGrid::MAX_SIDE as u32
+};
+
+/// Why frames of some size can't be encoded, see [`check_frame_dims()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameDimsError {
+    /// The width or height is more than [`MAX_FRAME_SIDE`]
+    TooBig { width: u32, height: u32 },
+
+    /// Each side fits, but a whole frame is more than [`MAX_FRAME_BYTES`]
+    TooManyBytes {
+        width: u32,
+        height: u32,
+        bytes: usize,
+    },
+}
+
+impl core::fmt::Display for FrameDimsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooBig { width, height } => write!(
+                f,
+                "{width}x{height} frames are too big, since videos can be at most {MAX_FRAME_SIDE} pixels on each side"
+            ),
+            Self::TooManyBytes {
+                width,
+                height,
+                bytes,
+            } => write!(
+                f,
+                "{width}x{height} frames take {bytes} bytes each, but chunks can hold at most {MAX_FRAME_BYTES}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for FrameDimsError {}
+
+/// Checks that 1-bit frames of `width` x `height` fit in a video
+///
+/// Encoding frames that don't fit panics, so tools can call this up front to report a friendlier error.
+pub fn check_frame_dims(width: u32, height: u32) -> Result<(), FrameDimsError> {
+    if width > MAX_FRAME_SIDE || height > MAX_FRAME_SIDE {
+        return Err(FrameDimsError::TooBig { width, height });
+    }
+
+    // Rows start on a byte boundary, like BitGrid
+    let bytes = (width as usize).div_ceil(8) * height as usize;
+    if bytes > MAX_FRAME_BYTES {
+        return Err(FrameDimsError::TooManyBytes {
+            width,
+            height,
+            bytes,
+        });
+    }
+
+    Ok(())
+}
+
 #[derive(Copy, Clone, Pod, Zeroable, PartialEq, Eq)]
 #[repr(C)]
 pub struct CodecHeader {
@@ -27,21 +97,52 @@ pub struct CodecHeader {
     pub width: u16,
     pub height: u16,
 
+    /// Byte offset (from the start of the stream) of the optional frame index, or `0` if there isn't one
+    ///
+    /// The index is `n_frames` little-endian `u32`s, each the byte offset of that frame's chunk. It comes after
+    /// every chunk, so decoders stop reading chunks when they reach it.
+    pub index_offset: u32,
+
+    /// Bits for optional features of the stream, like [`Self::FLAG_DEFLATE`]
+    pub flags: u32,
+
     /// Reserved for future use
-    pub reserved: [u32; 26],
+    pub reserved: [u32; 24],
 }
 assert_eq_size!(CodecHeader, [u32; 32]);
 
 impl CodecHeader {
     pub const SIZE: usize = core::mem::size_of::<Self>();
 
+    /// Everything after the header (every chunk, and the index) is one raw deflate stream
+    ///
+    /// Inflate it with [`decoder::inflate()`](crate::decoder::inflate) before decoding. Offsets, like
+    /// [`Self::index_offset`], are into the inflated stream.
+    pub const FLAG_DEFLATE: u32 = 1 << 0;
+
+    /// Some frames aren't the size in the header, because the stream has [`ChunkKind::RESIZE`] chunks
+    ///
+    /// The frame index only points at frame chunks, so decoders have to walk the chunks to seek in these streams.
+    pub const FLAG_RESIZES: u32 = 1 << 1;
+
+    /// # Panics
+    /// Panics if `width` or `height` don't fit in a `u16`, rather than writing a header with the wrong dimensions. See
+    /// [`check_frame_dims()`] to catch that sooner.
+    #[track_caller]
     pub fn new(n_frames: usize, width: u32, height: u32) -> Self {
+        let fit = |n: u32| {
+            u16::try_from(n)
+                .unwrap_or_else(|_| panic!("{}", FrameDimsError::TooBig { width, height }))
+        };
+
         Self {
             magic: MAGIC,
             version: VERSION,
             n_frames: n_frames as u32,
-            width: width as u16,
-            height: height as u16,
+            width: fit(width),
+            height: fit(height),
+            index_offset: 0,
+            flags: 0,
             reserved: Zeroable::zeroed(),
         }
     }
@@ -49,6 +150,11 @@ impl CodecHeader {
     pub fn read(bytes: &[u8]) -> Option<Self> {
         Some(bytemuck::pod_read_unaligned(bytes.get(..Self::SIZE)?))
     }
+
+    /// Whether the body of the stream needs inflating first, see [`Self::FLAG_DEFLATE`]
+    pub fn is_deflated(&self) -> bool {
+        self.flags & Self::FLAG_DEFLATE != 0
+    }
 }
 
 impl Debug for CodecHeader {
@@ -62,6 +168,8 @@ impl Debug for CodecHeader {
             .field("n_frames", &self.n_frames)
             .field("width", &self.width)
             .field("height", &self.height)
+            .field("index_offset", &self.index_offset)
+            .field("flags", &format_args!("{:#x}", self.flags))
             .finish()
     }
 }
@@ -85,7 +193,7 @@ impl FrameCompressionKind {
 
     /// The entire bitmap is encoded as runs of set and unset pixels
     ///
-    /// Counts alternate, and always start with unset.
+    /// Counts alternate, and start with unset. If the chunk's `background_set` is `1`, they start with set instead.
     ///
     /// - A `32` x `32` black image would be encoded like `[32*32]`.
     /// - A `32` x `32` white image would be encoded like `[0, 32*32]`
@@ -94,6 +202,30 @@ impl FrameCompressionKind {
     ///
     /// Note: The codec stores the dimensions for the frames
     pub const RUN_LENGTH_ENCODING: Self = Self(1);
+
+    /// Stores the complete frame as 2-bit gray levels, in the layout of a [`PackedGrid2`](crate::gray::PackedGrid2)
+    ///
+    /// Players that only show 1-bit frames treat levels `2` and `3` as set.
+    pub const UNCOMPRESSED_2BPP: Self = Self(2);
+
+    /// The entire frame is encoded as runs of 2-bit gray levels
+    ///
+    /// Each byte is one run: the level is in the top 2 bits, and the low 6 bits are the length of the run minus one.
+    /// So runs are 1 to 64 pixels long, and a longer stretch of one level takes several bytes.
+    ///
+    /// - A `32` x `32` black image would be encoded as 16 `0b00_111111` bytes.
+    /// - A row of 3 white pixels and then 2 dark gray pixels would be encoded like `[0b11_000010, 0b01_000001]`.
+    pub const RUN_LENGTH_ENCODING_2BPP: Self = Self(3);
+
+    /// Whether frames with this compression are 2-bit gray levels rather than 1-bit
+    pub const fn is_2bpp(self) -> bool {
+        matches!(self.0, 2 | 3)
+    }
+
+    /// Whether frames with this compression are stored whole, with no compression
+    pub const fn is_uncompressed(self) -> bool {
+        matches!(self.0, 0 | 2)
+    }
 }
 
 impl Debug for FrameCompressionKind {
@@ -101,6 +233,8 @@ impl Debug for FrameCompressionKind {
         let name = match self.0 {
             0 => "UNCOMPRESSED",
             1 => "RUN_LENGTH_ENCODING",
+            2 => "UNCOMPRESSED_2BPP",
+            3 => "RUN_LENGTH_ENCODING_2BPP",
             _ => "UNKNOWN",
         };
 
@@ -115,12 +249,16 @@ assert_eq_size!(ChunkKind, u16);
 
 impl ChunkKind {
     pub const COMPRESSED_FRAME: Self = Self(1);
+
+    /// Every frame after this chunk has new dimensions, see [`CodecChunkResize`]
+    pub const RESIZE: Self = Self(2);
 }
 
 impl Debug for ChunkKind {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let name = match self.0 {
             1 => "COMPRESSED_FRAME",
+            2 => "RESIZE",
             _ => "UNKNOWN",
         };
 
@@ -133,13 +271,34 @@ impl Debug for ChunkKind {
 pub struct CodecChunkCommon {
     pub kind: ChunkKind,
 
-    /// The count of bytes immediately after this chunk header that are part of this frame
+    /// The count of payload bytes immediately after this chunk's header
     ///
-    /// Note: This is the entire chunk header, not just the "common" base
+    /// Note: This doesn't count the header itself, which is the entire chunk header (like
+    /// [`CodecChunkCompressedFrame::SIZE`]), not just the "common" base. A whole chunk is the header size plus `size`.
+    ///
+    /// Every kind of chunk other than [`ChunkKind::COMPRESSED_FRAME`] has only this common header, so a whole chunk of
+    /// any other kind is [`CodecChunkCommon::SIZE`] plus `size`. That lets decoders skip kinds they don't know.
     pub size: u16,
 }
 assert_eq_size!(CodecChunkCommon, [u16; 2]);
 
+impl CodecChunkCommon {
+    pub const SIZE: usize = core::mem::size_of::<Self>();
+
+    /// The length of the whole chunk, header and payload
+    pub fn chunk_len(&self) -> usize {
+        if self.kind == ChunkKind::COMPRESSED_FRAME {
+            CodecChunkCompressedFrame::SIZE + self.size as usize
+        } else {
+            Self::SIZE + self.size as usize
+        }
+    }
+
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        Some(bytemuck::pod_read_unaligned(bytes.get(..Self::SIZE)?))
+    }
+}
+
 #[derive(Copy, Clone, Debug, Pod, Zeroable, PartialEq, Eq)]
 #[repr(C)]
 pub struct CodecChunkCompressedFrame {
@@ -151,6 +310,8 @@ pub struct CodecChunkCompressedFrame {
     /// If this is `0`, the "background" players should use is "unset" aka BLACK.
     /// If this is `1`, the "background" players should use is "set" aka WHITE.
     /// Other values are reserved.
+    ///
+    /// For [`FrameCompressionKind::RUN_LENGTH_ENCODING`], this is also the color of the first run.
     pub background_set: u8,
 }
 assert_eq_size!(CodecChunkCompressedFrame, [u8; 6]);
@@ -158,6 +319,7 @@ assert_eq_size!(CodecChunkCompressedFrame, [u8; 6]);
 impl CodecChunkCompressedFrame {
     pub const SIZE: usize = core::mem::size_of::<Self>();
 
+    /// Creates a header for a chunk with `size` bytes of payload, see [`CodecChunkCommon::size`]
     pub fn new(size: u16) -> Self {
         let mut this = Self::zeroed();
         this.common.kind = ChunkKind::COMPRESSED_FRAME;
@@ -165,6 +327,43 @@ impl CodecChunkCompressedFrame {
         this
     }
 
+    /// The length of the whole chunk, header and payload
+    pub fn chunk_len(&self) -> usize {
+        Self::SIZE + self.common.size as usize
+    }
+
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        Some(bytemuck::pod_read_unaligned(bytes.get(..Self::SIZE)?))
+    }
+}
+
+/// A whole [`ChunkKind::RESIZE`] chunk, which changes the dimensions of every frame after it
+///
+/// The new dimensions are the payload, so [`CodecChunkCommon::size`] is `4`. Decoders that don't know about resizing
+/// skip these like any other unknown chunk, and then decode the rest of the frames at the wrong size.
+#[derive(Copy, Clone, Debug, Pod, Zeroable, PartialEq, Eq)]
+#[repr(C)]
+pub struct CodecChunkResize {
+    pub common: CodecChunkCommon,
+    pub width: u16,
+    pub height: u16,
+}
+assert_eq_size!(CodecChunkResize, [u16; 4]);
+
+impl CodecChunkResize {
+    pub const SIZE: usize = core::mem::size_of::<Self>();
+
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            common: CodecChunkCommon {
+                kind: ChunkKind::RESIZE,
+                size: (Self::SIZE - CodecChunkCommon::SIZE) as u16,
+            },
+            width,
+            height,
+        }
+    }
+
     pub fn read(bytes: &[u8]) -> Option<Self> {
         Some(bytemuck::pod_read_unaligned(bytes.get(..Self::SIZE)?))
     }
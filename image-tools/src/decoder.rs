@@ -1,15 +1,102 @@
+use alloc::borrow::Cow;
 use core::fmt::Debug;
+use core::ops::Range;
 
 use simulations::BitGrid;
 
 use crate::codec::*;
+use crate::gray::PackedGrid2;
+use crate::rle::expand_runlength;
 
 #[derive(Clone)]
 pub struct VideoDecoder<'a> {
-    bytes: &'a [u8],
+    /// The whole stream, inflated first if it was deflated (and we can)
+    bytes: Cow<'a, [u8]>,
     curr: usize,
+    /// Where chunks stop: either the end of `bytes`, or the start of the frame index
+    end: usize,
     bitmap: BitGrid,
+    /// `id` and `background_set` of the frame in `bitmap`, or `None` if it doesn't hold a whole decoded frame
+    current: Option<(usize, bool)>,
+    /// Levels of the last 2-bit frame. This is only allocated once a stream has one.
+    gray: Option<PackedGrid2>,
+    /// Whether `gray` holds the frame in `bitmap`
+    gray_is_current: bool,
     frame_num: usize,
+    strict: bool,
+    pixel_count_mismatches: usize,
+    /// Set when the stream can't be decoded at all, and reported instead of the first frame
+    stream_error: Option<DecodeError>,
+}
+
+/// Errors reported by [`VideoDecoder::try_next_frame()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The stream ended partway through a chunk
+    Truncated,
+
+    /// A frame didn't cover exactly `width * height` pixels, which usually means the header's dimensions are wrong
+    PixelCountMismatch {
+        /// `id` of the frame, like [`Frame::id`]
+        frame: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The header or a [`ChunkKind::RESIZE`] chunk asked for frames bigger than a [`BitGrid`] can be
+    TooBig { width: u16, height: u16 },
+
+    /// The stream is deflated (see [`CodecHeader::FLAG_DEFLATE`]), and either the `deflate` feature is off or it
+    /// didn't inflate
+    Deflated,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "video stream ends partway through a chunk"),
+            Self::PixelCountMismatch {
+                frame,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "frame #{frame} has {actual} pixels, but the header says frames have {expected}"
+            ),
+            Self::TooBig { width, height } => {
+                write!(
+                    f,
+                    "video has {width}x{height} frames, which are too big to decode"
+                )
+            }
+            Self::Deflated => write!(
+                f,
+                "video stream is deflated, and couldn't be inflated (is the \"deflate\" feature on?)"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Undoes the outer deflate pass of a video stream (see [`CodecHeader::FLAG_DEFLATE`])
+///
+/// Streams that weren't deflated are borrowed as-is, so this is fine to call on any stream. [`VideoDecoder`] already
+/// does this itself, so this is only needed to get at the inflated bytes.
+#[cfg(feature = "deflate")]
+pub fn inflate(bytes: &[u8]) -> std::io::Result<Cow<'_, [u8]>> {
+    use std::io::Read;
+
+    // Leave anything we can't make sense of for the decoder to complain about
+    let Some(mut header) = CodecHeader::read(bytes).filter(|header| header.is_deflated()) else {
+        return Ok(Cow::Borrowed(bytes));
+    };
+    header.flags &= !CodecHeader::FLAG_DEFLATE;
+
+    let mut inflated = bytemuck::bytes_of(&header).to_vec();
+    flate2::read::DeflateDecoder::new(&bytes[CodecHeader::SIZE..]).read_to_end(&mut inflated)?;
+
+    Ok(Cow::Owned(inflated))
 }
 
 impl Debug for VideoDecoder<'_> {
@@ -17,6 +104,8 @@ impl Debug for VideoDecoder<'_> {
         f.debug_struct("VideoDecoder")
             .field("bytes #", &self.bytes.len())
             .field("curr", &self.curr)
+            .field("end", &self.end)
+            .field("strict", &self.strict)
             .field("bitmap dims", &self.bitmap.dims())
             .finish()
     }
@@ -40,7 +129,18 @@ impl Debug for Frame<'_> {
 }
 
 impl<'a> VideoDecoder<'a> {
+    /// Creates a lenient decoder, which treats a truncated stream as if it ended cleanly
     pub fn new(bytes: &'a [u8]) -> Self {
+        Self::new_with_strict(bytes, false)
+    }
+
+    /// Creates a decoder that, when `strict`, reports a truncated stream as [`DecodeError::Truncated`]
+    ///
+    /// Lenient decoders (`strict == false`) silently stop at the last complete frame instead.
+    ///
+    /// Deflated streams are inflated here with the `deflate` feature. Without it (or if they don't inflate), they
+    /// report [`DecodeError::Deflated`] instead of any frames.
+    pub fn new_with_strict(bytes: &'a [u8], strict: bool) -> Self {
         let curr = CodecHeader::SIZE;
         let header = CodecHeader::read(&bytes[..curr])
             // This is a fixed size so easy to catch
@@ -51,108 +151,393 @@ impl<'a> VideoDecoder<'a> {
                 header.version
             );
         }
-        let bitmap = BitGrid::new(header.width as _, header.height as _);
+
+        // Anything we can't inflate stays deflated, for `try_next_frame()` to report
+        #[cfg(feature = "deflate")]
+        let bytes = inflate(bytes).unwrap_or(Cow::Borrowed(bytes));
+        #[cfg(not(feature = "deflate"))]
+        let bytes = Cow::Borrowed(bytes);
+        let header = CodecHeader::read(&bytes[..curr]).unwrap();
+
+        let (bitmap, dims_error) = match BitGrid::try_new(header.width as _, header.height as _) {
+            Ok(bitmap) => (bitmap, None),
+            Err(_) => (
+                BitGrid::new(0, 0),
+                Some(DecodeError::TooBig {
+                    width: header.width,
+                    height: header.height,
+                }),
+            ),
+        };
+        let stream_error = if header.is_deflated() {
+            Some(DecodeError::Deflated)
+        } else {
+            dims_error
+        };
+
+        let end = match header.index_offset {
+            0 => bytes.len(),
+            // A bogus offset just means "no more chunks" sooner
+            offset => bytes.len().min(offset as usize),
+        };
 
         Self {
             bytes,
             curr,
+            end,
             bitmap,
+            current: None,
+            gray: None,
+            gray_is_current: false,
             frame_num: 0,
+            strict,
+            pixel_count_mismatches: 0,
+            stream_error,
         }
     }
 
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// How many decoded frames so far didn't fill exactly `width * height` pixels
+    ///
+    /// Strict decoders report these as [`DecodeError::PixelCountMismatch`], but lenient ones just count them here
+    /// (and print a warning, with the `std` feature).
+    pub fn pixel_count_mismatches(&self) -> usize {
+        self.pixel_count_mismatches
+    }
+
     pub fn header(&self) -> CodecHeader {
         CodecHeader::read(&self.bytes[..CodecHeader::SIZE]).unwrap()
     }
 
+    /// The frame most recently returned by [`Self::next_frame()`] and friends, without decoding anything
+    ///
+    /// This is `None` until a frame has been decoded, and again after [`Self::reset()`]. Reaching the end of the
+    /// stream leaves the last frame in place.
+    pub fn current_frame(&self) -> Option<Frame<'_>> {
+        let (id, background_set) = self.current?;
+        Some(Frame {
+            id,
+            bitmap: &self.bitmap,
+            background_set,
+        })
+    }
+
+    /// The gray levels of the current frame, if it was a 2-bit frame
+    ///
+    /// 2-bit frames are also thresholded into the 1-bit bitmap of [`Self::current_frame()`], with levels `2` and `3`
+    /// as set, so players that only show 1-bit frames can ignore this.
+    pub fn current_gray(&self) -> Option<&PackedGrid2> {
+        if self.current.is_some() && self.gray_is_current {
+            self.gray.as_ref()
+        } else {
+            None
+        }
+    }
+
     pub fn is_finished(&self) -> bool {
-        self.curr == self.bytes.len()
+        self.curr == self.end
+    }
+
+    /// Whether this stream has an index of frame offsets, which makes [`Self::seek_to_frame()`] O(1)
+    ///
+    /// Streams that change size partway through (see [`CodecHeader::FLAG_RESIZES`]) still seek by walking the chunks.
+    pub fn has_index(&self) -> bool {
+        self.header().index_offset != 0
+    }
+
+    /// Looks up the byte offset of the chunk for the frame at `index` (0-based) in the stream's frame index
+    ///
+    /// Returns `None` if the stream has no index, or `index` is past the end of it.
+    pub fn indexed_offset(&self, index: usize) -> Option<usize> {
+        let header = self.header();
+        if header.index_offset == 0 || index >= header.n_frames as usize {
+            return None;
+        }
+
+        let start = header.index_offset as usize + index * size_of::<u32>();
+        let bytes = self.bytes.get(start..start + size_of::<u32>())?;
+
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    /// Jumps to and decodes the frame at `index` (0-based), whose `id` is `index + 1`
+    ///
+    /// Every frame is stored whole, so with an index this is a single lookup and decode. Without one, we have to walk
+    /// the chunk headers from the start of the stream (but don't decode any of the frames we skip).
+    pub fn seek_to_frame(&mut self, index: usize) -> Option<Frame<'_>> {
+        // Jumping straight to a frame would miss any resizes on the way
+        let offset = self
+            .indexed_offset(index)
+            .filter(|_| self.header().flags & CodecHeader::FLAG_RESIZES == 0);
+
+        if let Some(offset) = offset {
+            if offset > self.end {
+                return None;
+            }
+            self.curr = offset;
+        } else {
+            self.reset();
+            for _ in 0..index {
+                self.next_frame_chunk().ok()??;
+            }
+        }
+
+        self.frame_num = index;
+        self.next_frame()
     }
 
     pub fn reset(&mut self) {
         // self.bytes is unchanged
         self.curr = CodecHeader::SIZE;
+        let header = self.header();
+        self.stream_error = if header.is_deflated() {
+            Some(DecodeError::Deflated)
+        } else {
+            self.resize(header.width, header.height).err()
+        };
         self.bitmap.clear();
+        self.current = None;
         self.frame_num = 0;
     }
 
-    /// Splits off the next `n` bytes, if there are that many, and adjusts `curr``
-    fn next(&mut self, n: usize) -> Option<&[u8]> {
-        assert!(self.curr <= self.bytes.len());
-        if let Some(bytes) = self.bytes[self.curr..].get(..n) {
+    /// Makes room for frames of a new size, dropping the current frame if the size changes
+    ///
+    /// Sizes too big for a [`BitGrid`] leave everything as it was and return [`DecodeError::TooBig`].
+    fn resize(&mut self, width: u16, height: u16) -> Result<(), DecodeError> {
+        if self.bitmap.dims() == (width as i16, height as i16) {
+            return Ok(());
+        }
+
+        self.bitmap = BitGrid::try_new(width as usize, height as usize)
+            .map_err(|_| DecodeError::TooBig { width, height })?;
+        self.current = None;
+        self.gray = None;
+        self.gray_is_current = false;
+
+        Ok(())
+    }
+
+    /// Splits off the next `n` bytes, if there are that many, and adjusts `curr`
+    ///
+    /// This returns where they are in `bytes` rather than borrowing them, so the caller can still decode into
+    /// `bitmap`. If there aren't `n` bytes left, `curr` jumps to the end so that the stream reads as finished from now
+    /// on.
+    fn next(&mut self, n: usize) -> Option<Range<usize>> {
+        assert!(self.curr <= self.end);
+        if self.end - self.curr >= n {
             self.curr += n;
-            Some(bytes)
+            Some(self.curr - n..self.curr)
         } else {
-            self.curr = self.bytes.len();
+            self.curr = self.end;
             None
         }
     }
 
-    pub fn next_frame(&mut self) -> Option<Frame> {
-        let chunk = CodecChunkCompressedFrame::read(self.next(CodecChunkCompressedFrame::SIZE)?)?;
+    /// Splits off the next frame's chunk header and payload, skipping over any other kinds of chunk before it
+    ///
+    /// [`ChunkKind::RESIZE`] chunks on the way are applied, so `bitmap` is always the right size for the frame.
+    ///
+    /// Returns `Ok(None)` if the stream ends cleanly first, and [`DecodeError::Truncated`] if it stops partway through
+    /// a chunk (of any kind). Frames too big to decode end the stream with [`DecodeError::TooBig`].
+    ///
+    /// The payload is returned as a range of `bytes`, like [`Self::next()`].
+    fn next_frame_chunk(
+        &mut self,
+    ) -> Result<Option<(CodecChunkCompressedFrame, Range<usize>)>, DecodeError> {
+        // There's nothing we can decode, so the stream is over
+        if let Some(err) = self.stream_error.take() {
+            self.curr = self.end;
+            return Err(err);
+        }
+
+        loop {
+            if self.is_finished() {
+                return Ok(None);
+            }
+
+            let Some(common) = CodecChunkCommon::read(&self.bytes[self.curr..self.end]) else {
+                self.curr = self.end;
+                return Err(DecodeError::Truncated);
+            };
+            let Some(chunk) = self.next(common.chunk_len()) else {
+                return Err(DecodeError::Truncated);
+            };
+
+            if common.kind == ChunkKind::RESIZE {
+                if let Some(resize) = CodecChunkResize::read(&self.bytes[chunk]) {
+                    if let Err(err) = self.resize(resize.width, resize.height) {
+                        self.curr = self.end;
+                        return Err(err);
+                    }
+                }
+                continue;
+            }
+
+            // Probably from a newer encoder, and nothing we know how to use
+            if common.kind != ChunkKind::COMPRESSED_FRAME {
+                continue;
+            }
+
+            let payload = chunk.start + CodecChunkCompressedFrame::SIZE..chunk.end;
+            let header =
+                CodecChunkCompressedFrame::read(&self.bytes[chunk.start..payload.start]).unwrap();
+            return Ok(Some((header, payload)));
+        }
+    }
+
+    /// Decodes the next frame, or returns `None` at the end of the stream
+    ///
+    /// This never reports errors: a lenient decoder never has any, and a strict one ends the stream instead.
+    /// Use [`Self::try_next_frame()`] to tell a truncated stream apart from one that ended cleanly.
+    pub fn next_frame(&mut self) -> Option<Frame<'_>> {
+        self.try_next_frame().ok().flatten()
+    }
+
+    /// Decodes the next frame, or returns `Ok(None)` at the end of the stream
+    ///
+    /// Chunks that aren't frames are skipped, so streams from newer encoders still play.
+    ///
+    /// If the stream stops partway through a chunk, strict decoders return [`DecodeError::Truncated`] once and then
+    /// behave as if finished. Lenient decoders just return `Ok(None)`. Frames too big to decode
+    /// ([`DecodeError::TooBig`]) and streams that couldn't be inflated ([`DecodeError::Deflated`]) end the stream the
+    /// same way.
+    pub fn try_next_frame(&mut self) -> Result<Option<Frame<'_>>, DecodeError> {
+        let (chunk, payload) = match self.next_frame_chunk() {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Ok(None),
+            Err(err) => return self.fail(err),
+        };
+        let bytes = &self.bytes[payload];
+
+        // Whatever happens next, `bitmap` won't hold the old frame anymore
+        self.current = None;
+        self.gray_is_current = chunk.compression.is_2bpp();
 
-        // Move our `bitmap` into the stackframe to convince the borrow checker that
-        //`self.next()` doesn't introduce aliasing.
-        // We use 0x0 dimensions to avoid allocating (and should never read from it anyway).
-        let mut bitmap = BitGrid::new(0, 0);
-        core::mem::swap(&mut bitmap, &mut self.bitmap);
+        let n_pixels = if chunk.compression.is_2bpp() {
+            let (width, height) = self.bitmap.dims();
+            let gray = self
+                .gray
+                .get_or_insert_with(|| PackedGrid2::new(width as u16, height as u16));
+            gray.clear();
 
-        let bytes = self.next(chunk.common.size as usize)?;
+            let n_pixels = if chunk.compression.is_uncompressed() {
+                expand_uncompressed_2bpp(gray, bytes)
+            } else {
+                expand_runlength_2bpp(gray, bytes)
+            };
 
-        if chunk.compression == FrameCompressionKind::UNCOMPRESSED {
-            bitmap.clear();
-            expand_uncompressed(&mut bitmap, bytes);
+            self.bitmap.clear();
+            for y in 0..height {
+                for x in 0..width {
+                    if gray.get(x as u16, y as u16) >= 2 {
+                        self.bitmap.set(x, y, true);
+                    }
+                }
+            }
+
+            n_pixels
+        } else if chunk.compression == FrameCompressionKind::UNCOMPRESSED {
+            self.bitmap.clear();
+            expand_uncompressed(&mut self.bitmap, bytes)
         } else if chunk.compression == FrameCompressionKind::RUN_LENGTH_ENCODING {
-            bitmap.clear();
-            expand_runlength(&mut bitmap, bytes);
+            self.bitmap.clear();
+            let n_pixels = expand_runlength(&mut self.bitmap, bytes);
+            if chunk.background_set != 0 {
+                // Runs started from set, so everything we "skipped" was actually set
+                self.bitmap.invert_in_place();
+            }
+            n_pixels
         } else {
             unimplemented!("Unsupported compression kind: {:?}", chunk.compression);
+        };
+
+        self.frame_num += 1;
+
+        let expected = self.bitmap.width() as usize * self.bitmap.height() as usize;
+        if n_pixels != expected {
+            self.pixel_count_mismatches += 1;
+            let err = DecodeError::PixelCountMismatch {
+                frame: self.frame_num,
+                expected,
+                actual: n_pixels,
+            };
+
+            if self.strict {
+                return Err(err);
+            }
+
+            #[cfg(feature = "std")]
+            eprintln!("[WARNING] {err}");
         }
 
-        // Move it back
-        core::mem::swap(&mut bitmap, &mut self.bitmap);
+        self.current = Some((self.frame_num, chunk.background_set != 0));
+        Ok(self.current_frame())
+    }
 
-        self.frame_num += 1;
-        Some(Frame {
-            id: self.frame_num,
-            bitmap: &self.bitmap,
-            background_set: false,
-        })
+    /// Reports `err` from a strict decoder, and quietly ends the stream for a lenient one
+    fn fail(&self, err: DecodeError) -> Result<Option<Frame<'_>>, DecodeError> {
+        if self.strict {
+            Err(err)
+        } else {
+            Ok(None)
+        }
     }
 }
 
-fn expand_uncompressed(bitmap: &mut BitGrid, in_bytes: &[u8]) {
-    // Bulk-copy everything
-    bitmap.as_mut_bytes().copy_from_slice(in_bytes);
-}
+/// Copies a whole bitmap, returning how many pixels `in_bytes` covers
+fn expand_uncompressed(bitmap: &mut BitGrid, in_bytes: &[u8]) -> usize {
+    // Bulk-copy everything we have room for
+    let out_bytes = bitmap.as_mut_bytes();
+    let n = out_bytes.len().min(in_bytes.len());
+    out_bytes[..n].copy_from_slice(&in_bytes[..n]);
+    bitmap.normalize();
 
-fn expand_runlength(bitmap: &mut BitGrid, in_bytes: &[u8]) {
-    let mut x = 0;
-    let mut y = 0;
+    // Every full row, plus whatever part of a row is left over
+    let width = bitmap.width() as usize;
+    let stride = width.div_ceil(8);
+    if stride == 0 {
+        return 0;
+    }
+    (in_bytes.len() / stride) * width + ((in_bytes.len() % stride) * 8).min(width)
+}
 
-    for pair in in_bytes.chunks(2) {
-        let [num_black, num_white] = [pair[0], *pair.get(1).unwrap_or(&0)];
+/// Copies a whole 2-bit frame, returning how many pixels `in_bytes` covers
+fn expand_uncompressed_2bpp(gray: &mut PackedGrid2, in_bytes: &[u8]) -> usize {
+    let out_bytes = gray.as_mut_bytes();
+    let n = out_bytes.len().min(in_bytes.len());
+    out_bytes[..n].copy_from_slice(&in_bytes[..n]);
 
-        // Skip black pixels
-        for _ in 0..num_black {
-            x += 1;
-            if x >= bitmap.width() {
-                x = 0;
-                y += 1;
-            }
+    let width = gray.width() as usize;
+    let stride = width.div_ceil(4);
+    if stride == 0 {
+        return 0;
+    }
+    (in_bytes.len() / stride) * width + ((in_bytes.len() % stride) * 4).min(width)
+}
 
-            // skip
-        }
+/// Expands runs of 2-bit levels, returning how many pixels the runs add up to
+///
+/// Runs that spill past the end of the frame are counted, but not drawn.
+fn expand_runlength_2bpp(gray: &mut PackedGrid2, in_bytes: &[u8]) -> usize {
+    let n_cells = gray.width() as usize * gray.height() as usize;
+    let mut i = 0;
 
-        // Write white pixels
-        for _ in 0..num_white {
-            bitmap.set(x, y, true);
+    for &run in in_bytes {
+        let level = run >> 6;
+        let len = (run & 0b11_1111) as usize + 1;
 
-            x += 1;
-            if x >= bitmap.width() {
-                x = 0;
-                y += 1;
+        for _ in 0..len {
+            if i < n_cells {
+                let x = i % gray.width() as usize;
+                let y = i / gray.width() as usize;
+                gray.set(x as u16, y as u16, level);
             }
+            i += 1;
         }
     }
+
+    i
 }
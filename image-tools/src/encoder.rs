@@ -1,15 +1,74 @@
+#[cfg(feature = "deflate")]
+use flate2::write::DeflateEncoder;
+#[cfg(feature = "deflate")]
+use flate2::Compression;
 use simulations::BitGrid;
 
+use std::fs::File;
 use std::io;
-use std::io::Write;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
 
 use crate::codec::*;
+use crate::gray::PackedGrid2;
+use crate::rle::encode_runs;
+
+/// A frame waiting to be encoded
+#[derive(Clone)]
+enum PendingFrame {
+    Mono(BitGrid),
+    Gray(PackedGrid2),
+}
+
+impl PendingFrame {
+    fn dims(&self) -> (i16, i16) {
+        match self {
+            Self::Mono(frame) => frame.dims(),
+            Self::Gray(frame) => (frame.width() as i16, frame.height() as i16),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct VideoEncoder {
-    frames: Vec<BitGrid>,
+    frames: Vec<PendingFrame>,
 
     dims: Option<(i16, i16)>,
+
+    /// Whether to append a frame index, see [`CodecHeader::index_offset`]
+    index: bool,
+
+    /// Whether to deflate everything after the header, see [`CodecHeader::FLAG_DEFLATE`]
+    #[cfg(feature = "deflate")]
+    deflate: bool,
+
+    /// One entry per frame from the last encode
+    stats: Vec<FrameStats>,
+}
+
+/// How big a frame came out with each kind of compression, and which one the encoder picked
+///
+/// Sizes are whole chunks, header included, so the chosen sizes plus [`CodecHeader::SIZE`] add up to the video (along
+/// with a [`CodecChunkResize::SIZE`] for each change of size, and the index). A kind of compression whose payload
+/// came out too big for a chunk is never chosen, and its size is `usize::MAX`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameStats {
+    /// `id` of the frame, counting from `1` like the decoder does
+    pub id: usize,
+    pub uncompressed_bytes: usize,
+    pub rle_bytes: usize,
+    pub chosen: FrameCompressionKind,
+}
+
+impl FrameStats {
+    /// Size of the chunk that actually went into the video
+    pub fn chosen_bytes(&self) -> usize {
+        if self.chosen.is_uncompressed() {
+            self.uncompressed_bytes
+        } else {
+            self.rle_bytes
+        }
+    }
 }
 
 impl Default for VideoEncoder {
@@ -23,18 +82,57 @@ impl VideoEncoder {
         Self {
             frames: vec![],
             dims: None,
+            index: false,
+            #[cfg(feature = "deflate")]
+            deflate: false,
+            stats: vec![],
         }
     }
 
+    /// Whether to append an index of frame offsets, so decoders can seek straight to any frame
+    pub fn with_index(mut self, index: bool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Whether to run everything after the header through deflate, on top of each frame's own compression
+    ///
+    /// This helps most with noisy frames (like dithered ones) that RLE can't do much with. Decoders need the
+    /// `deflate` feature to read these, so they're no good on the pico.
+    #[cfg(feature = "deflate")]
+    pub fn with_deflate(mut self, deflate: bool) -> Self {
+        self.deflate = deflate;
+        self
+    }
+
     pub fn frame_count(&self) -> usize {
         self.frames.len()
     }
 
+    /// Per-frame compression stats from the most recent encode, in frame order
+    ///
+    /// This is empty until something has been encoded.
+    pub fn frame_stats(&self) -> &[FrameStats] {
+        &self.stats
+    }
+
+    /// Adds a 1-bit frame
+    ///
+    /// The first frame decides the size in the header. Frames can be other sizes too, and the video changes size
+    /// to match each time (see [`ChunkKind::RESIZE`]).
     pub fn push(&mut self, frame: BitGrid) {
         if self.dims.is_none() {
             self.dims = Some(frame.dims());
         }
-        self.frames.push(frame);
+        self.frames.push(PendingFrame::Mono(frame));
+    }
+
+    /// Adds a 2-bit grayscale frame, which can be mixed freely with 1-bit frames from [`Self::push()`]
+    pub fn push_gray(&mut self, frame: PackedGrid2) {
+        if self.dims.is_none() {
+            self.dims = Some((frame.width() as i16, frame.height() as i16));
+        }
+        self.frames.push(PendingFrame::Gray(frame));
     }
 
     pub fn encode_to_vec(&mut self) -> io::Result<Vec<u8>> {
@@ -44,42 +142,249 @@ impl VideoEncoder {
         Ok(buf)
     }
 
+    /// Encodes to a newly created file at `path`, replacing it if it already exists
+    pub fn encode_to_path(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.encode_to(&mut w)?;
+
+        w.flush()
+    }
+
+    /// Forgets every pushed frame, so the next one pushed decides the video's dimensions again
+    ///
+    /// Settings like [`Self::with_index()`] are kept, as are the stats from the last encode.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.dims = None;
+    }
+
+    /// Encodes every pushed frame, in the order they were pushed
+    ///
+    /// Encoding is single threaded and nothing else (like a timestamp) goes into the stream, so the same frames
+    /// always encode to the same bytes.
+    ///
+    /// Frames are kept afterwards, so encoding again writes the same video. Use [`Self::clear()`] to start over.
+    ///
+    /// Each frame goes in whichever kind of compression makes it smallest, out of the ones whose payload fits in a
+    /// chunk. If no kind fits, this fails with [`io::ErrorKind::InvalidInput`] before writing anything.
     pub fn encode_to(&mut self, w: &mut impl io::Write) -> io::Result<()> {
         // Write out a header, even if we have no frames to encode
-        let header: CodecHeader;
+        let mut header: CodecHeader;
         if let Some((width, height)) = self.dims {
             header = CodecHeader::new(self.frame_count(), width as u32, height as u32);
         } else {
             // No data, write a boring header
             header = CodecHeader::new(0, 0, 0);
         }
+
+        // Each chunk, and whether it's a frame (for the index) rather than a resize
+        let mut chunks: Vec<(bool, Vec<u8>)> = vec![];
+        let mut curr_dims = self.dims;
+        self.stats.clear();
+        for (id, frame) in self.frames.iter().enumerate() {
+            if curr_dims != Some(frame.dims()) {
+                let (width, height) = frame.dims();
+                let resize = CodecChunkResize::new(width as u16, height as u16);
+                chunks.push((false, bytemuck::bytes_of(&resize).to_vec()));
+                header.flags |= CodecHeader::FLAG_RESIZES;
+                curr_dims = Some(frame.dims());
+            }
+
+            let (chunk, stats) = encode_frame(id + 1, frame)?;
+            self.stats.push(stats);
+            chunks.push((true, chunk));
+        }
+
+        // The index goes after every chunk, so we need their sizes before we can write the header
+        let mut offsets: Vec<u32> = vec![];
+        let mut offset = CodecHeader::SIZE;
+        for (is_frame, chunk) in &chunks {
+            if *is_frame {
+                offsets.push(offset as u32);
+            }
+            offset += chunk.len();
+        }
+        if self.index {
+            header.index_offset = offset as u32;
+        }
+        #[cfg(feature = "deflate")]
+        if self.deflate {
+            header.flags |= CodecHeader::FLAG_DEFLATE;
+        }
+
+        w.write_all(bytemuck::bytes_of(&header))?;
+        let index = self.index.then_some(offsets.as_slice());
+        #[cfg(feature = "deflate")]
+        if self.deflate {
+            let mut body = DeflateEncoder::new(w, Compression::best());
+            write_body(&mut body, &chunks, index)?;
+            body.finish()?;
+            return Ok(());
+        }
+        write_body(w, &chunks, index)
+    }
+}
+
+/// Compresses frame number `id` (counting from `1`) into a whole chunk, picking whichever kind of compression is smallest
+///
+/// Fails with [`io::ErrorKind::InvalidInput`] if no kind fits in a chunk.
+fn encode_frame(id: usize, frame: &PendingFrame) -> io::Result<(Vec<u8>, FrameStats)> {
+    let (uncompressed_bytes, runlength_bytes) = match frame {
+        PendingFrame::Mono(frame) => (compress_uncompressed(frame), compress_runlength(frame)),
+        PendingFrame::Gray(frame) => (
+            compress_uncompressed_2bpp(frame),
+            compress_runlength_2bpp(frame),
+        ),
+    };
+
+    let (uncompressed_len, runlength_len) =
+        (chunk_len(&uncompressed_bytes), chunk_len(&runlength_bytes));
+    let use_uncompressed = uncompressed_len <= runlength_len;
+    let chunk = if use_uncompressed {
+        // stderr, so this doesn't end up in the middle of videos written to stdout
+        if let Some(runlength_bytes) = &runlength_bytes {
+            eprintln!(
+                "Frame #{id} is smaller uncompressed than RLE: {} vs {}",
+                uncompressed_len,
+                runlength_bytes.len()
+            );
+        }
+        uncompressed_bytes
+    } else {
+        runlength_bytes
+    };
+
+    let Some(chunk) = chunk else {
+        let (width, height) = frame.dims();
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Frame #{id} ({width}x{height}) is too big for a chunk with any kind of compression"
+            ),
+        ));
+    };
+
+    let stats = FrameStats {
+        id,
+        uncompressed_bytes: uncompressed_len,
+        rle_bytes: runlength_len,
+        chosen: CodecChunkCompressedFrame::read(&chunk).unwrap().compression,
+    };
+    Ok((chunk, stats))
+}
+
+/// Encodes frames one at a time as they're pushed, rather than holding on to all of them like [`VideoEncoder`]
+///
+/// Each frame's chunk is written as soon as it's pushed, so memory stays bounded no matter how long the video is. The
+/// header goes out first with a frame count of `0`, and [`Self::finish()`] seeks back to fill it in, which is why
+/// this needs a [`Seek`]able writer. There's no frame index or deflate, since both need the whole video up front.
+pub struct StreamingEncoder<W: Write + Seek> {
+    w: W,
+
+    /// Where the header starts in `w`
+    start: u64,
+
+    header: CodecHeader,
+
+    /// Dimensions of the last frame written, or `None` before the first one
+    dims: Option<(i16, i16)>,
+}
+
+impl<W: Write + Seek> StreamingEncoder<W> {
+    /// Starts a video at `w`'s current position, writing a placeholder header
+    pub fn new(mut w: W) -> io::Result<Self> {
+        let start = w.stream_position()?;
+        let header = CodecHeader::new(0, 0, 0);
         w.write_all(bytemuck::bytes_of(&header))?;
 
-        for (id, frame) in self.frames.drain(..).enumerate() {
-            let uncompressed_bytes = compress_uncompressed(&frame);
-            let runlength_bytes = compress_runlength(&frame);
-
-            if uncompressed_bytes.len() <= runlength_bytes.len() {
-                println!(
-                    "Frame #{} is smaller uncompressed than RLE: {} vs {}",
-                    id + 1,
-                    uncompressed_bytes.len(),
-                    runlength_bytes.len()
-                );
-                w.write_all(&uncompressed_bytes)?;
-            } else {
-                w.write_all(&runlength_bytes)?;
+        Ok(Self {
+            w,
+            start,
+            header,
+            dims: None,
+        })
+    }
+
+    /// How many frames have been written so far
+    pub fn frame_count(&self) -> usize {
+        self.header.n_frames as usize
+    }
+
+    /// Compresses `frame` and writes it out straight away
+    ///
+    /// The first frame decides the size in the header, and frames of other sizes get a [`ChunkKind::RESIZE`] first,
+    /// like [`VideoEncoder::push()`].
+    pub fn push(&mut self, frame: BitGrid) -> io::Result<()> {
+        let (width, height) = frame.dims();
+        check_frame_dims(width as u32, height as u32)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+
+        match self.dims {
+            None => {
+                self.header.width = width as u16;
+                self.header.height = height as u16;
+            }
+            Some(dims) if dims != frame.dims() => {
+                let resize = CodecChunkResize::new(width as u16, height as u16);
+                self.w.write_all(bytemuck::bytes_of(&resize))?;
+                self.header.flags |= CodecHeader::FLAG_RESIZES;
             }
+            Some(_) => {}
         }
+        self.dims = Some(frame.dims());
+
+        let id = self.frame_count() + 1;
+        let (chunk, _stats) = encode_frame(id, &PendingFrame::Mono(frame))?;
+        self.w.write_all(&chunk)?;
+        self.header.n_frames += 1;
 
         Ok(())
     }
+
+    /// Fills in the header now that every frame is written, and hands back the writer positioned after the video
+    pub fn finish(mut self) -> io::Result<W> {
+        let end = self.w.stream_position()?;
+        self.w.seek(SeekFrom::Start(self.start))?;
+        self.w.write_all(bytemuck::bytes_of(&self.header))?;
+        self.w.seek(SeekFrom::Start(end))?;
+        self.w.flush()?;
+
+        Ok(self.w)
+    }
 }
 
-fn compress_uncompressed(frame: &BitGrid) -> Vec<u8> {
+/// Writes everything that comes after the [`CodecHeader`]: every chunk, and then the frame index if there is one
+fn write_body(
+    w: &mut impl io::Write,
+    chunks: &[(bool, Vec<u8>)],
+    index: Option<&[u32]>,
+) -> io::Result<()> {
+    for (_is_frame, chunk) in chunks {
+        w.write_all(chunk)?;
+    }
+
+    for offset in index.unwrap_or_default() {
+        w.write_all(&offset.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// The [`CodecChunkCommon::size`] for a chunk with `len` bytes of payload, or `None` if that's too much for one chunk
+fn payload_size(len: usize) -> Option<u16> {
+    u16::try_from(len).ok()
+}
+
+/// Length of a chunk from one of the `compress_*()` functions, with chunks that didn't fit sorting after everything
+fn chunk_len(chunk: &Option<Vec<u8>>) -> usize {
+    chunk.as_ref().map_or(usize::MAX, Vec::len)
+}
+
+/// Stores `frame` as-is, or `None` if it's too big for a chunk
+fn compress_uncompressed(frame: &BitGrid) -> Option<Vec<u8>> {
     let bytes = frame.as_bytes();
 
-    let mut chunk = CodecChunkCompressedFrame::new(bytes.len() as u16);
+    let mut chunk = CodecChunkCompressedFrame::new(payload_size(bytes.len())?);
     chunk.compression = FrameCompressionKind::UNCOMPRESSED;
     chunk.background_set = 0;
 
@@ -89,53 +394,277 @@ fn compress_uncompressed(frame: &BitGrid) -> Vec<u8> {
     cursor.write_all(bytemuck::bytes_of(&chunk)).unwrap();
     cursor.write_all(bytes).unwrap();
 
-    buf
+    Some(buf)
 }
 
-fn compress_runlength(frame: &BitGrid) -> Vec<u8> {
-    let mut runlen_buf = vec![];
-    let mut cursor = io::Cursor::new(&mut runlen_buf);
+/// Run-length encodes `frame`, counting from whichever color makes for fewer bytes
+///
+/// Runs normally start from unset (black). Frames that start on a set pixel (e.g. most mostly-white frames) come out
+/// shorter starting from set, since they skip the empty first run. We mark those with `background_set`. Ties go to
+/// black.
+///
+/// Returns `None` if the runs are too big for a chunk.
+fn compress_runlength(frame: &BitGrid) -> Option<Vec<u8>> {
+    let from_black = encode_runs(frame, false);
+    let from_white = encode_runs(frame, true);
 
-    let mut color: bool = false;
-    let mut count: u8 = 0;
-    for y in 0..frame.height() {
-        for x in 0..frame.width() {
-            if count == u8::MAX {
-                // Break up long streaks to prevent 8-bit overflow.
+    let (runlen_buf, background_set) = if from_white.len() < from_black.len() {
+        (from_white, 1)
+    } else {
+        (from_black, 0)
+    };
 
-                // We'll write out our max length,
-                cursor.write_all(&[u8::MAX]).unwrap();
-                // Write out a 0 for the other color, so we're back to the same color
-                cursor.write_all(&[0]).unwrap();
+    let mut chunk = CodecChunkCompressedFrame::new(payload_size(runlen_buf.len())?);
+    chunk.compression = FrameCompressionKind::RUN_LENGTH_ENCODING;
+    chunk.background_set = background_set;
 
-                count = 0;
-            }
+    let mut buf = vec![];
+    let mut cursor = io::Cursor::new(&mut buf);
+
+    cursor.write_all(bytemuck::bytes_of(&chunk)).unwrap();
+    cursor.write_all(&runlen_buf).unwrap();
+
+    Some(buf)
+}
+
+/// Stores `frame` as-is, or `None` if it's too big for a chunk
+fn compress_uncompressed_2bpp(frame: &PackedGrid2) -> Option<Vec<u8>> {
+    let bytes = frame.as_bytes();
+
+    let mut chunk = CodecChunkCompressedFrame::new(payload_size(bytes.len())?);
+    chunk.compression = FrameCompressionKind::UNCOMPRESSED_2BPP;
+
+    let mut buf = bytemuck::bytes_of(&chunk).to_vec();
+    buf.extend_from_slice(bytes);
 
-            if frame.get(x, y) != color {
-                cursor.write_all(&[count]).unwrap();
+    Some(buf)
+}
+
+/// Run-length encodes `frame`, see [`FrameCompressionKind::RUN_LENGTH_ENCODING_2BPP`]
+///
+/// Returns `None` if the runs are too big for a chunk.
+fn compress_runlength_2bpp(frame: &PackedGrid2) -> Option<Vec<u8>> {
+    let runlen_buf = encode_runs_2bpp(frame);
+
+    let mut chunk = CodecChunkCompressedFrame::new(payload_size(runlen_buf.len())?);
+    chunk.compression = FrameCompressionKind::RUN_LENGTH_ENCODING_2BPP;
+
+    let mut buf = bytemuck::bytes_of(&chunk).to_vec();
+    buf.extend_from_slice(&runlen_buf);
+
+    Some(buf)
+}
+
+/// Packs runs of levels into one byte each: the level in the top 2 bits and `length - 1` in the low 6
+fn encode_runs_2bpp(frame: &PackedGrid2) -> Vec<u8> {
+    const MAX_RUN: u8 = 64;
 
-                color = frame.get(x, y);
-                count = 1;
-            } else {
-                count += 1;
+    let mut runlen_buf = vec![];
+    let mut run: Option<(u8, u8)> = None;
+    for level in frame.levels() {
+        run = match run {
+            Some((run_level, len)) if run_level == level && len < MAX_RUN => Some((level, len + 1)),
+            Some((run_level, len)) => {
+                runlen_buf.push((run_level << 6) | (len - 1));
+                Some((level, 1))
             }
+            None => Some((level, 1)),
+        };
+    }
+    if let Some((level, len)) = run {
+        runlen_buf.push((level << 6) | (len - 1));
+    }
+
+    runlen_buf
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `width` x `height` and all white, except for every 10th pixel
+    fn make_mostly_white(width: usize, height: usize) -> BitGrid {
+        let mut frame = BitGrid::new(width, height);
+        frame.as_mut_bytes().fill(0xFF);
+        for i in (9..width * height).step_by(10) {
+            frame.set((i % width) as i16, (i / width) as i16, false);
         }
+
+        frame
     }
 
-    // Finish whatever color we were on
-    if count > 0 {
-        cursor.write_all(&[count]).unwrap();
+    #[test]
+    fn check_runlength_prefers_white_background() {
+        let frame = make_mostly_white(40, 40);
+
+        let from_black = encode_runs(&frame, false);
+        let from_white = encode_runs(&frame, true);
+        assert!(from_white.len() < from_black.len());
+
+        let bytes = compress_runlength(&frame).unwrap();
+        let chunk = CodecChunkCompressedFrame::read(&bytes).unwrap();
+        assert_eq!(chunk.compression, FrameCompressionKind::RUN_LENGTH_ENCODING);
+        assert_eq!(chunk.background_set, 1);
+        assert_eq!(chunk.common.size as usize, from_white.len());
+        assert_eq!(&bytes[CodecChunkCompressedFrame::SIZE..], from_white);
     }
 
-    let mut chunk = CodecChunkCompressedFrame::new(runlen_buf.len() as u16);
-    chunk.compression = FrameCompressionKind::RUN_LENGTH_ENCODING;
-    chunk.background_set = 0;
+    #[test]
+    fn check_runlength_keeps_black_background() {
+        // Half and half, starting with black
+        let mut frame = BitGrid::new(16, 1);
+        frame.as_mut_bytes()[1] = 0xFF;
 
-    let mut buf = vec![];
-    let mut cursor = io::Cursor::new(&mut buf);
+        assert_eq!(encode_runs(&frame, false), [8, 8]);
+        assert_eq!(encode_runs(&frame, true), [0, 8, 8]);
 
-    cursor.write_all(bytemuck::bytes_of(&chunk)).unwrap();
-    cursor.write_all(&runlen_buf).unwrap();
+        let bytes = compress_runlength(&frame).unwrap();
+        let chunk = CodecChunkCompressedFrame::read(&bytes).unwrap();
+        assert_eq!(chunk.compression, FrameCompressionKind::RUN_LENGTH_ENCODING);
+        assert_eq!(chunk.background_set, 0);
+        assert_eq!(&bytes[CodecChunkCompressedFrame::SIZE..], [8, 8]);
+    }
 
-    buf
+    #[test]
+    fn check_frame_stats() {
+        let mut encoder = VideoEncoder::new();
+        assert!(encoder.frame_stats().is_empty());
+
+        // Solid black packs down to almost nothing, but noise is cheaper stored as-is
+        let black = BitGrid::new(32, 32);
+        let mut noise = BitGrid::new(32, 32);
+        for i in 0..32 * 32 {
+            noise.set(i % 32, i / 32, (i * 7 / 3) % 2 == 1);
+        }
+        encoder.push(black);
+        encoder.push(noise);
+
+        let bytes = encoder.encode_to_vec().unwrap();
+        let stats = encoder.frame_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].id, 1);
+        assert_eq!(stats[0].chosen, FrameCompressionKind::RUN_LENGTH_ENCODING);
+        assert_eq!(stats[1].id, 2);
+        assert_eq!(stats[1].chosen, FrameCompressionKind::UNCOMPRESSED);
+
+        for stat in stats {
+            assert_eq!(
+                stat.chosen_bytes(),
+                stat.uncompressed_bytes.min(stat.rle_bytes)
+            );
+        }
+        let chosen: usize = stats.iter().map(FrameStats::chosen_bytes).sum();
+        assert_eq!(CodecHeader::SIZE + chosen, bytes.len());
+    }
+
+    #[test]
+    fn check_chunk_size_is_payload() {
+        let mut encoder = VideoEncoder::new();
+        encoder.push(BitGrid::new(24, 8));
+        encoder.push(make_mostly_white(24, 8));
+        encoder.push_gray(PackedGrid2::new(24, 8));
+        let bytes = encoder.encode_to_vec().unwrap();
+
+        // Hop from chunk to chunk using nothing but their headers
+        let mut offset = CodecHeader::SIZE;
+        let mut n_chunks = 0;
+        while offset < bytes.len() {
+            let chunk = CodecChunkCompressedFrame::read(&bytes[offset..]).unwrap();
+            assert_eq!(
+                chunk.chunk_len(),
+                CodecChunkCompressedFrame::SIZE + chunk.common.size as usize
+            );
+            assert_eq!(
+                chunk.chunk_len(),
+                encoder.frame_stats()[n_chunks].chosen_bytes()
+            );
+
+            offset += chunk.chunk_len();
+            n_chunks += 1;
+        }
+
+        assert_eq!(offset, bytes.len());
+        assert_eq!(n_chunks, 3);
+    }
+
+    #[test]
+    fn check_oversized_payload() {
+        assert_eq!(payload_size(u16::MAX as usize), Some(u16::MAX));
+        assert_eq!(payload_size(u16::MAX as usize + 1), None);
+    }
+
+    #[test]
+    fn check_skips_kinds_that_dont_fit() {
+        // Far too big to store as-is, but it's one long run of black
+        let mut encoder = VideoEncoder::new();
+        encoder.push(BitGrid::new(1024, 600));
+        encoder.push_gray(PackedGrid2::new(1024, 600));
+        let bytes = encoder.encode_to_vec().unwrap();
+
+        let stats = encoder.frame_stats();
+        assert_eq!(stats[0].uncompressed_bytes, usize::MAX);
+        assert_eq!(stats[0].chosen, FrameCompressionKind::RUN_LENGTH_ENCODING);
+        assert_eq!(stats[1].uncompressed_bytes, usize::MAX);
+        assert_eq!(
+            stats[1].chosen,
+            FrameCompressionKind::RUN_LENGTH_ENCODING_2BPP
+        );
+        assert_eq!(
+            CodecHeader::SIZE + stats[0].chosen_bytes() + stats[1].chosen_bytes(),
+            bytes.len()
+        );
+    }
+
+    #[test]
+    fn check_nothing_fits() {
+        // A checkerboard is too big as-is and even bigger as runs
+        let mut frame = BitGrid::new(1024, 600);
+        for y in 0..600 {
+            for x in 0..1024 {
+                frame.set(x, y, (x + y) % 2 == 0);
+            }
+        }
+
+        let mut encoder = VideoEncoder::new();
+        encoder.push(frame);
+        let mut buf = vec![];
+        let err = encoder.encode_to(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn check_runlength_2bpp() {
+        // 3 white, then 2 dark gray, then 70 black
+        let mut frame = PackedGrid2::new(25, 3);
+        for x in 0..3 {
+            frame.set(x, 0, 3);
+        }
+        frame.set(3, 0, 1);
+        frame.set(4, 0, 1);
+
+        assert_eq!(
+            encode_runs_2bpp(&frame),
+            [0b11_000010, 0b01_000001, 0b00_111111, 0b00_000101]
+        );
+
+        let bytes = compress_runlength_2bpp(&frame).unwrap();
+        let chunk = CodecChunkCompressedFrame::read(&bytes).unwrap();
+        assert_eq!(
+            chunk.compression,
+            FrameCompressionKind::RUN_LENGTH_ENCODING_2BPP
+        );
+        assert_eq!(chunk.common.size, 4);
+    }
+
+    #[test]
+    fn check_runlength_splits_long_runs() {
+        // 600 black pixels, then 255 white, then 1 black
+        let mut frame = BitGrid::new(856, 1);
+        for x in 600..855 {
+            frame.set(x, 0, true);
+        }
+
+        assert_eq!(encode_runs(&frame, false), [255, 0, 255, 0, 90, 255, 1]);
+    }
 }
@@ -0,0 +1,179 @@
+//! 2-bit grayscale frames, for softer edges than 1-bit frames can manage
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// The brightness of each of the 4 levels, from black to white
+pub const LUMA_LEVELS: [u8; 4] = [0x00, 0x55, 0xAA, 0xFF];
+
+/// A 2D grid of 2-bit gray levels, packed 4 to a byte
+///
+/// Like [`BitGrid`](simulations::BitGrid), each row starts on a new byte, and the first pixel of each byte is in its
+/// least significant bits. Levels go from `0` (black) to `3` (white), see [`LUMA_LEVELS`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct PackedGrid2 {
+    buf: Vec<u8>,
+    width: u16,
+    height: u16,
+}
+
+impl Debug for PackedGrid2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PackedGrid2")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl PackedGrid2 {
+    /// Creates a new grid where every pixel is level `0`
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            buf: vec![0; Self::byte_len_for(width, height)],
+            width,
+            height,
+        }
+    }
+
+    /// Quantizes an 8-bit grayscale image to the nearest of the 4 levels
+    ///
+    /// `luma` is one byte per pixel in row-major order, and must be exactly `width * height` long.
+    #[track_caller]
+    pub fn from_luma(width: u16, height: u16, luma: &[u8]) -> Self {
+        assert_eq!(
+            luma.len(),
+            width as usize * height as usize,
+            "Expected a {width}x{height} image"
+        );
+
+        let mut grid = Self::new(width, height);
+        for (i, &px) in luma.iter().enumerate() {
+            // Round to the nearest level, which are 85 apart
+            let level = (px as u16 + 42) / 85;
+            grid.set(
+                (i % width as usize) as u16,
+                (i / width as usize) as u16,
+                level as u8,
+            );
+        }
+
+        grid
+    }
+
+    /// The number of bytes needed to store a `width` x `height` grid
+    pub const fn byte_len_for(width: u16, height: u16) -> usize {
+        (width as usize).div_ceil(4) * height as usize
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn dims(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// The level of the pixel at `(x, y)`, from `0` to `3`
+    #[track_caller]
+    pub fn get(&self, x: u16, y: u16) -> u8 {
+        let (idx, shift) = self.idx(x, y);
+        (self.buf[idx] >> shift) & 0b11
+    }
+
+    /// Sets the pixel at `(x, y)` to `level`, returning its previous level
+    ///
+    /// Only the low 2 bits of `level` are used.
+    #[track_caller]
+    pub fn set(&mut self, x: u16, y: u16, level: u8) -> u8 {
+        let (idx, shift) = self.idx(x, y);
+        let old = (self.buf[idx] >> shift) & 0b11;
+
+        self.buf[idx] &= !(0b11 << shift);
+        self.buf[idx] |= (level & 0b11) << shift;
+
+        old
+    }
+
+    /// The brightness of the pixel at `(x, y)`, as one of [`LUMA_LEVELS`]
+    #[track_caller]
+    pub fn luma(&self, x: u16, y: u16) -> u8 {
+        LUMA_LEVELS[self.get(x, y) as usize]
+    }
+
+    /// Every pixel's level in row-major order, from the top left
+    pub fn levels(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| self.get(x, y)))
+    }
+
+    pub fn clear(&mut self) {
+        self.buf.fill(0);
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+        &mut self.buf
+    }
+
+    #[track_caller]
+    fn idx(&self, x: u16, y: u16) -> (usize, u8) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside of a {}x{} PackedGrid2",
+            self.width,
+            self.height
+        );
+
+        let stride = (self.width as usize).div_ceil(4);
+        let idx = x as usize / 4 + y as usize * stride;
+        (idx, 2 * (x % 4) as u8)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_set_get() {
+        let mut grid = PackedGrid2::new(5, 2);
+        assert_eq!(grid.as_bytes().len(), 4);
+
+        assert_eq!(grid.set(0, 0, 3), 0);
+        assert_eq!(grid.set(1, 0, 1), 0);
+        assert_eq!(grid.set(4, 0, 2), 0);
+        assert_eq!(grid.set(3, 1, 2), 0);
+        assert_eq!(grid.set(3, 1, 1), 2);
+
+        assert_eq!(grid.as_bytes(), [0b00_00_01_11, 0b10, 0b01_00_00_00, 0]);
+        assert_eq!(
+            grid.levels().collect::<Vec<_>>(),
+            [3, 1, 0, 0, 2, 0, 0, 0, 1, 0]
+        );
+        assert_eq!(grid.luma(0, 0), 0xFF);
+        assert_eq!(grid.luma(4, 0), 0xAA);
+    }
+
+    #[test]
+    fn check_from_luma_rounds_to_nearest() {
+        let luma = [0x00, 0x2A, 0x2B, 0x7F, 0x80, 0xD4, 0xD5, 0xFF];
+        let grid = PackedGrid2::from_luma(8, 1, &luma);
+
+        assert_eq!(grid.levels().collect::<Vec<_>>(), [0, 0, 1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of a 5x2 PackedGrid2")]
+    fn check_out_of_bounds() {
+        let grid = PackedGrid2::new(5, 2);
+        grid.get(5, 0);
+    }
+}
@@ -0,0 +1,87 @@
+//! Loading source frames for the [`VideoEncoder`](crate::VideoEncoder)
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek};
+use std::path::Path;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, GrayImage, ImageError, ImageFormat, ImageResult};
+use simulations::BitGrid;
+
+/// Decodes every frame of an animated GIF, in order, as grayscale images
+///
+/// Frames are composited onto the full canvas, so each one is a complete picture even if the GIF only stores what
+/// changed.
+pub fn gif_frames(reader: impl BufRead + Seek) -> ImageResult<Vec<GrayImage>> {
+    collect_frames(GifDecoder::new(reader)?)
+}
+
+/// Decodes every frame of an animated WebP, in order, as grayscale images
+pub fn webp_frames(reader: impl BufRead + Seek) -> ImageResult<Vec<GrayImage>> {
+    collect_frames(WebPDecoder::new(reader)?)
+}
+
+/// Decodes every frame of the animated GIF or WebP at `path`, picking the format from the file extension
+pub fn animation_frames(path: impl AsRef<Path>) -> ImageResult<Vec<GrayImage>> {
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path).map_err(ImageError::IoError)?);
+
+    match ImageFormat::from_path(path)? {
+        ImageFormat::WebP => webp_frames(reader),
+        // Let the GIF decoder complain about anything else
+        _ => gif_frames(reader),
+    }
+}
+
+fn collect_frames<'a>(decoder: impl AnimationDecoder<'a>) -> ImageResult<Vec<GrayImage>> {
+    decoder
+        .into_frames()
+        .map(|frame| Ok(DynamicImage::ImageRgba8(frame?.into_buffer()).to_luma8()))
+        .collect()
+}
+
+/// How [`quantize_luma()`] turns grayscale pixels into set and unset cells
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Quantize {
+    /// Pixels brighter than this are set
+    Threshold(u8),
+
+    /// Ordered dithering with a 4x4 Bayer matrix, so mid grays come out as a pattern instead of a solid block
+    Bayer4x4,
+}
+
+impl Default for Quantize {
+    fn default() -> Self {
+        Self::Threshold(0x80)
+    }
+}
+
+/// Thresholds a grayscale image into a [`BitGrid`], where bright pixels are set
+pub fn bitgrid_from_luma(img: &GrayImage) -> BitGrid {
+    quantize_luma(img, Quantize::default())
+}
+
+/// Turns a grayscale image into a [`BitGrid`], where bright pixels are set
+pub fn quantize_luma(img: &GrayImage, quantize: Quantize) -> BitGrid {
+    #[rustfmt::skip]
+    const BAYER_4X4: [[u8; 4]; 4] = [
+        [ 0,  8,  2, 10],
+        [12,  4, 14,  6],
+        [ 3, 11,  1,  9],
+        [15,  7, 13,  5],
+    ];
+
+    let mut bitmap = BitGrid::new(img.width() as usize, img.height() as usize);
+    for (x, y, px) in img.enumerate_pixels() {
+        let threshold = match quantize {
+            Quantize::Threshold(threshold) => threshold,
+            // Spread the thresholds evenly through 0..=255, centered in each step
+            Quantize::Bayer4x4 => BAYER_4X4[y as usize % 4][x as usize % 4] * 16 + 8,
+        };
+        let is_white = px.0[0] > threshold;
+        bitmap.set(x as _, y as _, is_white);
+    }
+
+    bitmap
+}
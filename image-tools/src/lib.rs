@@ -3,11 +3,16 @@
 // If this is too strict, `#[allow(unsafe_code)]` is a local workaround.
 #![deny(unsafe_code)]
 
+extern crate alloc;
+
 // Note: Encoding DOES require "std"
 #[cfg(feature = "encoder")]
 pub mod encoder;
 #[cfg(feature = "encoder")]
-pub use encoder::VideoEncoder;
+pub use encoder::{StreamingEncoder, VideoEncoder};
+
+#[cfg(feature = "encoder")]
+pub mod input;
 
 #[cfg(feature = "encoder")]
 pub fn encode(frames: impl IntoIterator<Item = simulations::BitGrid>) -> std::io::Result<Vec<u8>> {
@@ -19,6 +24,36 @@ pub fn encode(frames: impl IntoIterator<Item = simulations::BitGrid>) -> std::io
     encoder.encode_to_vec()
 }
 
+/// Encodes grayscale frames, quantizing each one with `quantize` first
+///
+/// Frames are pulled from `frames` one at a time and written as they arrive, see [`encode_luma_to()`].
+#[cfg(feature = "encoder")]
+pub fn encode_luma(
+    frames: impl IntoIterator<Item = image::GrayImage>,
+    quantize: input::Quantize,
+) -> std::io::Result<Vec<u8>> {
+    let w = encode_luma_to(frames, quantize, std::io::Cursor::new(vec![]))?;
+    Ok(w.into_inner())
+}
+
+/// Encodes grayscale frames into `w`, quantizing each one with `quantize` first
+///
+/// Each frame is quantized and written before the next one is pulled from `frames`, so long videos never need to
+/// fit in memory. This goes through [`StreamingEncoder`], so there's no frame index.
+#[cfg(feature = "encoder")]
+pub fn encode_luma_to<W: std::io::Write + std::io::Seek>(
+    frames: impl IntoIterator<Item = image::GrayImage>,
+    quantize: input::Quantize,
+    w: W,
+) -> std::io::Result<W> {
+    let mut encoder = StreamingEncoder::new(w)?;
+    for frame in frames {
+        encoder.push(input::quantize_luma(&frame, quantize))?;
+    }
+
+    encoder.finish()
+}
+
 // Note: Decoding DOES NOT require "std"
 #[cfg(feature = "decoder")]
 pub mod decoder;
@@ -26,3 +61,8 @@ pub mod decoder;
 pub use decoder::VideoDecoder;
 
 pub mod codec;
+
+pub mod gray;
+pub use gray::PackedGrid2;
+
+pub mod rle;
@@ -1,11 +1,11 @@
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use image::imageops;
 use indicatif::*;
 use rayon::prelude::*;
 use regex::Regex;
-use simulations::BitGrid;
 
 use image_tools::*;
 
@@ -24,23 +24,36 @@ use Cmd::*;
 
 #[derive(Parser, Clone, Debug)]
 struct Compress {
-    /// Path to folder with frames as numbed image files (e.g. "bad_apple_1234.png")
+    /// Path to folder with frames as numbered image files (e.g. "bad_apple_1234.png").
+    /// Any image format works, as long as the extension says which.
     #[arg(value_name = "DIR", default_value = "frames")]
     frames_dir: PathBuf,
 
+    /// Read frames from an animated GIF (or animated WebP) instead of DIR
+    #[arg(long, value_name = "FILE")]
+    input_gif: Option<PathBuf>,
+
+    /// Where to write the encoded video. Use "-" for stdout.
+    ///
+    /// Progress is always reported on stderr, so stdout only ever has the video on it.
     #[arg(short, long = "output", default_value = "out.bin")]
     output: PathBuf,
 
     /// Resize the video from the input dimensions.
     /// If only one of --width/--height are provided, aspect ratio is preserved.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "sizes")]
     width: Option<u32>,
 
     /// Resize the video from the input dimensions.
     /// If only one of --width/--height are provided, aspect ratio is preserved.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "sizes")]
     height: Option<u32>,
 
+    /// Encode a video at each of these sizes (like "128x64"), from a single load of the frames.
+    /// Each one is named after OUTPUT with the size added, like "out_128x64.bin".
+    #[arg(long = "size", value_name = "WxH")]
+    sizes: Vec<OutputSize>,
+
     /// Discard the first N frames, and continue taking frames after
     #[arg(long)]
     skip: Option<usize>,
@@ -52,6 +65,61 @@ struct Compress {
     /// Drop frames to reduce framerates "1" keeps every frame, "2" keeps every other, "3" keeps every 3rd, etc
     #[arg(long, default_value = "1")]
     frame_rate_div: usize,
+
+    /// The frame rate the input was made at, for --target-fps
+    #[arg(long, value_name = "FPS", value_parser = parse_fps, requires = "target_fps")]
+    source_fps: Option<f64>,
+
+    /// Resample to this frame rate by repeating or dropping frames, whichever is nearest in time.
+    /// Unlike --frame-rate-div, this works for any pair of rates (like 24 to 30).
+    #[arg(long, value_name = "FPS", value_parser = parse_fps, requires = "source_fps", conflicts_with = "frame_rate_div")]
+    target_fps: Option<f64>,
+
+    /// Deflate the whole video after encoding, which helps noisy (e.g. dithered) frames that RLE can't shrink.
+    /// Players need image-tools' "deflate" feature to read the result, so the pico can't play these.
+    #[arg(long)]
+    deflate: bool,
+
+    /// Write a CSV of how big each frame is with each kind of compression, and which one was used.
+    /// With --size, each report is named after PATH with the size added, like the videos are.
+    #[arg(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+}
+
+/// A `WxH` pair from `--size`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct OutputSize {
+    width: u32,
+    height: u32,
+}
+
+impl std::str::FromStr for OutputSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n.trim().parse::<u32>().ok().filter(|&n| n > 0);
+        match s.split_once(['x', 'X']) {
+            Some((w, h)) => match (parse(w), parse(h)) {
+                (Some(width), Some(height)) => Ok(Self { width, height }),
+                _ => Err(format!(
+                    "\"{s}\" should be two non-zero numbers, like \"128x64\""
+                )),
+            },
+            None => Err(format!(
+                "\"{s}\" should look like WIDTHxHEIGHT, like \"128x64\""
+            )),
+        }
+    }
+}
+
+/// Parses a frame rate for `--source-fps` and `--target-fps`
+fn parse_fps(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(fps) if fps.is_finite() && fps > 0.0 => Ok(fps),
+        _ => Err(format!(
+            "\"{s}\" should be a positive number, like \"29.97\""
+        )),
+    }
 }
 
 #[derive(Parser, Clone, Debug)]
@@ -87,7 +155,14 @@ fn do_info(info: &Info) {
                 continue;
             }
         };
-        let decoder = VideoDecoder::new(&bytes);
+        let inflated = match decoder::inflate(&bytes) {
+            Ok(inflated) => inflated,
+            Err(err) => {
+                eprintln!("    Failed to inflate \"{}\": {err}", input.display());
+                continue;
+            }
+        };
+        let decoder = VideoDecoder::new(&inflated);
         let header = decoder.header();
 
         if let Ok(magic) = core::str::from_utf8(&header.magic) {
@@ -110,122 +185,301 @@ fn do_info(info: &Info) {
         let bbytes = BinaryBytes(bytes.len() as _);
         let hbytes = HumanCount(bytes.len() as _);
         println!("    Size:   {bbytes} ({hbytes} bytes)");
+        if inflated.len() != bytes.len() {
+            let bbytes = BinaryBytes(inflated.len() as _);
+            println!("    Deflated from {bbytes}");
+        }
 
         // TODO: Be nice to know what chunk types are used
     }
 }
 
 fn do_compress(opts: &Compress) {
-    let pattern = Regex::new("[a-zA-Z_-]+([0-9]+).png").unwrap();
-    println!("+ Looking for frames in {:?}", opts.frames_dir.display());
-    let mut file_paths = find_files(&opts.frames_dir, pattern);
-    println!("+ Found {} frames", file_paths.len());
+    // Not something clap can express, since it depends on the value of --output
+    if opts.output == Path::new("-") && opts.sizes.len() > 1 {
+        Opts::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "can't write more than one --size to stdout (\"--output -\")",
+            )
+            .exit();
+    }
 
-    if let Some(skip) = opts.skip {
-        println!("+ Skipping first {skip} frames");
-        file_paths.drain(..skip);
-        println!("+ Done (now have {} frames)", file_paths.len());
-        println!();
+    // Catch sizes that can't be encoded before spending any time loading frames. A side left out of --width/--height
+    // comes from the frames' aspect ratio, so it's checked once we know it.
+    let resize = (opts.width.is_some() || opts.height.is_some())
+        .then(|| (opts.width.unwrap_or(1), opts.height.unwrap_or(1)));
+    let requested = opts
+        .sizes
+        .iter()
+        .map(|size| (size.width, size.height))
+        .chain(resize);
+    for (width, height) in requested {
+        check_output_dims(width, height);
     }
 
-    if opts.frame_rate_div != 1 {
-        println!("+ Dropping 1 in {} frames", opts.frame_rate_div);
-        // we could do this better but... meh.
-        file_paths = file_paths
-            .into_iter()
-            .enumerate()
-            .filter_map(|(i, path)| {
-                if i % opts.frame_rate_div == 0 {
-                    Some(path)
-                } else {
-                    None
-                }
+    let full_frames: Vec<image::GrayImage> = if let Some(gif) = &opts.input_gif {
+        eprintln!("+ Loading frames from {:?}", gif.display());
+        let frames = image_tools::input::animation_frames(gif).unwrap();
+        eprintln!("+ Found {} frames", frames.len());
+
+        let frames = select_frames(frames, opts);
+        eprintln!("+ Done");
+        eprintln!();
+
+        frames
+    } else {
+        let pattern = Regex::new(r"[a-zA-Z_-]+([0-9]+)\.[a-zA-Z0-9]+$").unwrap();
+        eprintln!("+ Looking for frames in {:?}", opts.frames_dir.display());
+        let file_paths = find_files(&opts.frames_dir, pattern);
+        eprintln!("+ Found {} frames", file_paths.len());
+
+        let file_paths = select_frames(file_paths, opts);
+
+        eprintln!("+ Loading {} frames", file_paths.len());
+        // Collecting an indexed parallel iterator keeps the input order, so the thread count never changes the output
+        let frames = file_paths
+            .par_iter()
+            .progress()
+            .map(|(_id, path)| {
+                let img = image::open(path).unwrap();
+                img.to_luma8()
             })
             .collect();
-        println!("+ Done (now have {} frames)", file_paths.len());
-        println!();
-    }
-
-    if let Some(take) = opts.take {
-        println!("+ Truncating to {take} frames");
-        let take = file_paths.len().min(take);
-        file_paths.drain(take..);
-        println!("+ Done (now have {} frames)", file_paths.len());
-        println!();
-    }
+        eprintln!("+ Done");
+        eprintln!();
 
-    println!("+ Loading {} frames", file_paths.len());
-    let full_frames: Vec<_> = file_paths
-        .par_iter()
-        .progress()
-        .map(|(_id, path)| {
-            let img = image::open(path).unwrap();
-            img.to_luma8()
-        })
-        .collect();
-    println!("+ Done");
-    println!();
-
-    let (out_width, out_height) = resolve_dimensions(
-        opts.width,
-        opts.height,
-        full_frames[0].width(),
-        full_frames[0].height(),
-    );
+        frames
+    };
 
     // Note: HumanCount doesn't respect format controls, so we `to_string()` and format that.
-    println!(
+    eprintln!(
         "+ OLD Dimensions: {:>5} x {:>5}",
         HumanCount(full_frames[0].width() as u64).to_string(),
         HumanCount(full_frames[0].height() as u64).to_string(),
     );
-    println!(
+
+    if opts.sizes.is_empty() {
+        let (out_width, out_height) = resolve_dimensions(
+            opts.width,
+            opts.height,
+            full_frames[0].width(),
+            full_frames[0].height(),
+        );
+        encode_frames(
+            &full_frames,
+            out_width,
+            out_height,
+            &opts.output,
+            opts.report.as_deref(),
+            opts.deflate,
+        );
+    } else {
+        for size in &opts.sizes {
+            let output = if opts.output == Path::new("-") {
+                opts.output.clone()
+            } else {
+                sized_output_path(&opts.output, size)
+            };
+            let report = opts
+                .report
+                .as_ref()
+                .map(|report| sized_output_path(report, size));
+            encode_frames(
+                &full_frames,
+                size.width,
+                size.height,
+                &output,
+                report.as_deref(),
+                opts.deflate,
+            );
+        }
+    }
+}
+
+/// Resizes every frame to `out_width` x `out_height`, and encodes them into `output` (or stdout, for `-`)
+///
+/// With a `report` path, per-frame compression stats are written there too.
+fn encode_frames(
+    full_frames: &[image::GrayImage],
+    out_width: u32,
+    out_height: u32,
+    output: &Path,
+    report: Option<&Path>,
+    deflate: bool,
+) {
+    eprintln!(
         "+ NEW dimensions: {:>5} x {:>5}",
         HumanCount(out_width as u64).to_string(),
         HumanCount(out_height as u64).to_string(),
     );
+    check_output_dims(out_width, out_height);
 
     // These are our resized, adjusted frames!
-    println!("+ Processing frames");
-    let frames: Vec<_> = (0..full_frames.len())
-        .into_par_iter()
+    eprintln!("+ Processing frames");
+    let frames: Vec<_> = full_frames
+        .par_iter()
         .progress()
-        .map(|i| {
+        .map(|full_frame| {
             let img = imageops::resize(
-                &full_frames[i],
+                full_frame,
                 out_width,
                 out_height,
                 imageops::FilterType::Nearest,
             );
 
-            let mut bitmap = BitGrid::new(img.width() as usize, img.height() as usize);
-            for (x, y, px) in img.enumerate_pixels() {
-                // TODO: Would be nice to dither or something
-                let is_white = px.0[0] > 0x80;
-                bitmap.set(x as _, y as _, is_white);
-            }
-            bitmap
+            image_tools::input::bitgrid_from_luma(&img)
         })
         .collect();
-    println!("+ Done");
-    println!();
+    eprintln!("+ Done");
+    eprintln!();
 
-    println!("+ Encoding");
-    let mut encoder = VideoEncoder::new();
+    eprintln!("+ Encoding");
+    let mut encoder = VideoEncoder::new().with_deflate(deflate);
     for frame in frames {
         encoder.push(frame);
     }
-    let packed_buffer: Vec<u8> = encoder.encode_to_vec().unwrap();
-    println!("+ Encoded as {}.", BinaryBytes(packed_buffer.len() as u64));
+    // Keep the encoded video around, since we print its size and header below
+    let packed_buffer = encoder.encode_to_vec().unwrap();
+    if output == Path::new("-") {
+        io::stdout().lock().write_all(&packed_buffer).unwrap();
+    } else {
+        let mut output = output.to_path_buf();
+        if output.is_dir() {
+            output.push("out.bin");
+        }
+        std::fs::write(&output, &packed_buffer).unwrap();
+        eprintln!("+ Wrote {:?}", output.display());
+    }
+    eprintln!("+ Encoded as {}.", BinaryBytes(packed_buffer.len() as u64));
+
+    if let Some(report) = report {
+        let file = std::fs::File::create(report).unwrap();
+        write_report(&mut io::BufWriter::new(file), encoder.frame_stats()).unwrap();
+        eprintln!("+ Wrote report {:?}", report.display());
+    }
 
+    let packed_buffer = decoder::inflate(&packed_buffer).unwrap();
     let decoder = VideoDecoder::new(&packed_buffer);
-    println!("+ {:#?}", decoder.header());
+    eprintln!("+ {:#?}", decoder.header());
+    eprintln!();
+}
+
+/// Writes one CSV row per frame: `id,uncompressed_bytes,rle_bytes,chosen_kind,chosen_bytes`
+fn write_report(w: &mut impl Write, stats: &[encoder::FrameStats]) -> io::Result<()> {
+    writeln!(
+        w,
+        "id,uncompressed_bytes,rle_bytes,chosen_kind,chosen_bytes"
+    )?;
+    for stat in stats {
+        let kind = match (stat.chosen.is_uncompressed(), stat.chosen.is_2bpp()) {
+            (true, false) => "uncompressed",
+            (false, false) => "rle",
+            (true, true) => "uncompressed_2bpp",
+            (false, true) => "rle_2bpp",
+        };
+        writeln!(
+            w,
+            "{},{},{},{kind},{}",
+            stat.id,
+            stat.uncompressed_bytes,
+            stat.rle_bytes,
+            stat.chosen_bytes()
+        )?;
+    }
+
+    w.flush()
+}
 
-    let mut output = opts.output.clone();
+/// `out.bin` becomes `out_128x64.bin` (and a directory `dir` becomes `dir/out_128x64.bin`)
+fn sized_output_path(output: &Path, size: &OutputSize) -> PathBuf {
+    let mut output = output.to_path_buf();
     if output.is_dir() {
         output.push("out.bin");
     }
-    std::fs::write(&output, &packed_buffer).unwrap();
+
+    let stem = output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "out".into());
+    let file_name = match output.extension() {
+        Some(ext) => format!(
+            "{stem}_{}x{}.{}",
+            size.width,
+            size.height,
+            ext.to_string_lossy()
+        ),
+        None => format!("{stem}_{}x{}", size.width, size.height),
+    };
+    output.set_file_name(file_name);
+
+    output
+}
+
+/// Applies `--skip`, `--frame-rate-div` or `--target-fps`, and `--take` (in that order) to a list of frames
+fn select_frames<T: Clone>(mut frames: Vec<T>, opts: &Compress) -> Vec<T> {
+    if let Some(skip) = opts.skip {
+        eprintln!("+ Skipping first {skip} frames");
+        frames.drain(..skip.min(frames.len()));
+        eprintln!("+ Done (now have {} frames)", frames.len());
+        eprintln!();
+    }
+
+    if opts.frame_rate_div != 1 {
+        eprintln!("+ Dropping 1 in {} frames", opts.frame_rate_div);
+        // we could do this better but... meh.
+        frames = frames
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, frame)| {
+                if i % opts.frame_rate_div == 0 {
+                    Some(frame)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        eprintln!("+ Done (now have {} frames)", frames.len());
+        eprintln!();
+    }
+
+    if let (Some(source_fps), Some(target_fps)) = (opts.source_fps, opts.target_fps) {
+        eprintln!("+ Resampling from {source_fps} fps to {target_fps} fps");
+        frames = resample_indices(frames.len(), source_fps, target_fps)
+            .into_iter()
+            .map(|i| frames[i].clone())
+            .collect();
+        eprintln!("+ Done (now have {} frames)", frames.len());
+        eprintln!();
+    }
+
+    if let Some(take) = opts.take {
+        eprintln!("+ Truncating to {take} frames");
+        let take = frames.len().min(take);
+        frames.drain(take..);
+        eprintln!("+ Done (now have {} frames)", frames.len());
+        eprintln!();
+    }
+
+    frames
+}
+
+/// Which of `n_frames` source frames to show at each frame of the resampled video
+///
+/// Each output frame shows whichever source frame would be on screen halfway through it, so raising the frame rate
+/// repeats frames and lowering it drops them. The video keeps its length: `n_frames` at `source_fps` lasts as long as the result does at `target_fps`,
+/// give or take a frame.
+fn resample_indices(n_frames: usize, source_fps: f64, target_fps: f64) -> Vec<usize> {
+    if n_frames == 0 {
+        return vec![];
+    }
+
+    let n_out = ((n_frames as f64 * target_fps / source_fps).round() as usize).max(1);
+    let step = source_fps / target_fps;
+
+    (0..n_out)
+        .map(|i| (((i as f64 + 0.5) * step) as usize).min(n_frames - 1))
+        .collect()
 }
 
 fn find_files(dir: &Path, pattern: Regex) -> Vec<(usize, PathBuf)> {
@@ -240,7 +494,8 @@ fn find_files(dir: &Path, pattern: Regex) -> Vec<(usize, PathBuf)> {
     for entry in std::fs::read_dir(dir).unwrap() {
         let entry = entry.unwrap();
         let path = entry.path();
-        if path.is_file() {
+        // Skip anything `image` doesn't know how to open, like stray text files
+        if path.is_file() && image::ImageFormat::from_path(&path).is_ok() {
             if let Some(matches) = pattern.captures(&path.as_os_str().to_string_lossy()) {
                 let id = matches.get(1).expect("pattern matched but no capture?");
                 let id: usize = id.as_str().parse().unwrap();
@@ -250,6 +505,7 @@ fn find_files(dir: &Path, pattern: Regex) -> Vec<(usize, PathBuf)> {
         }
     }
 
+    // `read_dir()` order is up to the OS, and ids are unique (see below), so this is the only order that matters
     files.sort_by_key(|(id, _f)| *id);
 
     let mut seen = vec![];
@@ -268,18 +524,26 @@ fn find_files(dir: &Path, pattern: Regex) -> Vec<(usize, PathBuf)> {
         .filter(|(id, seen)| (!*seen) && (*id > 0) && (*id < files.len()))
         .collect();
     if !missing.is_empty() {
-        println!(
+        eprintln!(
             "[WARNING] Missing frames from the range 1..{}:",
             files.len()
         );
         for (id, _) in missing {
-            println!("    Missing frame {id}");
+            eprintln!("    Missing frame {id}");
         }
     }
 
     files
 }
 
+/// Stops with an explanation if the codec can't hold `width` x `height` frames
+#[track_caller]
+fn check_output_dims(width: u32, height: u32) {
+    if let Err(err) = codec::check_frame_dims(width, height) {
+        panic!("Can't encode at that size: {err}");
+    }
+}
+
 fn resolve_dimensions(
     opts_width: Option<u32>,
     opts_height: Option<u32>,
@@ -309,7 +573,46 @@ fn resolve_dimensions(
 
 #[cfg(test)]
 mod t {
-    use crate::resolve_dimensions;
+    use crate::{parse_fps, resample_indices, resolve_dimensions, sized_output_path, OutputSize};
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn check_parse_output_size() {
+        assert_eq!(
+            "128x64".parse(),
+            Ok(OutputSize {
+                width: 128,
+                height: 64
+            })
+        );
+        assert_eq!(
+            "240X135".parse(),
+            Ok(OutputSize {
+                width: 240,
+                height: 135
+            })
+        );
+        assert!("128".parse::<OutputSize>().is_err());
+        assert!("0x64".parse::<OutputSize>().is_err());
+        assert!("axb".parse::<OutputSize>().is_err());
+    }
+
+    #[test]
+    fn check_sized_output_path() {
+        let size = OutputSize {
+            width: 128,
+            height: 64,
+        };
+        assert_eq!(
+            sized_output_path(Path::new("videos/out.bin"), &size),
+            PathBuf::from("videos/out_128x64.bin")
+        );
+        assert_eq!(
+            sized_output_path(Path::new("bad-apple"), &size),
+            PathBuf::from("bad-apple_128x64")
+        );
+    }
 
     #[test]
     fn check_resolve_dimensions() {
@@ -335,4 +638,37 @@ mod t {
             "Failed to resolve (Some(300), None)"
         );
     }
+
+    #[test]
+    fn check_resample_indices() {
+        // Same rate keeps every frame
+        assert_eq!(resample_indices(4, 30.0, 30.0), [0, 1, 2, 3]);
+
+        // 24 -> 30 repeats one frame in every 4
+        assert_eq!(
+            resample_indices(8, 24.0, 30.0),
+            [0, 1, 2, 2, 3, 4, 5, 6, 6, 7]
+        );
+
+        // 30 -> 24 drops one frame in every 5
+        assert_eq!(resample_indices(10, 30.0, 24.0), [0, 1, 3, 4, 5, 6, 8, 9]);
+
+        // Halving keeps every other frame
+        assert_eq!(resample_indices(6, 30.0, 15.0), [1, 3, 5]);
+
+        // Doubling shows every frame twice
+        assert_eq!(resample_indices(3, 15.0, 30.0), [0, 0, 1, 1, 2, 2]);
+
+        assert_eq!(resample_indices(0, 24.0, 30.0), [] as [usize; 0]);
+    }
+
+    #[test]
+    fn check_parse_fps() {
+        assert_eq!(parse_fps("29.97"), Ok(29.97));
+        assert_eq!(parse_fps("30"), Ok(30.0));
+        assert!(parse_fps("0").is_err());
+        assert!(parse_fps("-24").is_err());
+        assert!(parse_fps("inf").is_err());
+        assert!(parse_fps("fast").is_err());
+    }
 }
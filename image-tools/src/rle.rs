@@ -0,0 +1,165 @@
+//! Run-length encoding for a single [`BitGrid`], without any of the video framing
+//!
+//! Runs are single bytes that alternate between unset and set pixels, starting with unset, and read across each row in
+//! turn. Runs longer than 255 are split up with an empty run of the other color in between.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use simulations::{BitGrid, LenMismatch};
+
+/// Run-length encoding straight to and from a [`BitGrid`]
+///
+/// This is the same encoding that video frames use, minus the chunk header.
+///
+/// ```rust
+/// # use image_tools::rle::BitGridRle;
+/// # use simulations::BitGrid;
+/// let mut grid = BitGrid::new(4, 2);
+/// grid.set(1, 0, true);
+/// grid.set(2, 0, true);
+///
+/// let bytes = grid.to_rle_bytes();
+/// assert_eq!(bytes, [1, 2, 5]);
+/// assert_eq!(BitGrid::from_rle_bytes(4, 2, &bytes), Ok(grid));
+/// ```
+pub trait BitGridRle: Sized {
+    /// Counts alternating runs of unset and set pixels
+    fn to_rle_bytes(&self) -> Vec<u8>;
+
+    /// Builds a `width` x `height` grid from runs written by [`to_rle_bytes()`](BitGridRle::to_rle_bytes)
+    ///
+    /// Fails if the runs don't add up to exactly one run per pixel.
+    fn from_rle_bytes(width: usize, height: usize, bytes: &[u8]) -> Result<Self, LenMismatch>;
+}
+
+impl BitGridRle for BitGrid {
+    fn to_rle_bytes(&self) -> Vec<u8> {
+        encode_runs(self, false)
+    }
+
+    fn from_rle_bytes(width: usize, height: usize, bytes: &[u8]) -> Result<Self, LenMismatch> {
+        let mut grid = BitGrid::new(width, height);
+        let n_pixels = expand_runlength(&mut grid, bytes);
+        if n_pixels != width * height {
+            return Err(LenMismatch {
+                expected: width * height,
+                actual: n_pixels,
+            });
+        }
+
+        Ok(grid)
+    }
+}
+
+/// Counts alternating runs of pixels, starting with runs of `first_color`
+pub(crate) fn encode_runs(frame: &BitGrid, first_color: bool) -> Vec<u8> {
+    let mut runlen_buf = vec![];
+
+    for (i, (color, mut len)) in frame.runs().enumerate() {
+        if i == 0 && color != first_color {
+            // An empty run of the first color, so that we're on the right color
+            runlen_buf.push(0);
+        }
+
+        // Break up long streaks to prevent 8-bit overflow.
+        // Each max length run is followed by an empty run of the other color, so we're back to the same color.
+        while len > u8::MAX as u32 {
+            runlen_buf.extend_from_slice(&[u8::MAX, 0]);
+            len -= u8::MAX as u32;
+        }
+        runlen_buf.push(len as u8);
+    }
+
+    runlen_buf
+}
+
+/// Expands runs of pixels, returning how many pixels the runs add up to
+///
+/// Runs that spill past the end of the bitmap are counted, but not drawn.
+pub(crate) fn expand_runlength(bitmap: &mut BitGrid, in_bytes: &[u8]) -> usize {
+    let mut x = 0;
+    let mut y = 0;
+    let mut n_pixels = 0;
+
+    for pair in in_bytes.chunks(2) {
+        let [num_black, num_white] = [pair[0], *pair.get(1).unwrap_or(&0)];
+        n_pixels += num_black as usize + num_white as usize;
+
+        // Skip black pixels
+        for _ in 0..num_black {
+            x += 1;
+            if x >= bitmap.width() {
+                x = 0;
+                y += 1;
+            }
+
+            // skip
+        }
+
+        // Write white pixels
+        for _ in 0..num_white {
+            if y < bitmap.height() {
+                bitmap.set(x, y, true);
+            }
+
+            x += 1;
+            if x >= bitmap.width() {
+                x = 0;
+                y += 1;
+            }
+        }
+    }
+
+    n_pixels
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_rle_round_trip() {
+        let mut grid = BitGrid::new(13, 7);
+        for (x, y) in [(0, 0), (5, 0), (6, 0), (12, 3), (0, 4), (7, 6), (12, 6)] {
+            grid.set(x, y, true);
+        }
+
+        let bytes = grid.to_rle_bytes();
+        assert_eq!(BitGrid::from_rle_bytes(13, 7, &bytes), Ok(grid));
+    }
+
+    #[test]
+    fn check_rle_round_trip_long_runs() {
+        // 600 unset, then 300 set, then the rest unset, so both colors need their runs split
+        let mut grid = BitGrid::new(40, 30);
+        for i in 600..900 {
+            grid.set(i % 40, i / 40, true);
+        }
+
+        let bytes = grid.to_rle_bytes();
+        assert_eq!(bytes, [255, 0, 255, 0, 90, 255, 0, 45, 255, 0, 45]);
+        assert_eq!(BitGrid::from_rle_bytes(40, 30, &bytes), Ok(grid));
+    }
+
+    #[test]
+    fn check_rle_wrong_len() {
+        let grid = BitGrid::new(8, 8);
+        let bytes = grid.to_rle_bytes();
+
+        assert_eq!(
+            BitGrid::from_rle_bytes(8, 4, &bytes),
+            Err(LenMismatch {
+                expected: 32,
+                actual: 64
+            })
+        );
+        assert_eq!(
+            BitGrid::from_rle_bytes(8, 8, &bytes[..0]),
+            Err(LenMismatch {
+                expected: 64,
+                actual: 0
+            })
+        );
+    }
+}
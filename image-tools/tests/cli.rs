@@ -0,0 +1,273 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use image::{GrayImage, Luma};
+use image_tools::codec::CodecHeader;
+use image_tools::VideoDecoder;
+use simulations::BitGrid;
+
+use pretty_assertions::assert_eq;
+
+/// Writes `grids` out like a folder of exported video frames, and returns the folder
+fn write_frames_dir(name: &str, grids: &[BitGrid]) -> String {
+    let frames_dir = format!("./target/test-output/{name}");
+    let _ = std::fs::remove_dir_all(&frames_dir);
+    std::fs::create_dir_all(&frames_dir).unwrap();
+
+    for (i, grid) in grids.iter().enumerate() {
+        let img = GrayImage::from_fn(grid.width() as u32, grid.height() as u32, |x, y| {
+            Luma([if grid.get(x as _, y as _) { 0xFF } else { 0x00 }])
+        });
+        img.save(format!("{frames_dir}/frame_{}.png", i + 1))
+            .unwrap();
+    }
+
+    frames_dir
+}
+
+fn run_image_tools(args: &[&str]) -> Output {
+    run_image_tools_with_env(args, &[])
+}
+
+fn run_image_tools_with_env(args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_image-tools"))
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .expect("Failed to run image-tools");
+    assert!(
+        output.status.success(),
+        "image-tools failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
+
+fn decode_all(bytes: &[u8]) -> Vec<BitGrid> {
+    let mut decoder = VideoDecoder::new_with_strict(bytes, true);
+    let mut frames: Vec<BitGrid> = vec![];
+    while let Some(frame) = decoder.try_next_frame().expect("Failed to decode") {
+        frames.push(frame.bitmap.clone());
+    }
+
+    frames
+}
+
+#[test]
+fn check_compress_to_stdout() {
+    let mut expected = vec![];
+    let mut life = simulations::Life::new(12, 10);
+    life.write_right_glider(2, 2);
+    for _ in 0..4 {
+        expected.push(life.as_bitgrid().clone());
+        life.step();
+    }
+
+    let frames_dir = write_frames_dir("check_compress_to_stdout", &expected);
+    let output = run_image_tools(&["compress", &frames_dir, "--output", "-"]);
+
+    // Nothing but the video should be on stdout
+    assert_eq!(decode_all(&output.stdout), expected);
+}
+
+#[test]
+fn check_compress_is_deterministic() {
+    let mut frames = vec![];
+    let mut life = simulations::Life::new(40, 24);
+    life.fill_glider_field(8);
+    // Enough frames that the thread pool splits them up
+    for _ in 0..24 {
+        frames.push(life.as_bitgrid().clone());
+        life.step();
+    }
+    let frames_dir = write_frames_dir("check_compress_is_deterministic", &frames);
+
+    let outputs: Vec<Vec<u8>> = ["1", "4"]
+        .into_iter()
+        .map(|n_threads| {
+            let output = run_image_tools_with_env(
+                &["compress", &frames_dir, "--output", "-"],
+                &[("RAYON_NUM_THREADS", n_threads)],
+            );
+            output.stdout
+        })
+        .collect();
+
+    assert!(
+        outputs[0] == outputs[1],
+        "Outputs differ between thread counts"
+    );
+    assert_eq!(decode_all(&outputs[0]), frames);
+}
+
+#[test]
+fn check_compress_multiple_sizes() {
+    // A checkerboard of 4x4 squares, so that halving it is still a checkerboard
+    let mut grid = BitGrid::new(16, 8);
+    for y in 0..8 {
+        for x in 0..16 {
+            grid.set(x, y, ((x / 4) + (y / 4)) % 2 == 1);
+        }
+    }
+    let frames_dir = write_frames_dir("check_compress_multiple_sizes", &[grid.clone(), grid]);
+
+    let out_dir = "./target/test-output/check_compress_multiple_sizes_out";
+    let _ = std::fs::remove_dir_all(out_dir);
+    std::fs::create_dir_all(out_dir).unwrap();
+
+    run_image_tools(&[
+        "compress",
+        &frames_dir,
+        "--output",
+        &format!("{out_dir}/video.bin"),
+        "--size",
+        "16x8",
+        "--size",
+        "8x4",
+    ]);
+
+    for (width, height) in [(16, 8), (8, 4)] {
+        let bytes = std::fs::read(format!("{out_dir}/video_{width}x{height}.bin")).unwrap();
+        let header = VideoDecoder::new(&bytes).header();
+        assert_eq!((header.width, header.height), (width, height));
+
+        let frames = decode_all(&bytes);
+        assert_eq!(frames.len(), 2);
+        for frame in &frames {
+            assert_eq!(frame.dims(), (width as i16, height as i16));
+            assert_eq!(frame.count_ones(), (width * height / 2) as i32);
+        }
+    }
+}
+
+#[test]
+fn check_compress_report() {
+    let mut frames = vec![];
+    let mut life = simulations::Life::new(24, 16);
+    life.write_right_glider(2, 2);
+    for _ in 0..3 {
+        frames.push(life.as_bitgrid().clone());
+        life.step();
+    }
+    // Something RLE can't do much with
+    let mut noise = BitGrid::new(24, 16);
+    for i in 0..24 * 16 {
+        noise.set(i % 24, i / 24, (i * 7 / 3) % 2 == 1);
+    }
+    frames.push(noise);
+
+    let frames_dir = write_frames_dir("check_compress_report", &frames);
+    let video = format!("{frames_dir}/video.bin");
+    let report = format!("{frames_dir}/report.csv");
+    run_image_tools(&[
+        "compress",
+        &frames_dir,
+        "--output",
+        &video,
+        "--report",
+        &report,
+    ]);
+
+    let csv = std::fs::read_to_string(&report).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("id,uncompressed_bytes,rle_bytes,chosen_kind,chosen_bytes")
+    );
+
+    let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), frames.len());
+
+    let mut total = 0;
+    for (i, row) in rows.iter().enumerate() {
+        let [id, uncompressed, rle, kind, chosen] = row[..] else {
+            panic!("Row {i} should have 5 columns: {row:?}");
+        };
+        let [id, uncompressed, rle, chosen] =
+            [id, uncompressed, rle, chosen].map(|n| n.parse::<usize>().unwrap());
+
+        assert_eq!(id, i + 1);
+        assert_eq!(chosen, uncompressed.min(rle), "row {i}");
+        let expected_kind = if uncompressed <= rle {
+            "uncompressed"
+        } else {
+            "rle"
+        };
+        assert_eq!(kind, expected_kind, "row {i}");
+
+        total += chosen;
+    }
+    assert_eq!(rows[0][3], "rle");
+    assert_eq!(rows[3][3], "uncompressed");
+
+    // Every chunk plus the header is the whole video
+    let bytes = std::fs::read(&video).unwrap();
+    assert_eq!(total + CodecHeader::SIZE, bytes.len());
+    assert_eq!(decode_all(&bytes), frames);
+}
+
+#[test]
+fn check_compress_rejects_oversized_width() {
+    let frames_dir = write_frames_dir(
+        "check_compress_rejects_oversized_width",
+        &[BitGrid::new(8, 8)],
+    );
+    let output_path = "./target/test-output/check_compress_rejects_oversized_width.bin";
+    let _ = std::fs::remove_file(output_path);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_image-tools"))
+        .args([
+            "compress",
+            &frames_dir,
+            "--width",
+            "70000",
+            "--output",
+            output_path,
+        ])
+        .output()
+        .expect("Failed to run image-tools");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("at most 32767 pixels on each side"),
+        "{stderr}"
+    );
+
+    // It gave up before even looking for frames, let alone writing a video
+    assert!(!stderr.contains("+ Looking for frames"), "{stderr}");
+    assert!(!Path::new(output_path).exists());
+}
+
+#[test]
+fn check_compress_rejects_sizes_to_stdout() {
+    let frames_dir = write_frames_dir(
+        "check_compress_rejects_sizes_to_stdout",
+        &[BitGrid::new(8, 8)],
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_image-tools"))
+        .args([
+            "compress",
+            &frames_dir,
+            "--size",
+            "8x8",
+            "--size",
+            "4x4",
+            "--output",
+            "-",
+        ])
+        .output()
+        .expect("Failed to run image-tools");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    // A usage error, not a panic
+    assert_eq!(output.status.code(), Some(2), "{stderr}");
+    assert!(
+        stderr.contains("can't write more than one --size to stdout"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,193 @@
+use image::codecs::gif::GifEncoder;
+use image::{Frame, GrayImage, Luma, Rgba, RgbaImage};
+use image_tools::codec::CodecHeader;
+use image_tools::input::{self, Quantize};
+use image_tools::VideoDecoder;
+use simulations::BitGrid;
+
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+
+use pretty_assertions::assert_eq;
+
+/// Encodes `grids` as an animated GIF, with set cells as white
+fn make_gif(grids: &[BitGrid]) -> Vec<u8> {
+    let mut bytes = vec![];
+    {
+        let mut encoder = GifEncoder::new(&mut bytes);
+        for grid in grids {
+            let img = RgbaImage::from_fn(grid.width() as u32, grid.height() as u32, |x, y| {
+                if grid.get(x as _, y as _) {
+                    Rgba([0xFF, 0xFF, 0xFF, 0xFF])
+                } else {
+                    Rgba([0x00, 0x00, 0x00, 0xFF])
+                }
+            });
+            encoder.encode_frame(Frame::new(img)).unwrap();
+        }
+    }
+
+    bytes
+}
+
+#[test]
+fn check_gif_frames_to_bitgrids() {
+    let mut life = simulations::Life::new(10, 8);
+    life.write_right_glider(1, 1);
+
+    let mut expected = vec![];
+    for _ in 0..3 {
+        expected.push(life.as_bitgrid().clone());
+        life.step();
+    }
+
+    let gif = make_gif(&expected);
+    let frames = input::gif_frames(std::io::Cursor::new(gif)).expect("Failed to decode gif");
+    assert_eq!(frames.len(), 3);
+
+    let actual: Vec<BitGrid> = frames.iter().map(input::bitgrid_from_luma).collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn check_animation_frames_from_path() {
+    let mut expected = vec![BitGrid::new(4, 4), BitGrid::new(4, 4), BitGrid::new(4, 4)];
+    expected[0].set(0, 0, true);
+    expected[1].set(1, 1, true);
+    expected[2].set(3, 2, true);
+
+    let out_dir = "./target/test-output";
+    std::fs::create_dir_all(out_dir).unwrap();
+    let out_path = format!("{out_dir}/check_animation_frames_from_path.gif");
+    std::fs::write(&out_path, make_gif(&expected)).unwrap();
+
+    let frames = input::animation_frames(&out_path).expect("Failed to decode gif");
+    let actual: Vec<BitGrid> = frames.iter().map(input::bitgrid_from_luma).collect();
+    assert_eq!(actual, expected);
+}
+
+/// A diagonal gradient that slides along by `i` pixels
+fn make_gradient(i: u32) -> GrayImage {
+    GrayImage::from_fn(16, 12, |x, y| Luma([((x + y + i) * 9) as u8]))
+}
+
+/// Writes into a buffer that the test can look at while encoding is still going
+#[derive(Clone, Default)]
+struct SharedWriter(Rc<RefCell<io::Cursor<Vec<u8>>>>);
+
+impl io::Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+impl io::Seek for SharedWriter {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.0.borrow_mut().seek(pos)
+    }
+}
+
+#[test]
+fn check_encode_luma() {
+    for quantize in [Quantize::default(), Quantize::Bayer4x4] {
+        let frames = (0..8).map(make_gradient);
+        let bytes = image_tools::encode_luma(frames, quantize).expect("Failed to encode");
+
+        // Same video as quantizing everything up front
+        let quantized = (0..8).map(|i| input::quantize_luma(&make_gradient(i), quantize));
+        assert_eq!(
+            bytes,
+            image_tools::encode(quantized).expect("Failed to encode"),
+            "{quantize:?}"
+        );
+
+        let mut decoder = VideoDecoder::new(&bytes);
+        for i in 0..8 {
+            let frame = decoder.next_frame().expect("Missing a frame");
+            assert_eq!(
+                frame.bitmap,
+                &input::quantize_luma(&make_gradient(i), quantize),
+                "{quantize:?}, frame {i}"
+            );
+        }
+        assert_eq!(decoder.next_frame(), None);
+    }
+}
+
+#[test]
+fn check_encode_luma_streams() {
+    let w = SharedWriter::default();
+
+    // Each frame is only made once everything before it has been written
+    let mut lens = vec![];
+    let frames = (0..8).map(|i| {
+        let len = w.0.borrow().get_ref().len();
+        if i == 0 {
+            assert_eq!(len, CodecHeader::SIZE, "Header should be written first");
+        } else {
+            assert!(
+                len > lens[i as usize - 1],
+                "Frame {} wasn't written before frame {i} was made",
+                i - 1
+            );
+        }
+        lens.push(len);
+
+        make_gradient(i)
+    });
+    image_tools::encode_luma_to(frames, Quantize::default(), w.clone()).expect("Failed to encode");
+
+    let bytes = w.0.borrow().get_ref().clone();
+    let mut decoder = VideoDecoder::new(&bytes);
+    assert_eq!(decoder.header().n_frames, 8);
+    for i in 0..8 {
+        let frame = decoder.next_frame().expect("Missing a frame");
+        assert_eq!(
+            frame.bitmap,
+            &input::quantize_luma(&make_gradient(i), Quantize::default()),
+            "frame {i}"
+        );
+    }
+    assert_eq!(decoder.next_frame(), None);
+}
+
+#[test]
+fn check_quantize_bayer_mid_gray() {
+    let gray = GrayImage::from_pixel(8, 8, Luma([0x80]));
+
+    // Thresholding makes one solid block
+    assert_eq!(
+        input::quantize_luma(&gray, Quantize::default()).count_ones(),
+        0
+    );
+
+    // Dithering sets half the pixels, evenly spread through every 4x4 tile
+    let dithered = input::quantize_luma(&gray, Quantize::Bayer4x4);
+    assert_eq!(dithered.count_ones(), 32);
+    for ty in 0..2 {
+        for tx in 0..2 {
+            let n_set = (0..4)
+                .flat_map(|y| (0..4).map(move |x| (4 * tx + x, 4 * ty + y)))
+                .filter(|&(x, y)| dithered.get(x, y))
+                .count();
+            assert_eq!(n_set, 8);
+        }
+    }
+
+    // Black and white stay solid
+    let black = GrayImage::from_pixel(8, 8, Luma([0x00]));
+    let white = GrayImage::from_pixel(8, 8, Luma([0xFF]));
+    assert_eq!(
+        input::quantize_luma(&black, Quantize::Bayer4x4).count_ones(),
+        0
+    );
+    assert_eq!(
+        input::quantize_luma(&white, Quantize::Bayer4x4).count_ones(),
+        64
+    );
+}
@@ -1,7 +1,12 @@
 #![allow(clippy::print_literal)]
 
 use image::{imageops, Luma};
-use image_tools::{decoder::Frame, VideoDecoder, VideoEncoder};
+use image_tools::codec::{
+    check_frame_dims, ChunkKind, CodecChunkCommon, CodecChunkCompressedFrame, CodecChunkResize,
+    CodecHeader, FrameCompressionKind, FrameDimsError, MAX_FRAME_SIDE,
+};
+use image_tools::decoder::{DecodeError, Frame};
+use image_tools::{PackedGrid2, VideoDecoder, VideoEncoder};
 use simulations::BitGrid;
 
 use pretty_assertions::assert_eq;
@@ -411,7 +416,7 @@ fn check_one_frame_all_white_200x200() {
         Some(Frame {
             id: 1,
             bitmap: &img,
-            background_set: false,
+            background_set: true,
         })
     );
 
@@ -421,3 +426,784 @@ fn check_one_frame_all_white_200x200() {
     assert_eq!(decoder.next_frame(), None);
     assert_eq!(decoder.next_frame(), None);
 }
+
+#[test]
+fn check_one_frame_mostly_white() {
+    // ## Encode
+    let mut encoder = VideoEncoder::new();
+
+    // Mostly white, with a short black line at the end of every row. Sparse enough that RLE beats uncompressed.
+    let mut img = BitGrid::new(50, 30);
+    img.invert_in_place();
+    for y in 0..30 {
+        for x in 45..50 {
+            img.set(x, y, false);
+        }
+    }
+    save_test_image("mono_frame", "mostly_white_50x30_good", &img);
+
+    encoder.push(img.clone());
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    // ## Decode
+    let mut decoder = VideoDecoder::new_with_strict(&bytes, true);
+
+    let frame = decoder.try_next_frame().unwrap();
+    if let Some(frame) = &frame {
+        save_test_image("mono_frame", "mostly_white_50x30", frame.bitmap);
+    }
+    assert_eq!(
+        frame,
+        Some(Frame {
+            id: 1,
+            bitmap: &img,
+            background_set: true,
+        })
+    );
+    assert_eq!(decoder.try_next_frame(), Ok(None));
+}
+
+#[test]
+fn check_encode_to_path() {
+    // ## Encode
+    let mut encoder = VideoEncoder::new();
+
+    let mut life = simulations::Life::new(12, 10);
+    life.write_right_glider(1, 1);
+    let glider: BitGrid = life.as_bitgrid().clone();
+    encoder.push(glider.clone());
+
+    let out_dir = "./target/test-output";
+    std::fs::create_dir_all(out_dir).unwrap();
+    let out_path = format!("{out_dir}/check_encode_to_path.bin");
+    encoder
+        .encode_to_path(&out_path)
+        .expect("Failed to encode to path");
+
+    // ## Decode
+    let bytes = std::fs::read(&out_path).expect("Failed to read back encoded file");
+    let mut decoder = VideoDecoder::new(&bytes);
+
+    let header = decoder.header();
+    assert_eq!(header.n_frames, 1);
+    assert_eq!(header.width, life.width() as _);
+    assert_eq!(header.height, life.height() as _);
+
+    assert_eq!(
+        decoder.next_frame(),
+        Some(Frame {
+            id: 1,
+            bitmap: &glider,
+            background_set: false,
+        })
+    );
+    assert_eq!(decoder.next_frame(), None);
+}
+
+fn make_glider_video() -> (Vec<u8>, Vec<BitGrid>) {
+    let mut encoder = VideoEncoder::new();
+    let mut life = simulations::Life::new(16, 16);
+    life.write_right_glider(1, 1);
+
+    let mut frames = vec![];
+    for _ in 0..3 {
+        frames.push(life.as_bitgrid().clone());
+        encoder.push(life.as_bitgrid().clone());
+        life.step();
+    }
+
+    (encoder.encode_to_vec().expect("Failed to encode"), frames)
+}
+
+#[test]
+fn check_clean_stream_strict_and_lenient() {
+    let (bytes, frames) = make_glider_video();
+
+    for strict in [false, true] {
+        let mut decoder = VideoDecoder::new_with_strict(&bytes, strict);
+        assert_eq!(decoder.is_strict(), strict);
+
+        for (i, expected) in frames.iter().enumerate() {
+            let frame = decoder
+                .try_next_frame()
+                .expect("Clean stream should not error");
+            assert_eq!(
+                frame,
+                Some(Frame {
+                    id: i + 1,
+                    bitmap: expected,
+                    background_set: false,
+                }),
+                "strict={strict}"
+            );
+        }
+
+        assert_eq!(decoder.try_next_frame(), Ok(None), "strict={strict}");
+        assert_eq!(decoder.try_next_frame(), Ok(None), "strict={strict}");
+    }
+}
+
+#[test]
+fn check_truncated_stream_strict_and_lenient() {
+    let (bytes, frames) = make_glider_video();
+
+    // Cut off the last byte of the final frame, and separately, part of the final chunk header
+    let mid_frame = &bytes[..bytes.len() - 1];
+    let mid_header = {
+        let mut third_chunk = CodecHeader::SIZE;
+        for _ in 0..2 {
+            let chunk = CodecChunkCompressedFrame::read(&bytes[third_chunk..]).unwrap();
+            third_chunk += CodecChunkCompressedFrame::SIZE + chunk.common.size as usize;
+        }
+        &bytes[..third_chunk + 2]
+    };
+
+    for truncated in [mid_frame, mid_header] {
+        // Lenient decoders quietly stop after the last complete frame
+        let mut lenient = VideoDecoder::new_with_strict(truncated, false);
+        assert_eq!(
+            lenient.next_frame().map(|f| f.bitmap.clone()),
+            Some(frames[0].clone())
+        );
+        assert_eq!(
+            lenient.next_frame().map(|f| f.bitmap.clone()),
+            Some(frames[1].clone())
+        );
+        assert_eq!(lenient.try_next_frame(), Ok(None));
+        assert!(lenient.is_finished());
+
+        // Strict decoders say so, and then are finished
+        let mut strict = VideoDecoder::new_with_strict(truncated, true);
+        assert_eq!(
+            strict.next_frame().map(|f| f.bitmap.clone()),
+            Some(frames[0].clone())
+        );
+        assert_eq!(
+            strict.next_frame().map(|f| f.bitmap.clone()),
+            Some(frames[1].clone())
+        );
+        assert_eq!(strict.try_next_frame(), Err(DecodeError::Truncated));
+        assert_eq!(strict.try_next_frame(), Ok(None));
+        assert!(strict.is_finished());
+    }
+}
+
+#[test]
+fn check_index_points_at_chunks() {
+    let mut life = simulations::Life::new(24, 20);
+    life.write_right_glider(1, 1);
+    life.write_left_glider(15, 3);
+
+    let mut frames = vec![];
+    let mut encoder = VideoEncoder::new().with_index(true);
+    for _ in 0..6 {
+        frames.push(life.as_bitgrid().clone());
+        encoder.push(life.as_bitgrid().clone());
+        life.step();
+    }
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    // Walk the chunks by hand to find where each one starts
+    let mut chunk_starts = vec![];
+    let mut offset = CodecHeader::SIZE;
+    for _ in 0..frames.len() {
+        chunk_starts.push(offset);
+        let chunk = CodecChunkCompressedFrame::read(&bytes[offset..]).unwrap();
+        offset += CodecChunkCompressedFrame::SIZE + chunk.common.size as usize;
+    }
+
+    let mut decoder = VideoDecoder::new(&bytes);
+    let header = decoder.header();
+    assert!(decoder.has_index());
+    assert_eq!(header.index_offset as usize, offset);
+    assert_eq!(bytes.len(), offset + frames.len() * size_of::<u32>());
+
+    let indexed: Vec<usize> = (0..frames.len())
+        .map(|i| decoder.indexed_offset(i).expect("Missing index entry"))
+        .collect();
+    assert_eq!(indexed, chunk_starts);
+    assert_eq!(decoder.indexed_offset(frames.len()), None);
+
+    // Seeking goes straight to the right frame, in any order
+    for i in [4, 0, 5, 2] {
+        let frame = decoder.seek_to_frame(i).expect("Failed to seek");
+        assert_eq!(frame.id, i + 1);
+        assert_eq!(frame.bitmap, &frames[i]);
+    }
+
+    // Decoding in order stops at the index instead of reading it as a chunk
+    decoder.reset();
+    for expected in &frames {
+        assert_eq!(
+            decoder.next_frame().map(|f| f.bitmap.clone()),
+            Some(expected.clone())
+        );
+    }
+    assert_eq!(decoder.next_frame(), None);
+    assert!(decoder.is_finished());
+}
+
+#[test]
+fn check_seek_without_index() {
+    let (bytes, frames) = make_glider_video();
+
+    let mut decoder = VideoDecoder::new(&bytes);
+    assert!(!decoder.has_index());
+    assert_eq!(decoder.indexed_offset(0), None);
+
+    let frame = decoder.seek_to_frame(2).expect("Failed to seek");
+    assert_eq!(frame.id, 3);
+    assert_eq!(frame.bitmap, &frames[2]);
+
+    let frame = decoder.seek_to_frame(0).expect("Failed to seek");
+    assert_eq!(frame.id, 1);
+    assert_eq!(frame.bitmap, &frames[0]);
+
+    assert_eq!(decoder.seek_to_frame(frames.len()), None);
+}
+
+#[test]
+fn check_current_frame() {
+    let (bytes, frames) = make_glider_video();
+
+    let mut decoder = VideoDecoder::new(&bytes);
+    assert_eq!(decoder.current_frame(), None);
+
+    for (i, expected) in frames.iter().enumerate() {
+        let frame = decoder.next_frame().map(|frame| frame.id);
+        assert_eq!(frame, Some(i + 1));
+
+        // Re-reading doesn't advance
+        for _ in 0..2 {
+            assert_eq!(
+                decoder.current_frame(),
+                Some(Frame {
+                    id: i + 1,
+                    bitmap: expected,
+                    background_set: false,
+                })
+            );
+        }
+    }
+
+    // The last frame sticks around after the stream ends
+    assert_eq!(decoder.next_frame(), None);
+    assert_eq!(decoder.current_frame().map(|frame| frame.id), Some(3));
+    assert_eq!(decoder.current_frame().unwrap().bitmap, &frames[2]);
+
+    decoder.reset();
+    assert_eq!(decoder.current_frame(), None);
+
+    decoder.seek_to_frame(1).expect("Failed to seek");
+    assert_eq!(decoder.current_frame().map(|frame| frame.id), Some(2));
+    assert_eq!(decoder.current_frame().unwrap().bitmap, &frames[1]);
+}
+
+/// Round trips one 2-bit frame, checking the encoder picked `expected_kind`
+fn check_gray_round_trip(gray: PackedGrid2, expected_kind: FrameCompressionKind) {
+    let (width, height) = gray.dims();
+
+    let mut encoder = VideoEncoder::new();
+    encoder.push_gray(gray.clone());
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+    assert_eq!(encoder.frame_stats()[0].chosen, expected_kind);
+
+    let mut decoder = VideoDecoder::new(&bytes);
+    assert_eq!(decoder.current_gray(), None);
+
+    let frame = decoder.next_frame().expect("Failed to decode frame");
+    assert_eq!(frame.id, 1);
+    assert_eq!(decoder.pixel_count_mismatches(), 0);
+    assert_eq!(decoder.current_gray(), Some(&gray));
+
+    // 1-bit players see the brighter half of the levels
+    let mut expected = BitGrid::new(width as usize, height as usize);
+    for y in 0..height {
+        for x in 0..width {
+            expected.set(x as i16, y as i16, gray.get(x, y) >= 2);
+        }
+    }
+    assert_eq!(decoder.current_frame().unwrap().bitmap, &expected);
+
+    assert_eq!(decoder.next_frame(), None);
+}
+
+#[test]
+fn check_one_gray_frame_uncompressed() {
+    // Every pixel is a different level from its neighbors, so runs don't help
+    let mut gray = PackedGrid2::new(7, 5);
+    for y in 0..5 {
+        for x in 0..7 {
+            gray.set(x, y, ((x + 2 * y) % 4) as u8);
+        }
+    }
+
+    check_gray_round_trip(gray, FrameCompressionKind::UNCOMPRESSED_2BPP);
+}
+
+#[test]
+fn check_one_gray_frame_runlength() {
+    // A wide gradient, with long runs of each level
+    let mut gray = PackedGrid2::new(40, 6);
+    for y in 0..6 {
+        for x in 0..40 {
+            gray.set(x, y, (x / 10) as u8);
+        }
+    }
+
+    check_gray_round_trip(gray, FrameCompressionKind::RUN_LENGTH_ENCODING_2BPP);
+}
+
+#[test]
+fn check_gray_after_mono_frame() {
+    let mut gray = PackedGrid2::new(8, 8);
+    gray.set(3, 3, 3);
+    let mono = BitGrid::new(8, 8);
+
+    let mut encoder = VideoEncoder::new();
+    encoder.push_gray(gray.clone());
+    encoder.push(mono.clone());
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    let mut decoder = VideoDecoder::new(&bytes);
+    decoder.next_frame().expect("Failed to decode frame");
+    assert_eq!(decoder.current_gray(), Some(&gray));
+
+    // 1-bit frames don't have levels to report
+    decoder.next_frame().expect("Failed to decode frame");
+    assert_eq!(decoder.current_gray(), None);
+    assert_eq!(decoder.current_frame().unwrap().bitmap, &mono);
+}
+
+/// Builds a stream by hand, with one RLE frame made of `runs`
+fn make_rle_stream(width: u32, height: u32, runs: &[u8]) -> Vec<u8> {
+    let header = CodecHeader::new(1, width, height);
+    let mut chunk = CodecChunkCompressedFrame::new(runs.len() as u16);
+    chunk.compression = FrameCompressionKind::RUN_LENGTH_ENCODING;
+
+    let mut bytes = vec![];
+    bytes.extend_from_slice(bytemuck::bytes_of(&header));
+    bytes.extend_from_slice(bytemuck::bytes_of(&chunk));
+    bytes.extend_from_slice(runs);
+
+    bytes
+}
+
+#[test]
+fn check_pixel_count_matches() {
+    let bytes = make_rle_stream(8, 2, &[5, 3, 4, 4]);
+
+    let mut decoder = VideoDecoder::new_with_strict(&bytes, true);
+    let frame = decoder.try_next_frame().unwrap().unwrap();
+    assert_eq!(frame.bitmap.count_ones(), 7);
+    assert_eq!(decoder.pixel_count_mismatches(), 0);
+}
+
+#[test]
+fn check_pixel_count_underfilled() {
+    // 8x2 is 16 pixels, but these runs only cover 8
+    let bytes = make_rle_stream(8, 2, &[5, 3]);
+
+    let mut strict = VideoDecoder::new_with_strict(&bytes, true);
+    assert_eq!(
+        strict.try_next_frame(),
+        Err(DecodeError::PixelCountMismatch {
+            frame: 1,
+            expected: 16,
+            actual: 8,
+        })
+    );
+    assert_eq!(strict.pixel_count_mismatches(), 1);
+    assert_eq!(strict.try_next_frame(), Ok(None));
+
+    // Lenient decoders still hand out the frame, but keep count
+    let mut lenient = VideoDecoder::new_with_strict(&bytes, false);
+    let frame = lenient.try_next_frame().unwrap().expect("Missing frame");
+    assert_eq!(frame.bitmap.count_ones(), 3);
+    assert_eq!(lenient.pixel_count_mismatches(), 1);
+}
+
+#[test]
+fn check_pixel_count_overfilled() {
+    // Like a 128 pixel wide video with a header that says 85
+    let bytes = make_rle_stream(8, 2, &[8, 8, 8]);
+
+    let mut strict = VideoDecoder::new_with_strict(&bytes, true);
+    assert_eq!(
+        strict.try_next_frame(),
+        Err(DecodeError::PixelCountMismatch {
+            frame: 1,
+            expected: 16,
+            actual: 24,
+        })
+    );
+}
+
+#[test]
+fn check_encode_twice_is_identical() {
+    let mut encoder = VideoEncoder::new().with_index(true);
+    for n in 0..4 {
+        let mut frame = BitGrid::new(16, 8);
+        frame.set(n, n, true);
+        encoder.push(frame);
+    }
+
+    let first = encoder.encode_to_vec().expect("Failed to encode");
+    let second = encoder.encode_to_vec().expect("Failed to encode");
+    assert_eq!(first, second);
+    assert_eq!(encoder.frame_count(), 4);
+
+    let mut decoder = VideoDecoder::new(&second);
+    assert_eq!(decoder.header().n_frames, 4);
+    for _ in 0..4 {
+        assert!(decoder.next_frame().is_some());
+    }
+}
+
+#[test]
+fn check_clear() {
+    let mut encoder = VideoEncoder::new();
+    encoder.push(BitGrid::new(16, 8));
+    encoder.clear();
+    assert_eq!(encoder.frame_count(), 0);
+
+    // The next frame picks the dimensions again
+    encoder.push(BitGrid::new(8, 4));
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    let decoder = VideoDecoder::new(&bytes);
+    let header = decoder.header();
+    assert_eq!(header.n_frames, 1);
+    assert_eq!((header.width, header.height), (8, 4));
+}
+
+/// Encodes `frames`, then puts a chunk of an unknown kind before each frame chunk and at the very end
+fn make_stream_with_unknown_chunks(frames: &[BitGrid]) -> Vec<u8> {
+    let mut encoder = VideoEncoder::new();
+    for frame in frames {
+        encoder.push(frame.clone());
+    }
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    let mut unknown: CodecChunkCommon = bytemuck::Zeroable::zeroed();
+    unknown.kind = ChunkKind(0x7777);
+    unknown.size = 5;
+    let unknown_chunk = [bytemuck::bytes_of(&unknown), &[0xFF; 5]].concat();
+
+    let mut out = bytes[..CodecHeader::SIZE].to_vec();
+    let mut offset = CodecHeader::SIZE;
+    while offset < bytes.len() {
+        let chunk = CodecChunkCompressedFrame::read(&bytes[offset..]).unwrap();
+        out.extend_from_slice(&unknown_chunk);
+        out.extend_from_slice(&bytes[offset..offset + chunk.chunk_len()]);
+        offset += chunk.chunk_len();
+    }
+    out.extend_from_slice(&unknown_chunk);
+
+    out
+}
+
+#[test]
+fn check_unknown_chunks_are_skipped() {
+    let frames: Vec<BitGrid> = (0..3)
+        .map(|n| {
+            let mut frame = BitGrid::new(16, 8);
+            frame.set(n, n, true);
+            frame
+        })
+        .collect();
+    let bytes = make_stream_with_unknown_chunks(&frames);
+
+    let mut decoder = VideoDecoder::new_with_strict(&bytes, true);
+    for (i, expected) in frames.iter().enumerate() {
+        let frame = decoder.try_next_frame().unwrap().expect("Missing frame");
+        assert_eq!(frame.id, i + 1);
+        assert_eq!(frame.bitmap, expected);
+    }
+    assert_eq!(decoder.try_next_frame(), Ok(None));
+    assert!(decoder.is_finished());
+
+    // Seeking without an index walks past them too
+    let frame = decoder.seek_to_frame(2).expect("Missing frame");
+    assert_eq!(frame.bitmap, &frames[2]);
+}
+
+#[test]
+fn check_truncated_unknown_chunk() {
+    let frames = [BitGrid::new(16, 8)];
+    let bytes = make_stream_with_unknown_chunks(&frames);
+
+    // Cut off partway through the trailing unknown chunk
+    let bytes = &bytes[..bytes.len() - 2];
+    let mut strict = VideoDecoder::new_with_strict(bytes, true);
+    assert!(strict.try_next_frame().unwrap().is_some());
+    assert_eq!(strict.try_next_frame(), Err(DecodeError::Truncated));
+    assert_eq!(strict.try_next_frame(), Ok(None));
+}
+
+/// Dithered gradients, which come out as fine repeating patterns that RLE can't do anything with
+#[cfg(feature = "deflate")]
+fn make_dithered_frames(n_frames: u32) -> Vec<BitGrid> {
+    use image_tools::input::{quantize_luma, Quantize};
+
+    (0..n_frames)
+        .map(|n| {
+            let img = image::GrayImage::from_fn(64, 48, |x, y| Luma([(4 * x + y + 8 * n) as u8]));
+            quantize_luma(&img, Quantize::Bayer4x4)
+        })
+        .collect()
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn check_deflate_round_trip() {
+    let frames = make_dithered_frames(8);
+
+    let encode = |deflate: bool| {
+        let mut encoder = VideoEncoder::new().with_index(true).with_deflate(deflate);
+        for frame in &frames {
+            encoder.push(frame.clone());
+        }
+        encoder.encode_to_vec().expect("Failed to encode")
+    };
+    let plain = encode(false);
+    let deflated = encode(true);
+    assert!(
+        deflated.len() < plain.len(),
+        "plain: {} bytes, deflated: {} bytes",
+        plain.len(),
+        deflated.len()
+    );
+
+    // Plain streams are passed through untouched
+    assert_eq!(*image_tools::decoder::inflate(&plain).unwrap(), plain[..]);
+    assert!(!CodecHeader::read(&plain).unwrap().is_deflated());
+    assert!(CodecHeader::read(&deflated).unwrap().is_deflated());
+
+    let inflated = image_tools::decoder::inflate(&deflated).unwrap();
+    let mut from_plain = VideoDecoder::new_with_strict(&plain, true);
+    let mut from_deflated = VideoDecoder::new_with_strict(&inflated, true);
+    assert_eq!(
+        from_deflated.header().index_offset,
+        from_plain.header().index_offset
+    );
+    for expected in &frames {
+        let a = from_plain.try_next_frame().unwrap().expect("Missing frame");
+        assert_eq!(a.bitmap, expected);
+        let b = from_deflated
+            .try_next_frame()
+            .unwrap()
+            .expect("Missing frame");
+        assert_eq!(b.bitmap, expected);
+    }
+    assert_eq!(from_deflated.try_next_frame(), Ok(None));
+
+    // The index points into the inflated stream
+    let frame = from_deflated.seek_to_frame(5).expect("Missing frame");
+    assert_eq!(frame.bitmap, &frames[5]);
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn check_decode_deflated() {
+    let frames = make_dithered_frames(8);
+    let mut encoder = VideoEncoder::new().with_index(true).with_deflate(true);
+    for frame in &frames {
+        encoder.push(frame.clone());
+    }
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+    assert!(CodecHeader::read(&bytes).unwrap().is_deflated());
+
+    // No need to inflate it first
+    let mut decoder = VideoDecoder::new_with_strict(&bytes, true);
+    assert!(!decoder.header().is_deflated());
+    for expected in &frames {
+        let frame = decoder.try_next_frame().unwrap().expect("Missing frame");
+        assert_eq!(frame.bitmap, expected);
+    }
+    assert_eq!(decoder.try_next_frame(), Ok(None));
+
+    let frame = decoder.seek_to_frame(5).expect("Missing frame");
+    assert_eq!(frame.bitmap, &frames[5]);
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn check_decode_bad_deflate() {
+    let mut encoder = VideoEncoder::new().with_deflate(true);
+    encoder.push(BitGrid::new(16, 8));
+    let mut bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    // Deflate blocks of type 3 don't exist
+    bytes.truncate(CodecHeader::SIZE);
+    bytes.extend([0xFF; 8]);
+
+    let mut strict = VideoDecoder::new_with_strict(&bytes, true);
+    assert_eq!(strict.try_next_frame(), Err(DecodeError::Deflated));
+    assert_eq!(strict.try_next_frame(), Ok(None));
+
+    // It comes back after a reset, like any other error at the start of the stream
+    strict.reset();
+    assert_eq!(strict.try_next_frame(), Err(DecodeError::Deflated));
+
+    let mut lenient = VideoDecoder::new(&bytes);
+    assert_eq!(lenient.try_next_frame(), Ok(None));
+}
+
+/// A frame with a diagonal line, so frames of different sizes look different
+fn make_diagonal(width: usize, height: usize) -> BitGrid {
+    let mut frame = BitGrid::new(width, height);
+    for i in 0..width.min(height) as i16 {
+        frame.set(i, i, true);
+    }
+    frame
+}
+
+#[test]
+fn check_resize_between_frames() {
+    let frames = [
+        make_diagonal(16, 8),
+        make_diagonal(8, 4),
+        make_diagonal(8, 4),
+        make_diagonal(24, 3),
+    ];
+
+    for index in [false, true] {
+        let mut encoder = VideoEncoder::new().with_index(index);
+        for frame in &frames {
+            encoder.push(frame.clone());
+        }
+        let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+        let mut decoder = VideoDecoder::new_with_strict(&bytes, true);
+        let header = decoder.header();
+        assert_eq!((header.width, header.height), (16, 8));
+        assert_ne!(header.flags & CodecHeader::FLAG_RESIZES, 0);
+
+        // One RESIZE chunk for each change of size, right before the first frame of the new size
+        let resize = CodecChunkResize::read(
+            &bytes[CodecHeader::SIZE + encoder.frame_stats()[0].chosen_bytes()..],
+        )
+        .unwrap();
+        assert_eq!(resize, CodecChunkResize::new(8, 4));
+        assert_eq!(resize.common.chunk_len(), CodecChunkResize::SIZE);
+
+        for (i, expected) in frames.iter().enumerate() {
+            let frame = decoder.try_next_frame().unwrap().expect("Missing frame");
+            assert_eq!(frame.id, i + 1);
+            assert_eq!(frame.bitmap, expected, "index={index}, frame #{}", i + 1);
+        }
+        assert_eq!(decoder.try_next_frame(), Ok(None));
+        assert_eq!(decoder.pixel_count_mismatches(), 0);
+
+        // Seeking picks up every resize on the way, even with an index
+        let frame = decoder.seek_to_frame(2).expect("Missing frame");
+        assert_eq!(frame.bitmap, &frames[2], "index={index}");
+        let frame = decoder.seek_to_frame(0).expect("Missing frame");
+        assert_eq!(frame.bitmap, &frames[0], "index={index}");
+
+        decoder.reset();
+        assert_eq!(decoder.next_frame().unwrap().bitmap, &frames[0]);
+    }
+}
+
+#[test]
+fn check_same_size_has_no_resizes() {
+    let mut encoder = VideoEncoder::new();
+    encoder.push(make_diagonal(16, 8));
+    encoder.push(make_diagonal(16, 8));
+    let bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    let header = CodecHeader::read(&bytes).unwrap();
+    assert_eq!(header.flags, 0);
+    let chosen: usize = encoder.frame_stats().iter().map(|s| s.chosen_bytes()).sum();
+    assert_eq!(CodecHeader::SIZE + chosen, bytes.len());
+}
+
+#[test]
+fn check_frame_dims_limits() {
+    assert_eq!(check_frame_dims(128, 64), Ok(()));
+    assert_eq!(check_frame_dims(MAX_FRAME_SIDE, 15), Ok(()));
+    assert_eq!(MAX_FRAME_SIDE, 32767);
+
+    // Fits in the header, but not in a BitGrid
+    assert_eq!(
+        check_frame_dims(40_000, 1),
+        Err(FrameDimsError::TooBig {
+            width: 40_000,
+            height: 1
+        })
+    );
+    assert_eq!(
+        check_frame_dims(u16::MAX as u32 + 1, 1),
+        Err(FrameDimsError::TooBig {
+            width: 65536,
+            height: 1
+        })
+    );
+
+    // Each side fits, but the frame doesn't: 256 bytes per row, and 256 rows is one byte too many
+    assert_eq!(check_frame_dims(2048, 255), Ok(()));
+    assert_eq!(
+        check_frame_dims(2048, 256),
+        Err(FrameDimsError::TooManyBytes {
+            width: 2048,
+            height: 256,
+            bytes: 65536
+        })
+    );
+}
+
+#[test]
+fn check_resize_too_big() {
+    let mut encoder = VideoEncoder::new();
+    encoder.push(make_diagonal(8, 8));
+    encoder.push(make_diagonal(8, 8));
+    let mut bytes = encoder.encode_to_vec().expect("Failed to encode");
+
+    // Too wide for a BitGrid, but it fits in the chunk's u16 just fine
+    let first_len = encoder.frame_stats()[0].chosen_bytes();
+    let resize = CodecChunkResize::new(40_000, 1);
+    let at = CodecHeader::SIZE + first_len;
+    bytes.splice(at..at, bytemuck::bytes_of(&resize).iter().copied());
+
+    let mut strict = VideoDecoder::new_with_strict(&bytes, true);
+    assert!(strict.try_next_frame().unwrap().is_some());
+    assert_eq!(
+        strict.try_next_frame(),
+        Err(DecodeError::TooBig {
+            width: 40_000,
+            height: 1
+        })
+    );
+    assert_eq!(strict.try_next_frame(), Ok(None));
+
+    let mut lenient = VideoDecoder::new(&bytes);
+    assert!(lenient.next_frame().is_some());
+    assert_eq!(lenient.try_next_frame(), Ok(None));
+}
+
+#[test]
+fn check_header_too_big() {
+    let mut bytes = image_tools::encode([make_diagonal(8, 8)]).expect("Failed to encode");
+    let mut header = CodecHeader::read(&bytes).unwrap();
+    header.width = 40_000;
+    bytes[..CodecHeader::SIZE].copy_from_slice(bytemuck::bytes_of(&header));
+
+    let mut strict = VideoDecoder::new_with_strict(&bytes, true);
+    assert_eq!(
+        strict.try_next_frame(),
+        Err(DecodeError::TooBig {
+            width: 40_000,
+            height: 8
+        })
+    );
+    assert_eq!(strict.try_next_frame(), Ok(None));
+
+    let mut lenient = VideoDecoder::new(&bytes);
+    assert_eq!(lenient.try_next_frame(), Ok(None));
+
+    // Starting over runs into the same problem
+    strict.reset();
+    assert!(strict.try_next_frame().is_err());
+}
@@ -19,7 +19,7 @@ use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::*;
 use embedded_graphics::text::renderer::CharacterStyle;
 use embedded_graphics::text::Text;
-use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::digital::OutputPin;
 use fugit::*;
 use hal::prelude::*;
 use rp_pico::hal;
@@ -32,6 +32,7 @@ use rand::{rngs::SmallRng, SeedableRng};
 
 use pico::peripherals::*;
 use pico::scene::*;
+use pico::{ButtonMap, Buttons, FrameLimiter};
 
 #[global_allocator]
 static HEAP: Heap = Heap::empty();
@@ -97,10 +98,12 @@ fn main() {
     let cs = pins.gpio9.into_push_pull_output();
     let mut rst = pins.gpio12.into_push_pull_output();
 
-    // Note: key1 on the board
-    let mut btn_a = pins.gpio15.into_pull_up_input();
-    // Note: key0 on the board
-    let mut btn_b = pins.gpio17.into_pull_up_input();
+    // Scenes only see A and B. See `ButtonMap` for which key is which.
+    let mut buttons = Buttons::new(
+        pins.gpio17.into_pull_up_input(),
+        pins.gpio15.into_pull_up_input(),
+        ButtonMap::default(),
+    );
 
     // LED on the board - we use this mostly for proof-of-life
     let mut led = pins.led.into_push_pull_output();
@@ -123,7 +126,7 @@ fn main() {
     // See if we left any interesting panic info in RAM
     if let Some(mut msg) = panic_persist::get_panic_message_utf8() {
         display.clear_unset();
-        display.driver().inverse_on();
+        display.set_inverted(true);
 
         if msg.starts_with("panicked at src/") {
             msg = &msg[16..];
@@ -136,27 +139,18 @@ fn main() {
         }
 
         // Panic info
-        {
-            // Chunk the text to fit our display.
-            let mut y = 6;
-            pico::chunk_lines(msg, 24, |line: &str| {
-                let text = Text::new(
-                    line,
-                    Point::new(5, y),
-                    MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
-                );
-                let _ = text.draw(&mut display);
-                y += 8;
-            });
-        }
+        display.draw_wrapped_text(
+            msg,
+            Point::new(5, 0),
+            MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
+        );
 
         display.flush();
 
         // Wait for input and then reset to USB mode
         {
             for i in 0.. {
-                let a = btn_a.is_low().unwrap_or(true);
-                let b = btn_b.is_low().unwrap_or(true);
+                let (a, b) = buttons.read();
                 if a || b {
                     break;
                 }
@@ -202,8 +196,7 @@ fn main() {
 
         // Wait until a button press
         for i in 0.. {
-            let a: bool = btn_a.is_low().unwrap();
-            let b: bool = btn_b.is_low().unwrap();
+            let (a, b) = buttons.read();
 
             // If EITHER A or B are pressed, move on to the next screen
             if a || b {
@@ -232,13 +225,16 @@ fn main() {
         btn_a: false,
         btn_b: false,
         time: timer.get_counter().ticks(),
+        frame: 0,
     };
 
     let mut scene = pico::scene::BadAppleScene::new(ctx.time);
 
+    // Bad Apple!! runs at 30 fps
+    let mut limiter = FrameLimiter::new(30);
+
     loop {
-        ctx.btn_a = btn_a.is_low().unwrap();
-        ctx.btn_b = btn_b.is_low().unwrap();
+        buttons.update(&mut ctx);
         ctx.time = timer.get_counter().ticks();
 
         if ctx.btn_a && ctx.btn_b {
@@ -246,9 +242,10 @@ fn main() {
         }
 
         if scene.update(&mut ctx, &mut display) {
-            display.flush();
+            display.flush_dma();
         }
+        ctx.frame += 1;
 
-        delay.delay_us(1_000);
+        limiter.sleep_until_next(&mut delay, timer.get_counter().ticks());
     }
 }
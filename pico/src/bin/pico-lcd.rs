@@ -17,7 +17,7 @@ use rp_pico::hal;
 
 use rand::{rngs::SmallRng, SeedableRng};
 use simulations::Elementry;
-use simulations::Life;
+use simulations::{Life, ReseedPolicy};
 
 use pico::peripherals::*;
 use pico::*;
@@ -148,9 +148,14 @@ fn main() -> ! {
 
     let mut display = st7789::ST7789Display::new(spi_dev, dc, &mut rst, &mut delay);
 
+    // Whatever's on the ADC sets the backlight, see `auto_brightness()`
+    let adc = hal::Adc::new(pac.ADC, &mut pac.RESETS);
+    let mut light_sensor = Tmp36Sensor::new(adc, pins.gpio26);
+    let mut frame: u64 = 0;
+
     // Generate more of these at: https://coolors.co/313715-d16014
     // Pick two and hit Space to generate random pairs until you like what you see
-    let palettes = [
+    let palettes: [Palette; 4] = [
         // [Background, Foreground]
         [AOC_BLUE, AOC_GOLD],
         [Rgb565::from_rgb888(0x1B081D), Rgb565::from_rgb888(0x830C8F)],
@@ -168,6 +173,14 @@ fn main() -> ! {
         let mut sim = Life::new(st7789::WIDTH as usize / 4, st7789::HEIGHT as usize / 4);
         sim.clear_random(&mut rng);
 
+        // Start over once the board freezes, or after about 5 minutes at 10 fps
+        let reseed = ReseedPolicy::new(3_000);
+
+        // Each cell is a 4x4 big pixel
+        let mapper = PaletteMapper::new(4);
+
+        let mut limiter = FrameLimiter::new(10);
+        let mut step_timer = StepTimer::new();
         loop {
             led.set_high().unwrap();
 
@@ -191,28 +204,25 @@ fn main() -> ! {
                 }
             }
 
-            let n_updated = sim.step();
+            let n_updated = step_timer.time(|| timer.get_counter().ticks(), || sim.step());
+            if reseed.should_reseed(n_updated, sim.generation()) {
+                sim.clear_random(&mut rng);
+                palette += 1;
+                palette %= palettes.len();
+            }
             if n_updated != 0 {
-                for y in 0..sim.height() {
-                    for x in 0..sim.width() {
-                        let is_alive = sim.get(x, y);
-
-                        // Write a 4x4 big pixel
-                        for dy in 0..4 {
-                            let yy = 4 * (y as u16) + dy;
-                            for dx in 0..4 {
-                                let xx = 4 * (x as u16) + dx;
-                                image[(xx, yy)] = palettes[palette][is_alive as usize];
-                            }
-                        }
-                    }
-                }
+                mapper.map_1bit(sim.as_bitgrid(), &palettes[palette], &mut image);
             }
 
+            if frame.is_multiple_of(brightness::AUTO_BRIGHTNESS_FRAMES) {
+                display.write_brightness(auto_brightness(light_sensor.read_raw()));
+            }
+            frame += 1;
+
             display.present(&image);
 
             led.set_low().unwrap();
-            delay.delay_ms(100);
+            limiter.sleep_until_next(&mut delay, timer.get_counter().ticks());
         }
     } else {
         // let rule = 30;
@@ -230,6 +240,7 @@ fn main() -> ! {
         display.define_vertical_scroll_areas(0, 0);
 
         // Run our simulation sideways so we can use "vertical" scrolling to move it smoothly.
+        let mut limiter = FrameLimiter::new(100);
         loop {
             // Each column is a snapshot of the simulation
             for x in (0..st7789::WIDTH).step_by(scale as usize).rev() {
@@ -260,19 +271,24 @@ fn main() -> ! {
                 // Write the updated state into our buffer
                 for y in 0..sim.width() {
                     let is_alive = sim.get(y);
-                    let y = y as u16;
                     // Write a scale by scale big pixel
-                    for dx in 0..scale {
-                        for dy in 0..scale {
-                            image[((x + dx), scale * y + dy)] =
-                                palettes[palette][is_alive as usize];
-                        }
-                    }
+                    let big_pixel = Rect::new(
+                        x as i32,
+                        scale as i32 * y as i32,
+                        scale as u32,
+                        scale as u32,
+                    );
+                    image.fill_rect(big_pixel, palettes[palette][is_alive as usize]);
+                }
+
+                if frame.is_multiple_of(brightness::AUTO_BRIGHTNESS_FRAMES) {
+                    display.write_brightness(auto_brightness(light_sensor.read_raw()));
                 }
+                frame += 1;
 
                 // Scroll and update the display with our new image
                 display.present(&image);
-                delay.delay_ms(10);
+                limiter.sleep_until_next(&mut delay, timer.get_counter().ticks());
             }
         }
     }
@@ -15,10 +15,7 @@ use embedded_alloc::LlffHeap as Heap;
 use embedded_graphics::mono_font::{ascii, MonoTextStyle};
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
-use embedded_graphics::primitives::*;
-use embedded_graphics::text::renderer::CharacterStyle;
-use embedded_graphics::text::Text;
-use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::digital::OutputPin;
 use fugit::*;
 use hal::prelude::*;
 use rp_pico::hal;
@@ -31,6 +28,7 @@ use rand::{rngs::SmallRng, SeedableRng};
 
 use pico::peripherals::*;
 use pico::scene::*;
+use pico::{ButtonMap, Buttons, FrameLimiter};
 
 #[global_allocator]
 static HEAP: Heap = Heap::empty();
@@ -105,10 +103,12 @@ fn main() {
     let cs = pins.gpio9.into_push_pull_output();
     let mut rst = pins.gpio12.into_push_pull_output();
 
-    // Note: key1 on the board
-    let mut btn_a = pins.gpio15.into_pull_up_input();
-    // Note: key0 on the board
-    let mut btn_b = pins.gpio17.into_pull_up_input();
+    // Scenes only see A and B. See `ButtonMap` for which key is which.
+    let mut buttons = Buttons::new(
+        pins.gpio17.into_pull_up_input(),
+        pins.gpio15.into_pull_up_input(),
+        ButtonMap::default(),
+    );
 
     // LED on the board - we use this mostly for proof-of-life
     let mut led = pins.led.into_push_pull_output();
@@ -131,34 +131,25 @@ fn main() {
     // See if we left any interesting panic info in RAM
     if let Some(mut msg) = panic_persist::get_panic_message_utf8() {
         display.clear_unset();
-        display.driver().inverse_on();
+        display.set_inverted(true);
 
         if msg.starts_with("panicked at src/") {
             msg = &msg[16..];
         }
 
         // Panic info
-        {
-            // Chunk the text to fit our display.
-            let mut y = 6;
-            pico::chunk_lines(msg, 24, |line: &str| {
-                let text = Text::new(
-                    line,
-                    Point::new(5, y),
-                    MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
-                );
-                let _ = text.draw(&mut display);
-                y += 8;
-            });
-        }
+        display.draw_wrapped_text(
+            msg,
+            Point::new(5, 0),
+            MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
+        );
 
         display.flush();
 
         // Wait for input and then reset to USB mode
         {
             for i in 0.. {
-                let a = btn_a.is_low().unwrap_or(true);
-                let b = btn_b.is_low().unwrap_or(true);
+                let (a, b) = buttons.read();
                 if a || b {
                     break;
                 }
@@ -177,56 +168,12 @@ fn main() {
 
     // Show a pretty title screen, and wait on it until user input
     {
-        let width = display.width() as i32;
-        let height = display.height() as i32;
-
-        // Fullscreen white-border
-        let style_white_border = PrimitiveStyleBuilder::new()
-            .stroke_width(1)
-            .stroke_color(BinaryColor::On)
-            .build();
-        let r = 4;
-        let screen_border = RoundedRectangle::with_equal_corners(
-            Rectangle::new(Point::new(0, 0), Size::new(width as u32, height as u32)),
-            Size::new(r, r),
-        );
-        let _ = screen_border.draw_styled(&style_white_border, &mut display);
-
-        // Draw "bitflipper", stylized
-        {
-            let mut bit_style = MonoTextStyle::new(&ascii::FONT_6X13_BOLD, BinaryColor::Off);
-            bit_style.set_background_color(Some(BinaryColor::On));
-            let bit = Text::new("BIT", Point::new(38, 19), bit_style);
-            let _ = bit.draw(&mut display);
-
-            let flipper_style = MonoTextStyle::new(&ascii::FONT_6X13_ITALIC, BinaryColor::On);
-            let flipper = Text::new("flipper", Point::new(58, 22), flipper_style);
-            let _ = flipper.draw(&mut display);
-        }
-
-        // Draw some lines below everything
-        for i in 0..3 {
-            let xs = width * 1 / 8 + 3 * (3 - i);
-            let xe = width * 7 / 8 - 3 * (3 - i);
-            let y = 3 * height / 4 + (i - 1) * 5 - 16;
-            let line0 = Line::new(Point::new(xs, y), Point::new(xe, y));
-            let _ = line0.draw_styled(&style_white_border, &mut display);
-        }
-
-        // Instruct the obediant
-        let anykey = Text::new(
-            "PRESS ANY KEY",
-            Point::new(32, 3 * height / 4 + 4),
-            MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
-        );
-        let _ = anykey.draw(&mut display);
-
+        pico::draw_title(&mut display);
         display.flush();
 
         // Wait until a button press
         for i in 0.. {
-            let a: bool = btn_a.is_low().unwrap();
-            let b: bool = btn_b.is_low().unwrap();
+            let (a, b) = buttons.read();
 
             // If EITHER A or B are pressed, move on to the next screen
             if a || b {
@@ -264,7 +211,6 @@ fn main() {
     );
 
     let adc = hal::Adc::new(pac.ADC, &mut pac.RESETS);
-    #[allow(unused)]
     let mut temp_sensor = Tmp36Sensor::new(adc, pins.gpio26);
     #[allow(unused)]
     let mut battery = INA219::new(i2c);
@@ -282,32 +228,33 @@ fn main() {
         btn_a: false,
         btn_b: false,
         time: timer.get_counter().ticks(),
+        frame: 0,
     };
 
-    let frame_time_target_usec = 1_000_000 / 60 /*fps*/;
+    let mut limiter = FrameLimiter::new(60);
+
+    // Scenes that stop changing dim the panel after this long, about 10 seconds
+    const FRAMES_UNTIL_SLEEP: u32 = 10 * 60;
+    let mut contrast = 128;
 
-    let mut load_main_scene = true;
-    let mut next_update_due = 0_u32;
-    'big: loop {
-        if load_main_scene {
-            // let mut scene = pico::scene::DebugTextScene::new();
-            let mut scene = pico::scene::BitflipperScene::new(&display);
+    // Runs a scene until A and B are held together, then moves on to the next one
+    macro_rules! run_scene {
+        ($scene:expr) => {{
+            let mut scene = SleepWhenIdle::new($scene, FRAMES_UNTIL_SLEEP);
+            scene.set_contrast(&mut display, contrast);
             let load_time = timer.get_counter().ticks();
 
             loop {
-                delay.delay_us(next_update_due.saturating_sub(timer.get_counter_low()));
-                next_update_due = timer.get_counter_low() + frame_time_target_usec;
+                limiter.sleep_until_next(&mut delay, timer.get_counter().ticks());
 
                 if (ctx.time > load_time + 500_000/*usec*/) && ctx.btn_a && ctx.btn_b {
-                    load_main_scene = !load_main_scene;
-                    continue 'big;
+                    break;
                 }
 
-                ctx.btn_a = btn_a.is_low().unwrap();
-                ctx.btn_b = btn_b.is_low().unwrap();
+                buttons.update(&mut ctx);
                 ctx.time = timer.get_counter().ticks();
 
-                // scene.text = alloc::format!(
+                // scene.scene.text = alloc::format!(
                 //     indoc::indoc!(
                 //         r#"time:  {time}
                 //         Battery:
@@ -327,28 +274,30 @@ fn main() {
                 //     tmp36 = temp_sensor.read_fahrenheit(),
                 // );
 
-                if scene.update(&mut ctx, &mut display) {
-                    display.flush();
-                }
-            }
-        } else {
-            let mut scene = pico::scene::CreditsScene::new();
-            let load_time = timer.get_counter().ticks();
-
-            loop {
-                ctx.btn_a = btn_a.is_low().unwrap();
-                ctx.btn_b = btn_b.is_low().unwrap();
-                ctx.time = timer.get_counter().ticks();
-
-                if (ctx.time > load_time + 500_000/*usec*/) && ctx.btn_a && ctx.btn_b {
-                    load_main_scene = !load_main_scene;
-                    continue 'big;
+                if ctx.every(pico::brightness::AUTO_BRIGHTNESS_FRAMES) {
+                    contrast = pico::auto_brightness(temp_sensor.read_raw());
+                    scene.set_contrast(&mut display, contrast);
                 }
 
                 if scene.update(&mut ctx, &mut display) {
-                    display.flush();
+                    display.flush_dma();
                 }
+                ctx.frame += 1;
             }
+        }};
+    }
+
+    const N_SCENES: usize = 4;
+    let mut scene_index = 0;
+    loop {
+        match scene_index {
+            // 0 => run_scene!(pico::scene::DebugTextScene::new()),
+            0 => run_scene!(pico::scene::BitflipperScene::new(&display)),
+            1 => run_scene!(pico::scene::ConwayScene::new(ctx.rng, &display)),
+            2 => run_scene!(pico::scene::GunScene::new(&display)),
+            _ => run_scene!(pico::scene::CreditsScene::new()),
         }
+
+        scene_index = (scene_index + 1) % N_SCENES;
     }
 }
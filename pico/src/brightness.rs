@@ -0,0 +1,54 @@
+//! Follows the room's brightness with the panel's, from an analog sensor on the ADC
+
+/// The dimmest [`auto_brightness()`] goes, so the panel never turns off entirely in the dark
+pub const MIN_AUTO_BRIGHTNESS: u8 = 16;
+
+/// The biggest reading the rp2040's 12-bit ADC can give
+pub const MAX_ADC_READING: u16 = (1 << 12) - 1;
+
+/// How often the main loops take a new reading, in frames
+///
+/// Rooms don't change brightness very quickly, and readings are noisy, so there's no point doing this every frame.
+pub const AUTO_BRIGHTNESS_FRAMES: u64 = 60;
+
+/// Maps a raw ADC reading to a panel brightness, for `set_contrast()` on the OLED or `write_brightness()` on the LCD
+///
+/// Brighter surroundings (bigger readings) make a brighter panel. Readings go straight across from
+/// [`MIN_AUTO_BRIGHTNESS`] at `0` to `255` at [`MAX_ADC_READING`], and anything past that is clamped.
+pub fn auto_brightness(sensor_reading: u16) -> u8 {
+    let reading = sensor_reading.min(MAX_ADC_READING) as u32;
+    let range = (u8::MAX - MIN_AUTO_BRIGHTNESS) as u32;
+
+    MIN_AUTO_BRIGHTNESS + (reading * range / MAX_ADC_READING as u32) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case::dark(0, MIN_AUTO_BRIGHTNESS)]
+    #[case::mid(MAX_ADC_READING / 2, 135)]
+    #[case::bright(MAX_ADC_READING, 255)]
+    #[case::clamped(MAX_ADC_READING + 1, 255)]
+    #[case::clamped_max(u16::MAX, 255)]
+    fn check_auto_brightness(#[case] reading: u16, #[case] expected: u8) {
+        assert_eq!(auto_brightness(reading), expected);
+    }
+
+    #[test]
+    fn check_auto_brightness_never_dims_when_brighter() {
+        let mut last = 0;
+        for reading in 0..=MAX_ADC_READING {
+            let brightness = auto_brightness(reading);
+            assert!(
+                brightness >= last,
+                "{reading} came out dimmer than {}",
+                reading - 1
+            );
+            last = brightness;
+        }
+    }
+}
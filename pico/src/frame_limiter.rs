@@ -0,0 +1,102 @@
+//! Keeps main loops running at a steady frame rate, no matter how long each frame takes to draw
+
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayUs as DelayUs;
+
+/// Sleeps between frames to hit a target frame rate
+///
+/// Times are in microseconds, like the rp2040's `Timer` counter. Frames that run long aren't made up for later: the
+/// next frame is just due one frame time after the late one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameLimiter {
+    frame_time_usec: u64,
+
+    /// When the next frame should start, or `None` before the first frame
+    next_frame_due: Option<u64>,
+}
+
+impl FrameLimiter {
+    pub fn new(target_fps: u32) -> Self {
+        Self {
+            frame_time_usec: 1_000_000 / target_fps.max(1) as u64,
+            next_frame_due: None,
+        }
+    }
+
+    pub fn frame_time_usec(&self) -> u64 {
+        self.frame_time_usec
+    }
+
+    /// How long to sleep when the timer reads `now`, and schedules the frame after that
+    ///
+    /// The first call never sleeps.
+    pub fn next_sleep_usec(&mut self, now: u64) -> u64 {
+        let wake = match self.next_frame_due {
+            Some(due) => due.max(now),
+            None => now,
+        };
+        self.next_frame_due = Some(wake + self.frame_time_usec);
+
+        wake - now
+    }
+
+    /// Sleeps until the next frame is due, given the timer reads `now`
+    pub fn sleep_until_next(&mut self, delay: &mut impl DelayUs<u32>, now: u64) {
+        let usec = self.next_sleep_usec(now);
+        if usec > 0 {
+            delay.delay_us(usec.min(u32::MAX as u64) as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rstest::*;
+
+    #[rstest]
+    #[case::fps_60(60, 16_666)]
+    #[case::fps_30(30, 33_333)]
+    #[case::fps_10(10, 100_000)]
+    #[case::fps_100(100, 10_000)]
+    #[case::fps_0(0, 1_000_000)]
+    fn check_frame_time(#[case] fps: u32, #[case] expected: u64) {
+        assert_eq!(FrameLimiter::new(fps).frame_time_usec(), expected);
+    }
+
+    #[rstest]
+    // Frames that finish early sleep off the rest of their frame
+    #[case::fps_60_fast(60, 1_000, 15_666)]
+    #[case::fps_10_fast(10, 40_000, 60_000)]
+    #[case::fps_100_instant(100, 0, 10_000)]
+    // Frames that take exactly as long as they should, or longer, don't sleep at all
+    #[case::fps_30_exact(30, 33_333, 0)]
+    #[case::fps_30_slow(30, 50_000, 0)]
+    fn check_sleep_after_first_frame(
+        #[case] fps: u32,
+        #[case] elapsed: u64,
+        #[case] expected: u64,
+    ) {
+        let start = 1_234_567;
+        let mut limiter = FrameLimiter::new(fps);
+
+        assert_eq!(limiter.next_sleep_usec(start), 0);
+        assert_eq!(limiter.next_sleep_usec(start + elapsed), expected);
+    }
+
+    #[test]
+    fn check_slow_frames_are_not_made_up() {
+        let mut limiter = FrameLimiter::new(100);
+        assert_eq!(limiter.next_sleep_usec(0), 0);
+
+        // Way behind, so the next frame starts right away...
+        assert_eq!(limiter.next_sleep_usec(55_000), 0);
+
+        // ...and the one after that is a full frame later, not squeezed in to catch up
+        assert_eq!(limiter.next_sleep_usec(56_000), 9_000);
+
+        // Slept until 65ms, so the next frame is due at 75ms
+        assert_eq!(limiter.next_sleep_usec(80_000), 0);
+        assert_eq!(limiter.next_sleep_usec(85_000), 5_000);
+    }
+}
@@ -0,0 +1,340 @@
+//! `embedded_graphics` adapters for the types in `simulations`
+
+use embedded_graphics::mono_font::{ascii, MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::{
+    Line, PrimitiveStyleBuilder, Rectangle, RoundedRectangle, StyledDrawable,
+};
+use embedded_graphics::text::renderer::{CharacterStyle, TextRenderer};
+use embedded_graphics::text::{Baseline, Text};
+
+/// Measures how much space `text` takes up when drawn in `font`
+///
+/// Every `\n` starts a new line, so this also measures text that was wrapped with [`chunk_lines`](crate::chunk_lines)
+/// and joined back together. The width is that of the widest line.
+pub fn text_bounds(text: &str, font: &MonoFont<'_>) -> Size {
+    if text.is_empty() {
+        return Size::zero();
+    }
+
+    let style = MonoTextStyle::new(font, BinaryColor::On);
+    let mut bounds = Size::zero();
+    for line in text.split('\n') {
+        let metrics = style.measure_string(line, Point::zero(), Baseline::Top);
+        bounds.width = bounds.width.max(metrics.bounding_box.size.width);
+        bounds.height += style.line_height();
+    }
+
+    bounds
+}
+
+/// Draws the "BIT flipper" title screen that `pico-oled` shows at boot, laid out for the 128x64 OLED
+///
+/// This only draws on top of what's there, so clear the display first.
+pub fn draw_title<D>(display: &mut D)
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let size = display.bounding_box().size;
+    let width = size.width as i32;
+    let height = size.height as i32;
+
+    // Fullscreen white-border
+    let style_white_border = PrimitiveStyleBuilder::new()
+        .stroke_width(1)
+        .stroke_color(BinaryColor::On)
+        .build();
+    let r = 4;
+    let screen_border = RoundedRectangle::with_equal_corners(
+        Rectangle::new(Point::new(0, 0), size),
+        Size::new(r, r),
+    );
+    let _ = screen_border.draw_styled(&style_white_border, display);
+
+    // Draw "bitflipper", stylized
+    {
+        let mut bit_style = MonoTextStyle::new(&ascii::FONT_6X13_BOLD, BinaryColor::Off);
+        bit_style.set_background_color(Some(BinaryColor::On));
+        let bit = Text::new("BIT", Point::new(38, 19), bit_style);
+        let _ = bit.draw(display);
+
+        let flipper_style = MonoTextStyle::new(&ascii::FONT_6X13_ITALIC, BinaryColor::On);
+        let flipper = Text::new("flipper", Point::new(58, 22), flipper_style);
+        let _ = flipper.draw(display);
+    }
+
+    // Draw some lines below everything
+    for i in 0..3 {
+        let xs = width * 1 / 8 + 3 * (3 - i);
+        let xe = width * 7 / 8 - 3 * (3 - i);
+        let y = 3 * height / 4 + (i - 1) * 5 - 16;
+        let line0 = Line::new(Point::new(xs, y), Point::new(xe, y));
+        let _ = line0.draw_styled(&style_white_border, display);
+    }
+
+    // Instruct the obediant
+    let anykey = Text::new(
+        "PRESS ANY KEY",
+        Point::new(32, 3 * height / 4 + 4),
+        MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
+    );
+    let _ = anykey.draw(display);
+}
+
+/// Draws the live cells of a [`Life`](simulations::Life) board as filled squares
+///
+/// Dead cells are not drawn, so clear the area first if the target isn't already blank.
+#[derive(Copy, Clone)]
+pub struct LifeDrawable<'a> {
+    life: &'a simulations::Life,
+    position: Point,
+    cell_size: u32,
+}
+
+impl<'a> LifeDrawable<'a> {
+    /// `position` is the top-left corner of the board, and each cell is drawn as a `cell_size` x `cell_size` square
+    pub fn new(life: &'a simulations::Life, position: Point, cell_size: u32) -> Self {
+        Self {
+            life,
+            position,
+            cell_size,
+        }
+    }
+}
+
+impl Dimensions for LifeDrawable<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        let size = Size::new(self.life.width() as u32, self.life.height() as u32) * self.cell_size;
+        Rectangle::new(self.position, size)
+    }
+}
+
+impl Drawable for LifeDrawable<'_> {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let cell = Size::new_equal(self.cell_size);
+        let step = self.cell_size as i32;
+
+        for y in 0..self.life.height() {
+            for x in 0..self.life.width() {
+                if self.life.get(x, y) {
+                    let corner = self.position + Point::new(x as i32 * step, y as i32 * step);
+                    target.fill_solid(&Rectangle::new(corner, cell), BinaryColor::On)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Which way an [`ElementryDrawable`] lays out its cells
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// Cells go left to right, so generations stack up like rows of text
+    #[default]
+    Horizontal,
+
+    /// Cells go top to bottom, for running the simulation "sideways"
+    Vertical,
+}
+
+/// Draws the current generation of an [`Elementry`](simulations::Elementry) simulation as a line of filled squares
+///
+/// Unlike [`LifeDrawable`], dead cells are drawn too (as [`BinaryColor::Off`]), since each generation is usually drawn
+/// right over an older one.
+#[derive(Copy, Clone)]
+pub struct ElementryDrawable<'a> {
+    sim: &'a simulations::Elementry,
+    position: Point,
+    cell_size: u32,
+    orientation: Orientation,
+}
+
+impl<'a> ElementryDrawable<'a> {
+    /// `position` is the top-left corner of the first cell, and each cell is drawn as a `cell_size` x `cell_size`
+    /// square
+    pub fn new(sim: &'a simulations::Elementry, position: Point, cell_size: u32) -> Self {
+        Self {
+            sim,
+            position,
+            cell_size,
+            orientation: Orientation::Horizontal,
+        }
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+}
+
+impl Dimensions for ElementryDrawable<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        let len = self.sim.width() as u32 * self.cell_size;
+        let size = match self.orientation {
+            Orientation::Horizontal => Size::new(len, self.cell_size),
+            Orientation::Vertical => Size::new(self.cell_size, len),
+        };
+        Rectangle::new(self.position, size)
+    }
+}
+
+impl Drawable for ElementryDrawable<'_> {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let cell = Size::new_equal(self.cell_size);
+        let step = match self.orientation {
+            Orientation::Horizontal => Point::new(self.cell_size as i32, 0),
+            Orientation::Vertical => Point::new(0, self.cell_size as i32),
+        };
+
+        for (i, is_alive) in self.sim.cells().enumerate() {
+            let corner = self.position + step * i as i32;
+            target.fill_solid(&Rectangle::new(corner, cell), is_alive.into())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::mock;
+
+    use embedded_graphics::mock_display::MockDisplay;
+
+    fn make_glider() -> simulations::Life {
+        let mut life = simulations::Life::new(3, 3);
+        life.write_right_glider(0, 0);
+        life
+    }
+
+    #[test]
+    fn check_golden_title() {
+        let (mut display, _log) = mock::sh1107_display();
+        draw_title(&mut display);
+
+        mock::assert_golden("title", &mock::snapshot(&display));
+    }
+
+    #[test]
+    fn check_draw_life_unit_cells() {
+        let life = make_glider();
+        let mut display = MockDisplay::new();
+
+        LifeDrawable::new(&life, Point::new(1, 2), 1)
+            .draw(&mut display)
+            .unwrap();
+
+        display.assert_pattern(&[
+            "    ", //
+            "    ", //
+            "  # ", //
+            "   #", //
+            " ###", //
+        ]);
+    }
+
+    #[test]
+    fn check_draw_life_big_cells() {
+        let life = make_glider();
+        let mut display = MockDisplay::new();
+
+        let drawable = LifeDrawable::new(&life, Point::new(1, 0), 2);
+        assert_eq!(
+            drawable.bounding_box(),
+            Rectangle::new(Point::new(1, 0), Size::new(6, 6))
+        );
+        drawable.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "   ##  ", //
+            "   ##  ", //
+            "     ##", //
+            "     ##", //
+            " ######", //
+            " ######", //
+        ]);
+    }
+
+    fn make_elementry() -> simulations::Elementry {
+        let mut sim = simulations::Elementry::new(90, 5);
+        sim.set(1, true);
+        sim.set(4, true);
+        sim
+    }
+
+    #[test]
+    fn check_draw_elementry_row() {
+        let sim = make_elementry();
+        let mut display = MockDisplay::new();
+
+        let drawable = ElementryDrawable::new(&sim, Point::new(1, 1), 2);
+        assert_eq!(
+            drawable.bounding_box(),
+            Rectangle::new(Point::new(1, 1), Size::new(10, 2))
+        );
+        drawable.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "           ", //
+            " ..##....##", //
+            " ..##....##", //
+        ]);
+    }
+
+    #[test]
+    fn check_draw_elementry_sideways() {
+        let sim = make_elementry();
+        let mut display = MockDisplay::new();
+
+        let drawable = ElementryDrawable::new(&sim, Point::new(2, 0), 1)
+            .with_orientation(Orientation::Vertical);
+        assert_eq!(
+            drawable.bounding_box(),
+            Rectangle::new(Point::new(2, 0), Size::new(1, 5))
+        );
+        drawable.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "  .", //
+            "  #", //
+            "  .", //
+            "  .", //
+            "  #", //
+        ]);
+    }
+
+    #[test]
+    fn check_text_bounds_monospace() {
+        let text = "17 -3";
+        let font = &ascii::FONT_5X8;
+
+        assert_eq!(
+            text_bounds(text, font),
+            Size::new(text.len() as u32 * font.character_size.width, 8)
+        );
+    }
+
+    #[test]
+    fn check_text_bounds_multi_line() {
+        let font = &ascii::FONT_6X10;
+
+        assert_eq!(text_bounds("", font), Size::zero());
+        assert_eq!(text_bounds("ab\nabcd\n", font), Size::new(4 * 6, 3 * 10));
+    }
+}
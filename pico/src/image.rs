@@ -1,4 +1,5 @@
 use bytemuck::*;
+use simulations::BitGrid;
 
 use alloc::{vec, vec::Vec};
 use core::ops::{Index, IndexMut};
@@ -15,6 +16,40 @@ impl Rgb565 {
     pub const fn from_rgb888(x: u32) -> Self {
         Self(to_rgb565(x).to_be_bytes())
     }
+
+    /// The packed 16-bit value, as the display sees it
+    pub const fn to_rgb565(self) -> u16 {
+        u16::from_be_bytes(self.0)
+    }
+
+    /// Expands back out to `0xRRGGBB`
+    ///
+    /// The low bits of each channel are lost when packing, so this won't always round-trip with [`Self::from_rgb888()`].
+    pub const fn to_rgb888(self) -> u32 {
+        const fn scale_to_8_bits(n: u16, bits: u32) -> u32 {
+            // Repeat the high bits into the low bits, so that full scale maps to 0xFF
+            let n = n as u32;
+            (n << (8 - bits)) | (n >> (2 * bits - 8))
+        }
+        let rgb565 = self.to_rgb565();
+        let b = scale_to_8_bits((rgb565 >> 0) & 0x1f, 5);
+        let g = scale_to_8_bits((rgb565 >> 5) & 0x3f, 6);
+        let r = scale_to_8_bits((rgb565 >> (5 + 6)) & 0x1f, 5);
+
+        (b << 0) | (g << 8) | (r << 16)
+    }
+}
+
+impl core::fmt::Debug for Rgb565 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Rgb565(0x{:04X} ~= {self})", self.to_rgb565())
+    }
+}
+
+impl core::fmt::Display for Rgb565 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{:06X}", self.to_rgb888())
+    }
 }
 
 const fn to_rgb565(color: u32) -> u16 {
@@ -29,6 +64,71 @@ const fn to_rgb565(color: u32) -> u16 {
     (b << 0) | (g << 5) | (r << (5 + 6))
 }
 
+/// A rectangle of pixels, with its top left corner at `(x, y)`
+///
+/// Rects can hang off the edges of an image (or sit entirely outside it), and only the part that overlaps is used.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, w: u32, h: u32) -> Self {
+        Self { x, y, w, h }
+    }
+}
+
+/// The two colors a 1-bit grid is drawn with, as `[unset, set]`
+pub type Palette = [Rgb565; 2];
+
+/// Draws 1-bit [`BitGrid`]s into an [`Image<Rgb565>`], as a `scale` x `scale` block of pixels per cell
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PaletteMapper {
+    pub scale: u16,
+}
+
+impl PaletteMapper {
+    pub const fn new(scale: u16) -> Self {
+        Self { scale }
+    }
+
+    /// Overwrites `image` with `grid`, starting from the top left corner
+    ///
+    /// Cells that would land off the edge of `image` are skipped, and pixels past the scaled grid are left alone.
+    pub fn map_1bit(&self, grid: &BitGrid, palette: &Palette, image: &mut Image<Rgb565>) {
+        let scale = self.scale as usize;
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let used_width = (grid.width() as usize * scale).min(width);
+
+        for y in 0..grid.height() {
+            let top = y as usize * scale;
+            if top >= height {
+                break;
+            }
+
+            // Draw the first line of this row of cells, then repeat it for the rest of the block
+            let line = top * width;
+            for x in 0..grid.width() {
+                let left = x as usize * scale;
+                if left >= width {
+                    break;
+                }
+                let right = (left + scale).min(width);
+                image.buf[line + left..line + right].fill(palette[grid.get(x, y) as usize]);
+            }
+            for dst_y in top + 1..(top + scale).min(height) {
+                image
+                    .buf
+                    .copy_within(line..line + used_width, dst_y * width);
+            }
+        }
+    }
+}
+
 pub struct Image<Pixel = Rgb565> {
     buf: Vec<Pixel>,
     width: u16,
@@ -60,6 +160,55 @@ where
         self.buf.fill(color);
     }
 
+    /// Fills the part of `rect` that's on the image with `color`
+    pub fn fill_rect(&mut self, rect: Rect, color: Pixel) {
+        let clip = |start: i32, len: u32, max: u16| {
+            let end = (start as i64 + len as i64).clamp(0, max as i64) as usize;
+            let start = start.clamp(0, max as i32) as usize;
+            start..end.max(start)
+        };
+        let xs = clip(rect.x, rect.w, self.width);
+        let ys = clip(rect.y, rect.h, self.height);
+
+        let width = self.width as usize;
+        for y in ys {
+            self.buf[y * width + xs.start..y * width + xs.end].fill(color);
+        }
+    }
+
+    /// A copy of this image rotated a quarter turn clockwise
+    ///
+    /// The width and height swap: the left column becomes the top row.
+    pub fn rotate90(&self) -> Self {
+        self.rotated(self.height, self.width, |x, y| (self.height - 1 - y, x))
+    }
+
+    /// A copy of this image rotated a half turn
+    pub fn rotate180(&self) -> Self {
+        self.rotated(self.width, self.height, |x, y| {
+            (self.width - 1 - x, self.height - 1 - y)
+        })
+    }
+
+    /// A copy of this image rotated a quarter turn counter-clockwise
+    ///
+    /// The width and height swap: the right column becomes the top row.
+    pub fn rotate270(&self) -> Self {
+        self.rotated(self.height, self.width, |x, y| (y, self.width - 1 - x))
+    }
+
+    /// Builds a `width` x `height` image, moving each pixel at `(x, y)` in `self` to `to_dst(x, y)`
+    fn rotated(&self, width: u16, height: u16, to_dst: impl Fn(u16, u16) -> (u16, u16)) -> Self {
+        let mut dst = Self::new(width, height);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                dst[to_dst(x, y)] = self[(x, y)];
+            }
+        }
+
+        dst
+    }
+
     fn raw_idx(&self, x: u16, y: u16) -> Option<usize> {
         if x < self.width() && y < self.height() {
             let idx = x + y * self.width();
@@ -70,6 +219,56 @@ where
     }
 }
 
+impl Image<Rgb565> {
+    /// Writes this image as a 16-bit BMP, which any image viewer can open
+    ///
+    /// Pixels are stored as-is with `BI_BITFIELDS` masks, so no color is lost.
+    #[cfg(any(test, feature = "std"))]
+    pub fn write_bmp(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        const FILE_HEADER_LEN: u32 = 14;
+        const INFO_HEADER_LEN: u32 = 40;
+        const MASKS_LEN: u32 = 3 * 4;
+        const PIXELS_OFFSET: u32 = FILE_HEADER_LEN + INFO_HEADER_LEN + MASKS_LEN;
+        const BI_BITFIELDS: u32 = 3;
+
+        // Every row is padded out to a multiple of 4 bytes
+        let row_len = (2 * self.width() as u32).next_multiple_of(4);
+        let pixels_len = row_len * self.height() as u32;
+
+        // File header
+        w.write_all(b"BM")?;
+        w.write_all(&(PIXELS_OFFSET + pixels_len).to_le_bytes())?;
+        w.write_all(&[0; 4])?; // Reserved
+        w.write_all(&PIXELS_OFFSET.to_le_bytes())?;
+
+        // Info header
+        w.write_all(&INFO_HEADER_LEN.to_le_bytes())?;
+        w.write_all(&(self.width() as i32).to_le_bytes())?;
+        w.write_all(&(self.height() as i32).to_le_bytes())?;
+        w.write_all(&1_u16.to_le_bytes())?; // Planes
+        w.write_all(&16_u16.to_le_bytes())?; // Bits per pixel
+        w.write_all(&BI_BITFIELDS.to_le_bytes())?;
+        w.write_all(&pixels_len.to_le_bytes())?;
+        w.write_all(&[0; 16])?; // Resolution and palette sizes, which nobody needs
+
+        // Red, green, and blue masks
+        for mask in [0xF800_u32, 0x07E0, 0x001F] {
+            w.write_all(&mask.to_le_bytes())?;
+        }
+
+        // BMPs store the bottom row first
+        let padding = [0; 3];
+        for y in (0..self.height()).rev() {
+            for x in 0..self.width() {
+                w.write_all(&self[(x, y)].to_rgb565().to_le_bytes())?;
+            }
+            w.write_all(&padding[..(row_len - 2 * self.width() as u32) as usize])?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<Pixel> Index<(u16, u16)> for Image<Pixel>
 where
     Pixel: Pod,
@@ -104,3 +303,215 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rstest::*;
+
+    #[test]
+    fn check_fmt_aoc_gold() {
+        // 0x66 doesn't survive the trip through 5 bits of blue
+        assert_eq!(format!("{}", crate::AOC_GOLD), "#FFFF63");
+
+        let debug = format!("{:?}", crate::AOC_GOLD);
+        assert!(debug.contains("#FFFF63"), "{debug}");
+        assert_eq!(debug, "Rgb565(0xFFEC ~= #FFFF63)");
+    }
+
+    /// A 3x2 image where every pixel is different, so any mistake in a rotation shows up
+    ///
+    /// ```txt
+    /// 1 2 3
+    /// 4 5 6
+    /// ```
+    fn make_asymmetric() -> Image<u8> {
+        let mut img = Image::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                img[(x, y)] = (1 + x + 3 * y) as u8;
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn check_fill_rect() {
+        let mut img: Image<u8> = Image::new(5, 4);
+        img.fill_rect(Rect::new(1, 1, 3, 2), 7);
+
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 0, 0, 0,
+            0, 7, 7, 7, 0,
+            0, 7, 7, 7, 0,
+            0, 0, 0, 0, 0,
+        ];
+        assert_eq!(img.as_bytes(), expected);
+    }
+
+    #[rstest]
+    #[case::top_left(Rect::new(-2, -1, 4, 3), [(0, 0), (1, 1)])]
+    #[case::bottom_right(Rect::new(3, 2, 10, 10), [(3, 2), (4, 3)])]
+    #[case::everything(Rect::new(-100, -100, 1000, 1000), [(0, 0), (4, 3)])]
+    fn check_fill_rect_clips(#[case] rect: Rect, #[case] corners: [(u16, u16); 2]) {
+        let mut img: Image<u8> = Image::new(5, 4);
+        img.fill_rect(rect, 1);
+
+        let [(x0, y0), (x1, y1)] = corners;
+        for y in 0..4 {
+            for x in 0..5 {
+                let inside = (x0..=x1).contains(&x) && (y0..=y1).contains(&y);
+                assert_eq!(img[(x, y)], inside as u8, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::left(Rect::new(-5, 0, 5, 4))]
+    #[case::below(Rect::new(0, 4, 5, 4))]
+    #[case::empty(Rect::new(1, 1, 0, 3))]
+    fn check_fill_rect_misses(#[case] rect: Rect) {
+        let mut img: Image<u8> = Image::new(5, 4);
+        img.fill_rect(rect, 1);
+        assert!(img.as_bytes().iter().all(|&px| px == 0));
+    }
+
+    #[test]
+    fn check_map_1bit_at_scale_2() {
+        const BG: Rgb565 = Rgb565::new(0x0001);
+        const FG: Rgb565 = Rgb565::new(0xF000);
+
+        // #.
+        // .#
+        // #.
+        let mut grid = BitGrid::new(2, 3);
+        grid.set(0, 0, true);
+        grid.set(1, 1, true);
+        grid.set(0, 2, true);
+
+        // One column and row past the scaled grid, which should be left alone
+        let mut img = Image::<Rgb565>::new(5, 7);
+        let untouched = Rgb565::new(0x7777);
+        img.fill(untouched);
+
+        PaletteMapper::new(2).map_1bit(&grid, &[BG, FG], &mut img);
+
+        for y in 0..7 {
+            for x in 0..5 {
+                let expected = if x >= 4 || y >= 6 {
+                    untouched
+                } else if grid.get(x as i16 / 2, y as i16 / 2) {
+                    FG
+                } else {
+                    BG
+                };
+                assert_eq!(img[(x, y)], expected, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn check_map_1bit_clips() {
+        let mut grid = BitGrid::new(3, 3);
+        grid.set(2, 2, true);
+
+        let mut img = Image::<Rgb565>::new(5, 5);
+        PaletteMapper::new(2).map_1bit(&grid, &[crate::AOC_BLUE, crate::AOC_GOLD], &mut img);
+
+        // Only the top-left quarter of the bottom right cell fits
+        assert_eq!(img[(4, 4)], crate::AOC_GOLD);
+        assert_eq!(img[(3, 3)], crate::AOC_BLUE);
+        assert_eq!(img[(4, 3)], crate::AOC_BLUE);
+    }
+
+    #[test]
+    fn check_rotate90() {
+        let img = make_asymmetric();
+
+        let r90 = img.rotate90();
+        assert_eq!((r90.width(), r90.height()), (2, 3));
+        assert_eq!(r90.as_bytes(), [4, 1, 5, 2, 6, 3]);
+
+        let r180 = img.rotate180();
+        assert_eq!((r180.width(), r180.height()), (3, 2));
+        assert_eq!(r180.as_bytes(), [6, 5, 4, 3, 2, 1]);
+
+        let r270 = img.rotate270();
+        assert_eq!((r270.width(), r270.height()), (2, 3));
+        assert_eq!(r270.as_bytes(), [3, 6, 2, 5, 1, 4]);
+
+        // Rotations compose
+        assert_eq!(r90.rotate90().as_bytes(), r180.as_bytes());
+        assert_eq!(r90.rotate180().as_bytes(), r270.as_bytes());
+        assert_eq!(r270.rotate90().as_bytes(), img.as_bytes());
+    }
+
+    #[test]
+    fn check_rotate90_four_times_is_identity() {
+        let mut img = Image::<Rgb565>::new(5, 3);
+        for y in 0..3 {
+            for x in 0..5 {
+                img[(x, y)] = Rgb565::new(x * 0x100 + y);
+            }
+        }
+
+        let mut rotated = img.rotate90();
+        for _ in 0..3 {
+            assert_ne!(rotated.as_bytes(), img.as_bytes());
+            rotated = rotated.rotate90();
+        }
+        assert_eq!((rotated.width(), rotated.height()), (5, 3));
+        assert_eq!(rotated.as_bytes(), img.as_bytes());
+    }
+
+    #[test]
+    fn check_write_bmp() {
+        let mut img = Image::<Rgb565>::new(3, 2);
+        img[(0, 0)] = Rgb565::new(0x1234);
+        img[(2, 1)] = Rgb565::new(0xABCD);
+
+        let mut bmp = vec![];
+        img.write_bmp(&mut bmp).unwrap();
+
+        let u16_at = |i: usize| u16::from_le_bytes([bmp[i], bmp[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes(bmp[i..i + 4].try_into().unwrap());
+
+        // Rows of 3 pixels are 6 bytes, padded to 8
+        let pixels_len = 8 * 2;
+        assert_eq!(bmp.len(), 66 + pixels_len);
+
+        // File header
+        assert_eq!(&bmp[0..2], b"BM");
+        assert_eq!(u32_at(2) as usize, bmp.len());
+        assert_eq!(u32_at(10), 66);
+
+        // Info header
+        assert_eq!(u32_at(14), 40);
+        assert_eq!((u32_at(18), u32_at(22)), (3, 2));
+        assert_eq!(u16_at(26), 1);
+        assert_eq!(u16_at(28), 16);
+        assert_eq!(u32_at(30), 3);
+        assert_eq!(u32_at(34) as usize, pixels_len);
+        assert_eq!(
+            (u32_at(54), u32_at(58), u32_at(62)),
+            (0xF800, 0x07E0, 0x001F)
+        );
+
+        // Bottom row first
+        assert_eq!(u16_at(66 + 4), 0xABCD);
+        assert_eq!(u16_at(66 + 8), 0x1234);
+        assert_eq!(bmp[66..].iter().filter(|&&b| b != 0).count(), 4);
+    }
+
+    #[rstest]
+    #[case::black(0x00_00_00)]
+    #[case::white(0xff_ff_ff)]
+    #[case::red(0xff_00_00)]
+    #[case::green(0x00_ff_00)]
+    #[case::blue(0x00_00_ff)]
+    fn check_rgb888_round_trip(#[case] rgb888: u32) {
+        assert_eq!(Rgb565::from_rgb888(rgb888).to_rgb888(), rgb888);
+    }
+}
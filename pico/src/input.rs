@@ -0,0 +1,156 @@
+//! Buttons, by the role they play rather than the pin they're wired to
+//!
+//! Scenes only ever see "A" and "B" through [`Context`]. Which physical key is which is decided once at startup with
+//! a [`ButtonMap`], so every binary agrees on it.
+
+use embedded_hal::digital::InputPin;
+
+use crate::scene::Context;
+
+/// The two keys on the Pico-OLED-1.3, named after their labels on the board
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// `KEY0`, wired to GPIO 17
+    Key0,
+
+    /// `KEY1`, wired to GPIO 15
+    Key1,
+}
+
+/// Which key acts as each of the A and B buttons that scenes see
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ButtonMap {
+    pub a: Key,
+    pub b: Key,
+}
+
+impl Default for ButtonMap {
+    /// `KEY1` is A and `KEY0` is B
+    fn default() -> Self {
+        Self {
+            a: Key::Key1,
+            b: Key::Key0,
+        }
+    }
+}
+
+impl ButtonMap {
+    /// The same map with A and B trading keys
+    pub const fn swapped(self) -> Self {
+        Self {
+            a: self.b,
+            b: self.a,
+        }
+    }
+}
+
+/// Reads the board's keys and reports them as the A and B buttons, according to a [`ButtonMap`]
+pub struct Buttons<Key0Pin, Key1Pin> {
+    key0: Key0Pin,
+    key1: Key1Pin,
+    map: ButtonMap,
+}
+
+impl<Key0Pin, Key1Pin> Buttons<Key0Pin, Key1Pin>
+where
+    Key0Pin: InputPin,
+    Key1Pin: InputPin,
+{
+    /// `key0` and `key1` should be pulled up, since pressing a key pulls its pin low
+    pub fn new(key0: Key0Pin, key1: Key1Pin, map: ButtonMap) -> Self {
+        Self { key0, key1, map }
+    }
+
+    pub fn map(&self) -> ButtonMap {
+        self.map
+    }
+
+    /// Whether `key` is held down
+    ///
+    /// Pins that fail to read count as held, so a wait-for-any-key loop can't get stuck on a broken pin.
+    pub fn is_pressed(&mut self, key: Key) -> bool {
+        match key {
+            Key::Key0 => self.key0.is_low().unwrap_or(true),
+            Key::Key1 => self.key1.is_low().unwrap_or(true),
+        }
+    }
+
+    /// Whether the A and B buttons are held down, in that order
+    pub fn read(&mut self) -> (bool, bool) {
+        let (a, b) = (self.map.a, self.map.b);
+        (self.is_pressed(a), self.is_pressed(b))
+    }
+
+    /// Copies the state of the A and B buttons into `ctx`
+    pub fn update(&mut self, ctx: &mut Context<'_>) {
+        (ctx.btn_a, ctx.btn_b) = self.read();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::{self, MockPin};
+    use crate::peripherals::SH1107Display;
+    use crate::scene::{Context, Scene};
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use rstest::*;
+
+    /// Remembers which buttons it saw on its last update
+    #[derive(Default)]
+    struct RecordingScene {
+        seen: Option<(bool, bool)>,
+    }
+
+    impl Scene for RecordingScene {
+        fn update<Device, DataCmdPin>(
+            &mut self,
+            ctx: &mut Context<'_>,
+            _display: &mut SH1107Display<Device, DataCmdPin>,
+        ) -> bool
+        where
+            DataCmdPin: embedded_hal::digital::OutputPin,
+            Device: embedded_hal::spi::SpiDevice,
+        {
+            self.seen = Some((ctx.btn_a, ctx.btn_b));
+            false
+        }
+    }
+
+    /// A pin for a key that's held (pulled low) or not
+    fn key(is_held: bool) -> MockPin {
+        MockPin::with_levels([!is_held])
+    }
+
+    #[rstest]
+    #[case::default(ButtonMap::default())]
+    #[case::swapped(ButtonMap::default().swapped())]
+    fn check_a_reaches_scene_for_any_map(#[case] map: ButtonMap) {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        // Hold down whichever key this map calls A
+        let mut buttons = Buttons::new(key(map.a == Key::Key0), key(map.a == Key::Key1), map);
+        let mut scene = RecordingScene::default();
+
+        buttons.update(&mut ctx);
+        scene.update(&mut ctx, &mut display);
+        assert_eq!(scene.seen, Some((true, false)));
+    }
+
+    #[test]
+    fn check_map_decides_roles() {
+        let map = ButtonMap::default();
+        assert_eq!((map.a, map.b), (Key::Key1, Key::Key0));
+
+        // Only KEY0 is held, which is B by default and A once swapped
+        let mut buttons = Buttons::new(key(true), key(false), map);
+        assert_eq!(buttons.read(), (false, true));
+
+        let mut buttons = Buttons::new(key(true), key(false), map.swapped());
+        assert_eq!(buttons.read(), (true, false));
+    }
+}
@@ -1,4 +1,4 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![allow(
     clippy::identity_op,
     clippy::collapsible_if,
@@ -7,84 +7,105 @@
 
 extern crate alloc;
 
+pub mod brightness;
+pub use brightness::auto_brightness;
+
+pub mod frame_limiter;
+pub use frame_limiter::FrameLimiter;
+
+pub mod graphics;
+pub use graphics::{draw_title, text_bounds, ElementryDrawable, LifeDrawable, Orientation};
+
 pub mod image;
-pub use image::{Image, Rgb565};
+pub use image::{Image, Palette, PaletteMapper, Rect, Rgb565};
+
+pub mod input;
+pub use input::{ButtonMap, Buttons};
 
 pub mod peripherals;
 pub mod scene;
 
+pub mod step_timer;
+pub use step_timer::StepTimer;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
 pub const AOC_BLUE: Rgb565 = Rgb565::from_rgb888(0x0f_0f_23);
 pub const AOC_GOLD: Rgb565 = Rgb565::from_rgb888(0xff_ff_66);
 pub const OHNO_PINK: Rgb565 = Rgb565::new(0xF8_1F);
 
 /// Chunk lines for drawing on a small display
+///
+/// Every `\n` is a hard break, regardless of `chars_per_line`. A run of `N` newlines produces `N - 1` empty lines
+/// between its neighbors, the same at every width, and trailing whitespace at the end of `text` is dropped.
+///
+/// Lines longer than `chars_per_line` are then wrapped at the last whitespace that fits, falling back to the last
+/// non-alphanumeric byte, and finally to a hard break mid-word. Lines that come out of wrapping blank (e.g. a long run
+/// of spaces) are skipped.
+///
+/// Nothing fits in a `chars_per_line` of `0`, so that produces no lines at all.
 // TODO: Make this return an iterator instead of using a callback
 pub fn chunk_lines<'a>(text: &'a str, chars_per_line: usize, mut callback: impl FnMut(&'a str)) {
-    let mut bytes: &[u8] = text.as_bytes();
+    let text = text.trim_end();
+    if text.is_empty() || chars_per_line == 0 {
+        return;
+    }
 
-    while bytes.len() > chars_per_line {
-        // println!("===");
-        // println!("  + bytes.len()={}", bytes.len());
+    for line in text.split('\n') {
+        let line = line.trim_end();
+        if line.is_empty() {
+            // Explicit blank lines are kept
+            callback(line);
+        } else {
+            wrap_line(line, chars_per_line, &mut callback);
+        }
+    }
+}
+
+/// Wraps a single line (no `\n`s) for [`chunk_lines`]
+fn wrap_line<'a>(line: &'a str, chars_per_line: usize, callback: &mut impl FnMut(&'a str)) {
+    let mut bytes: &[u8] = line.as_bytes();
 
-        let next_len = bytes.len().min(chars_per_line);
-        // println!("  + Searching {} bytes", next_len);
-        // println!("    + {:?}", unsafe {
-        //     core::str::from_utf8_unchecked(&bytes[..next_len])
-        // });
+    while bytes.len() > chars_per_line {
+        let next_len = chars_per_line;
 
         // Within the next line's worth of bytes, find somewhere to break.
-        let break_idx: usize = if let Some(idx) = bytes[..next_len].iter().position(|&b| b == b'\n')
-        {
-            // println!("  + Found first newline, using idx={idx}");
-            idx + 1
-        } else if let Some(idx) = bytes[..next_len]
+        let break_idx: usize = if let Some(idx) = bytes[..next_len]
             .iter()
             .rposition(|b| b.is_ascii_whitespace())
         {
-            // println!("  + Found last whitespace, using idx={idx}");
+            // Found the last whitespace
             idx + 1
         } else if let Some(idx) = bytes[..next_len]
             .iter()
             .rposition(|b| !b.is_ascii_alphanumeric())
         {
-            // println!(
-            //     "  + Found NO whitespace, using ugly letter ({:?}) at idx={idx}",
-            //     bytes[idx] as char
-            // );
+            // Found NO whitespace, so use an ugly non-letter
             idx + 1
         } else {
-            // println!("  + Found nothing worth breaking at, forcing break at {next_len}");
+            // Found nothing worth breaking at, so force a break
             next_len
         };
-        // println!(
-        //     "  + Breaking on {:?}",
-        //     bytes.get(break_idx).map(|&b| b as char)
-        // );
 
-        let line = unsafe { core::str::from_utf8_unchecked(&bytes[..break_idx]) };
-        let line = line.trim_end();
+        let part = unsafe { core::str::from_utf8_unchecked(&bytes[..break_idx]) };
+        let part = part.trim_end();
 
         bytes = &bytes[break_idx..];
 
-        if line.trim().is_empty() {
+        if part.trim().is_empty() {
             // empty line? SKIP
             continue;
         }
 
-        for part in line.split("\n") {
-            callback(part);
-        }
+        callback(part);
     }
 
-    let line = unsafe { core::str::from_utf8_unchecked(bytes) };
-    let line = line.trim_end();
-    if !line.trim().is_empty() {
-        for part in line.split("\n") {
-            callback(part);
-        }
+    let part = unsafe { core::str::from_utf8_unchecked(bytes) };
+    let part = part.trim_end();
+    if !part.trim().is_empty() {
+        callback(part);
     }
-
-    // println!("  + bytes.len()={}", bytes.len());
 }
 
 #[cfg(test)]
@@ -123,6 +144,14 @@ mod test {
         "........................",
     ])]
     #[case::preserve_newlines("abcd\n\n\ndefg", 100, ["abcd", "",  "","defg"])]
+    #[case::preserve_newlines_narrow("abcd\n\n\ndefg", 4, ["abcd", "", "", "defg"])]
+    #[case::newline_short("a\nb", 3, ["a", "b"])]
+    #[case::newline_double("a\n\nb", 3, ["a", "", "b"])]
+    #[case::newline_then_long_word("a\nbbbbbbbbbb", 3, ["a", "bbb", "bbb", "bbb", "b"])]
+    #[case::newline_at_width("abc\ndef", 3, ["abc", "def"])]
+    #[case::trailing_newlines("abc\n\n", 3, ["abc"])]
+    #[case::zero_width("abc def", 0, [])]
+    #[case::zero_width_newlines("a\n\nb", 0, [])]
     // Check some cases where we SHOULD NOT line break
     #[case::short_alpha("abcd", 100, ["abcd"])]
     #[case::short_alpha_white("abcd defg", 100, ["abcd defg"])]
@@ -0,0 +1,356 @@
+//! Host-side stand-ins for the hal objects our peripherals are built on
+//!
+//! These let tests construct the real display drivers and inspect exactly what they would have sent over the wire.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use cortex_m::prelude::*;
+use embedded_hal::digital::{ErrorType as PinErrorType, InputPin, OutputPin};
+use embedded_hal::i2c::{ErrorType as I2cErrorType, I2c, Operation as I2cOperation};
+use embedded_hal::spi::{ErrorType as SpiErrorType, Operation, SpiDevice};
+
+/// A single `SpiDevice::write` (or write operation within a transaction)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpiWrite {
+    /// State of the Data/Command pin when this was written. `false` means "command".
+    pub dc: bool,
+    pub bytes: Vec<u8>,
+}
+
+/// Everything seen on a mocked SPI bus
+#[derive(Clone, Debug, Default)]
+pub struct BusLog {
+    pub dc: bool,
+    pub writes: Vec<SpiWrite>,
+}
+
+impl BusLog {
+    /// Every command byte that was sent, in order
+    pub fn commands(&self) -> Vec<u8> {
+        self.writes
+            .iter()
+            .filter(|w| !w.dc)
+            .flat_map(|w| w.bytes.iter().copied())
+            .collect()
+    }
+
+    /// Every data byte that was sent, in order
+    pub fn data(&self) -> Vec<u8> {
+        self.writes
+            .iter()
+            .filter(|w| w.dc)
+            .flat_map(|w| w.bytes.iter().copied())
+            .collect()
+    }
+}
+
+pub type SharedBusLog = Rc<RefCell<BusLog>>;
+
+/// Creates an SPI device and a DC pin that both record into the same [`BusLog`]
+pub fn spi_bus() -> (MockSpi, MockDcPin, SharedBusLog) {
+    let log = SharedBusLog::default();
+    (
+        MockSpi { log: log.clone() },
+        MockDcPin { log: log.clone() },
+        log,
+    )
+}
+
+/// SPI device that records every write and reads back zeros
+pub struct MockSpi {
+    log: SharedBusLog,
+}
+
+impl SpiErrorType for MockSpi {
+    type Error = Infallible;
+}
+
+impl SpiDevice for MockSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut log = self.log.borrow_mut();
+        for op in operations {
+            match op {
+                Operation::Write(bytes) => {
+                    let dc = log.dc;
+                    log.writes.push(SpiWrite {
+                        dc,
+                        bytes: bytes.to_vec(),
+                    });
+                }
+                Operation::Read(buf) | Operation::TransferInPlace(buf) => buf.fill(0),
+                Operation::Transfer(read, _write) => read.fill(0),
+                Operation::DelayNs(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Data/Command pin that tracks its level in the shared [`BusLog`]
+pub struct MockDcPin {
+    log: SharedBusLog,
+}
+
+impl PinErrorType for MockDcPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockDcPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.log.borrow_mut().dc = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.log.borrow_mut().dc = true;
+        Ok(())
+    }
+}
+
+/// A pin that ignores writes, and reads back a scripted sequence of levels
+///
+/// Once the script runs out, reads return the last level forever.
+#[derive(Clone, Debug, Default)]
+pub struct MockPin {
+    pub levels: Vec<bool>,
+    pub reads: usize,
+}
+
+impl MockPin {
+    pub fn with_levels(levels: impl IntoIterator<Item = bool>) -> Self {
+        Self {
+            levels: levels.into_iter().collect(),
+            reads: 0,
+        }
+    }
+
+    fn level(&mut self) -> bool {
+        let level = self
+            .levels
+            .get(self.reads)
+            .or(self.levels.last())
+            .copied()
+            .unwrap_or(false);
+        self.reads += 1;
+        level
+    }
+}
+
+impl PinErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl InputPin for MockPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.level())
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.level())
+    }
+}
+
+/// Delay that returns immediately
+pub struct MockDelay;
+
+impl _embedded_hal_blocking_delay_DelayMs<u32> for MockDelay {
+    fn delay_ms(&mut self, _ms: u32) {}
+}
+
+/// I2C device with a bank of 16-bit big-endian registers, like the INA219
+///
+/// A write of one byte selects a register, and a write of three bytes selects one and stores a value in it.
+/// Reads return whatever the selected register holds.
+#[derive(Clone, Debug, Default)]
+pub struct MockI2c {
+    pub registers: [u16; 8],
+    pub selected: usize,
+    /// Address of every transaction, in order
+    pub addrs: Vec<u8>,
+}
+
+impl I2cErrorType for MockI2c {
+    type Error = Infallible;
+}
+
+impl I2c for MockI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [I2cOperation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.addrs.push(address);
+
+        for op in operations {
+            match op {
+                I2cOperation::Write(bytes) => {
+                    if let Some((&reg, value)) = bytes.split_first() {
+                        self.selected = reg as usize;
+                        if let &[hi, lo] = value {
+                            self.registers[self.selected] = u16::from_be_bytes([hi, lo]);
+                        }
+                    }
+                }
+                I2cOperation::Read(buf) => {
+                    let value = self.registers[self.selected].to_be_bytes();
+                    for (dst, src) in buf.iter_mut().zip(value.iter().cycle()) {
+                        *dst = *src;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a [`SH1107Display`](crate::peripherals::SH1107Display) that records into a [`BusLog`]
+///
+/// The log is cleared after initialization, so it only contains what happens after this returns.
+pub fn sh1107_display() -> (
+    crate::peripherals::SH1107Display<MockSpi, MockDcPin>,
+    SharedBusLog,
+) {
+    use crate::peripherals::{SH1107Display, SH1107Driver};
+
+    let (spi, dc, log) = spi_bus();
+    let driver = SH1107Driver::new(spi, dc, &mut MockPin::default(), &mut MockDelay);
+    log.borrow_mut().writes.clear();
+
+    (SH1107Display::new(driver), log)
+}
+
+/// A [`Context`](crate::scene::Context) for driving scenes in tests: no buttons held, at time and frame `0`
+pub fn context(rng: &mut rand::rngs::SmallRng) -> crate::scene::Context<'_> {
+    crate::scene::Context {
+        rng,
+        btn_a: false,
+        btn_b: false,
+        time: 0,
+        frame: 0,
+    }
+}
+
+/// Copies what's in `display`'s framebuffer into a grid the same size
+pub fn snapshot<Device, DataCmdPin>(
+    display: &crate::peripherals::SH1107Display<Device, DataCmdPin>,
+) -> simulations::BitGrid
+where
+    DataCmdPin: embedded_hal::digital::OutputPin,
+    Device: embedded_hal::spi::SpiDevice,
+{
+    let mut grid = simulations::BitGrid::new(display.width() as usize, display.height() as usize);
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            grid.set(x, y, display.get(x, y));
+        }
+    }
+
+    grid
+}
+
+/// Checks `actual` against the golden image at `tests/golden/{name}.pbm`, to catch rendering changes
+///
+/// Goldens are plain (`P1`) PBMs, where set pixels are `1`. That's black in most image viewers, so they show up
+/// inverted compared to the panel.
+///
+/// On a mismatch, `actual` is written to `target/test-images/{name}.pbm` to look at before this panics. If the change
+/// was on purpose, copy it over the golden (or run the test with `UPDATE_GOLDEN=1` to write it there directly).
+#[track_caller]
+pub fn assert_golden(name: &str, actual: &simulations::BitGrid) {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let golden_path = root.join("tests/golden").join(format!("{name}.pbm"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        std::fs::write(&golden_path, to_pbm(actual)).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&golden_path)
+        .ok()
+        .and_then(|pbm| from_pbm(&pbm));
+    if expected.as_ref() == Some(actual) {
+        return;
+    }
+
+    let out_dir = root.join("target/test-images");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let actual_path = out_dir.join(format!("{name}.pbm"));
+    std::fs::write(&actual_path, to_pbm(actual)).unwrap();
+
+    if expected.is_none() {
+        panic!(
+            "Missing or unreadable golden {}, wrote what we got to {}",
+            golden_path.display(),
+            actual_path.display()
+        );
+    } else {
+        panic!(
+            "Rendering doesn't match {}, wrote what we got to {}",
+            golden_path.display(),
+            actual_path.display()
+        );
+    }
+}
+
+/// Writes `grid` as a plain PBM, with one line of text per row
+fn to_pbm(grid: &simulations::BitGrid) -> String {
+    let mut pbm = format!("P1\n{} {}\n", grid.width(), grid.height());
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            pbm.push(if grid.get(x, y) { '1' } else { '0' });
+        }
+        pbm.push('\n');
+    }
+
+    pbm
+}
+
+/// Reads a plain PBM, or returns `None` if it isn't one
+fn from_pbm(pbm: &str) -> Option<simulations::BitGrid> {
+    // Comments run from `#` to the end of the line
+    let mut tokens = pbm
+        .lines()
+        .map(|line| line.split('#').next().unwrap())
+        .flat_map(str::split_whitespace);
+    if tokens.next()? != "P1" {
+        return None;
+    }
+    let width: usize = tokens.next()?.parse().ok()?;
+    let height: usize = tokens.next()?.parse().ok()?;
+
+    // Pixels don't need whitespace between them
+    let pixels: Vec<bool> = tokens
+        .flat_map(str::chars)
+        .map(|c| match c {
+            '0' => Some(false),
+            '1' => Some(true),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if pixels.len() != width * height {
+        return None;
+    }
+
+    let mut grid = simulations::BitGrid::new(width, height);
+    for (i, &is_set) in pixels.iter().enumerate() {
+        grid.set((i % width) as i16, (i / width) as i16, is_set);
+    }
+
+    Some(grid)
+}
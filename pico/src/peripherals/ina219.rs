@@ -76,6 +76,19 @@ where
         self.read(0x04, &mut value) as i16
     }
 
+    /// Reads every register, in register order: config, shunt, bus, power, current, and calibration
+    ///
+    /// This doesn't touch the calibration first (unlike [`Self::current_milliamps()`]), so a chip that has reset itself
+    /// shows up here as a zero calibration value. A chip that isn't responding at all reads back as all zeros.
+    pub fn dump_registers(&mut self) -> [u16; 6] {
+        let mut regs = [0_u16; 6];
+        for (reg, value) in regs.iter_mut().enumerate() {
+            self.read(reg as u8, value);
+        }
+
+        regs
+    }
+
     pub fn write(&mut self, reg: u8, value: u16) {
         let bytes = [
             reg,                    //
@@ -163,3 +176,29 @@ where
         self.write(0x00, config);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockI2c;
+
+    #[test]
+    fn check_dump_registers() {
+        let mut i2c = MockI2c::default();
+        i2c.registers[..6].copy_from_slice(&[0x1111, 0x2222, 0x3333, 0x4444, 0x5555, 0x6666]);
+
+        let mut ina = INA219::new(i2c);
+        let regs = ina.dump_registers();
+
+        // Config and calibration are written by `new()`, everything else is what we put there
+        let config = 0x2000 | 0x1800 | 0x0180 | 0x0068 | 7;
+        assert_eq!(regs, [config, 0x2222, 0x3333, 0x4444, 0x5555, 4096]);
+
+        let (i2c,) = ina.free();
+        assert!(
+            i2c.addrs.iter().all(|&addr| addr == 0x43),
+            "{:x?}",
+            i2c.addrs
+        );
+    }
+}
@@ -1,17 +1,18 @@
 use simulations::BitGrid;
 
-use cortex_m::delay::Delay;
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs as DelayMs;
+use embedded_graphics::mono_font::MonoTextStyle;
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::text::{Baseline, Text};
 use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::SpiDevice;
 
-// TODO: This is our specific model and not general.
-//       We should consider taking these as either const generics on `Display`, or runtime values
-//       The physical dimensions of the screen change across specific parts, but we always have 128x128 bits of memory. I think.
+// The controller always has 128x128 bits of memory, but how many lines of it are on the panel changes across parts.
+// The Pico-OLED-1.3 shows 64 of them. See `SH1107Driver::new_with_height()` for taller panels.
 const WIDTH: u16 = 128;
-const HEIGHT: u16 = 64;
+const DEFAULT_HEIGHT: u16 = 64;
 
 /// A `Display` represents the interface to the Pico-OLED-1.3 `SH1107` Display
 ///
@@ -59,12 +60,37 @@ where
 
     /// Returns whether the pixel at the given coordinate is set or unset.
     ///
-    /// The Display can adjust the set/unset color mapping.
-    /// See [`SH1107Driver::inverse_on`] and [`SH1107Driver::inverse_off`] for more details.
+    /// The Display can adjust the set/unset color mapping. Use [`SH1107Display::displayed_color`] to find out what
+    /// color the pixel actually shows up as.
     pub fn get(&self, x: i16, y: i16) -> bool {
         self.framebuffer.get(x, y)
     }
 
+    /// Returns the color the pixel at the given coordinate shows up as, accounting for inversion mode
+    ///
+    /// [`BinaryColor::On`] is white and [`BinaryColor::Off`] is black.
+    ///
+    /// Like [`SH1107Display::get`], this reads the framebuffer, so it may not match the display until the next flush.
+    pub fn displayed_color(&self, x: i16, y: i16) -> BinaryColor {
+        (self.get(x, y) != self.is_inverted()).into()
+    }
+
+    /// Whether the display is in inverse mode, where set pixels are black and unset pixels are white
+    pub fn is_inverted(&self) -> bool {
+        self.driver.is_inverted()
+    }
+
+    /// Turns inverse mode on or off. See [`SH1107Driver::inverse_on`] for details.
+    ///
+    /// This takes effect immediately, and doesn't change the framebuffer.
+    pub fn set_inverted(&mut self, inverted: bool) {
+        if inverted {
+            self.driver.inverse_on();
+        } else {
+            self.driver.inverse_off();
+        }
+    }
+
     /// Sets the pixel at the given coordinate.
     ///
     /// The Display can adjust the set/unset color mapping.
@@ -103,7 +129,7 @@ where
     ///
     /// This behaves as if `self.set(x, y, false)` was called for every pixel.
     ///
-    /// Whether this is black or white depends on the display's inversion mode. See [`SH1107Driver::inverse_on`].
+    /// Whether this is black or white depends on the display's inversion mode. See [`SH1107Display::is_inverted`].
     pub fn clear_unset(&mut self) {
         let _ = self.clear(BinaryColor::Off);
     }
@@ -112,11 +138,24 @@ where
     ///
     /// This behaves as if `self.set(x, y, true)` was called for every pixel.
     ///
-    /// Whether this is black or white depends on the display's inversion mode. See [`SH1107Driver::inverse_on`].
+    /// Whether this is black or white depends on the display's inversion mode. See [`SH1107Display::is_inverted`].
     pub fn clear_set(&mut self) {
         let _ = self.clear(BinaryColor::On);
     }
 
+    /// Tiles an 8x8 pattern across the whole framebuffer, for dithered "gray" backgrounds
+    ///
+    /// `pattern[y % 8]` is the row used for pixel row `y`, and bit `x % 8` of it (counting from the least significant
+    /// bit) is the pixel in column `x`. So `[0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA]` is a 50% checkerboard.
+    pub fn fill_pattern(&mut self, pattern: [u8; 8]) {
+        for y in 0..self.framebuffer.height() {
+            let row = pattern[y as usize % 8];
+            for x in 0..self.framebuffer.width() {
+                self.framebuffer.set(x, y, (row >> (x % 8)) & 1 != 0);
+            }
+        }
+    }
+
     /// Writes the full state of the framebuffer to the display
     ///
     /// This writes the full state of the framebuffer to the display. After this method returns,
@@ -136,18 +175,101 @@ where
         }
     }
 
+    /// Writes the full state of the framebuffer to the display, one SPI write per page
+    ///
+    /// This has the same result as [`SH1107Display::flush()`], but batches the data for each page into a single
+    /// `SpiDevice::write` instead of sending it a byte at a time. It doesn't use the DMA peripheral (yet), but the
+    /// page-at-a-time layout is what a DMA transfer would want.
+    ///
+    /// The display is rotated, so each page is one row of the framebuffer, and lands in one column of display RAM.
+    pub fn flush_dma(&mut self) {
+        const ROW_BYTES: usize = WIDTH as usize / 8;
+        let mut page = [0_u8; ROW_BYTES];
+
+        self.driver.set_page_addr(0); // ???
+        for (y, row) in self.framebuffer.page_slices(1).enumerate() {
+            // The display wants the right-most pixel first, and the MSB as the left-most pixel of each byte
+            for (dst, src) in page.iter_mut().zip(row.iter().rev()) {
+                *dst = src.reverse_bits();
+            }
+
+            self.driver.set_column_addr(y as u8);
+            self.driver.data_bytes(&page[..row.len()]);
+        }
+    }
+
     /// Writes the full state of the given framebuffer to the display
     ///
     /// This acts like [`SH1107Display::flush()`] but with the provided `image` instead of the stored framebuffer.
     ///
     /// The contents of the Display framebuffer cache are not changed after this call.
     pub fn copy_image(&mut self, image: &BitGrid) {
-        assert_eq!(image.width(), self.framebuffer.width());
-        assert_eq!(image.height(), self.framebuffer.height());
+        if let Err(err) = self.framebuffer.copy_from(image) {
+            panic!("copy_image() needs an image the size of the display: {err}");
+        }
+    }
 
-        self.framebuffer
-            .as_mut_bytes()
-            .copy_from_slice(image.as_bytes());
+    /// Copies `image` into the framebuffer and writes it to the display
+    ///
+    /// This is [`SH1107Display::copy_image()`] followed by [`SH1107Display::flush()`], and like `copy_image()`,
+    /// `image` must be the same size as the display.
+    pub fn present(&mut self, image: &BitGrid) {
+        self.copy_image(image);
+        self.flush();
+    }
+
+    /// Saves the framebuffer under `area`, so a HUD drawn over it can be erased with
+    /// [`SH1107Display::restore_under()`] instead of redrawing everything
+    ///
+    /// Any part of `area` off the display is ignored.
+    pub fn save_under(&self, area: Rectangle) -> SavedArea {
+        let area = area.intersection(&self.bounding_box());
+        let pixels = self.framebuffer.subgrid(
+            area.top_left.x as i16,
+            area.top_left.y as i16,
+            area.size.width as usize,
+            area.size.height as usize,
+        );
+
+        SavedArea {
+            top_left: area.top_left,
+            pixels,
+        }
+    }
+
+    /// Puts back the pixels from [`SH1107Display::save_under()`], leaving the rest of the framebuffer alone
+    pub fn restore_under(&mut self, saved: &SavedArea) {
+        let (x0, y0) = (saved.top_left.x as i16, saved.top_left.y as i16);
+        for y in 0..saved.pixels.height() {
+            for x in 0..saved.pixels.width() {
+                self.framebuffer.set(x0 + x, y0 + y, saved.pixels.get(x, y));
+            }
+        }
+    }
+
+    /// Draws `text` with its top left corner at `top_left`, wrapped (see [`chunk_lines`](crate::chunk_lines)) to
+    /// fit between there and the right edge of the display
+    ///
+    /// Lines that end up off the display are clipped, but still take up space. Returns the y just past the last line,
+    /// which is where the next line would go.
+    pub fn draw_wrapped_text(
+        &mut self,
+        text: &str,
+        top_left: Point,
+        style: MonoTextStyle<'_, BinaryColor>,
+    ) -> i32 {
+        let char_width = style.font.character_size.width + style.font.character_spacing;
+        let chars_per_line = (self.width() as i32 - top_left.x).max(0) as u32 / char_width;
+        let line_height = style.font.character_size.height as i32;
+
+        let mut y = top_left.y;
+        crate::chunk_lines(text, chars_per_line as usize, |line| {
+            let _ = Text::with_baseline(line, Point::new(top_left.x, y), style, Baseline::Top)
+                .draw(self);
+            y += line_height;
+        });
+
+        y
     }
 
     /// Consume the Display object and recover its hal objects.
@@ -158,6 +280,30 @@ where
     pub fn driver(&mut self) -> &mut SH1107Driver<Device, DataCmdPin> {
         &mut self.driver
     }
+
+    /// The framebuffer itself, for filling in bulk (like with [`simulations::Life::copy_into_bitgrid()`])
+    ///
+    /// Like any other drawing, nothing changes on the display until [`SH1107Display::flush()`].
+    pub fn framebuffer_mut(&mut self) -> &mut BitGrid {
+        &mut self.framebuffer
+    }
+}
+
+/// Pixels saved from part of the framebuffer, see [`SH1107Display::save_under()`]
+#[derive(Clone, Debug)]
+pub struct SavedArea {
+    top_left: Point,
+    pixels: BitGrid,
+}
+
+impl SavedArea {
+    /// The part of the display that was saved, clipped to the display
+    pub fn area(&self) -> Rectangle {
+        Rectangle::new(
+            self.top_left,
+            Size::new(self.pixels.width() as u32, self.pixels.height() as u32),
+        )
+    }
 }
 
 // This trait is exposed from `embedded_graphics`
@@ -204,6 +350,7 @@ where
             self.framebuffer.as_mut_bytes().fill(0b0000_0000);
         } else {
             self.framebuffer.as_mut_bytes().fill(0b1111_1111);
+            self.framebuffer.normalize();
         }
 
         Ok(())
@@ -217,6 +364,12 @@ pub struct SH1107Driver<Device, DataCmdPin> {
 
     /// Data/Command Pin to control whether we're writing a command or its data
     dc: DataCmdPin,
+
+    /// Whether inverse mode was last turned on or off
+    inverted: bool,
+
+    /// How many lines the panel shows, either 64 or 128
+    height: u16,
 }
 
 /// Higher level usage of the OLED Display
@@ -226,11 +379,42 @@ where
     DataCmdPin: OutputPin,
 {
     /// Construct a new driver object from its required SPI device and pins.
-    pub fn new<Pin>(dev: Device, dc: DataCmdPin, rst: &mut Pin, delay: &mut Delay) -> Self
+    ///
+    /// This is for the 128x64 Pico-OLED-1.3. Use [`SH1107Driver::new_with_height`] for other panels.
+    pub fn new<Pin, Delay>(dev: Device, dc: DataCmdPin, rst: &mut Pin, delay: &mut Delay) -> Self
     where
         Pin: embedded_hal::digital::OutputPin,
+        Delay: DelayMs<u32>,
     {
-        let mut this = Self { dev, dc };
+        Self::new_with_height(dev, dc, rst, delay, DEFAULT_HEIGHT)
+    }
+
+    /// Construct a new driver object for a panel that shows `height` lines
+    ///
+    /// # Panics
+    /// Panics if `height` isn't `64` or `128`, the two panel sizes we know how to drive.
+    pub fn new_with_height<Pin, Delay>(
+        dev: Device,
+        dc: DataCmdPin,
+        rst: &mut Pin,
+        delay: &mut Delay,
+        height: u16,
+    ) -> Self
+    where
+        Pin: embedded_hal::digital::OutputPin,
+        Delay: DelayMs<u32>,
+    {
+        assert!(
+            height == 64 || height == 128,
+            "SH1107 panels are 64 or 128 lines tall, not {height}"
+        );
+
+        let mut this = Self {
+            dev,
+            dc,
+            inverted: false,
+            height,
+        };
 
         this.reset(rst, delay);
         this.init(delay);
@@ -246,7 +430,12 @@ where
 
     /// The height in pixels of the display
     pub const fn height(&self) -> u16 {
-        HEIGHT
+        self.height
+    }
+
+    /// Whether inverse mode is on. See [`SH1107Driver::inverse_on`].
+    pub const fn is_inverted(&self) -> bool {
+        self.inverted
     }
 
     /// Directly clears the display
@@ -265,7 +454,7 @@ where
 
     /// Consume the Driver object and recover its hal objects.
     pub fn free(self) -> (Device, DataCmdPin) {
-        let Self { dev, dc } = self;
+        let Self { dev, dc, .. } = self;
         (dev, dc)
     }
 }
@@ -329,6 +518,7 @@ where
     /// See: [`SH1107Driver::inverse_on`]
     pub fn inverse_off(&mut self) {
         self.reg(0xA6);
+        self.inverted = false;
     }
 
     /// Enables inverse mode
@@ -343,6 +533,7 @@ where
     /// See: [`SH1107Driver::inverse_off`]
     pub fn inverse_on(&mut self) {
         self.reg(0xA7);
+        self.inverted = true;
     }
 
     /// Turns the display off while keeping other functions active
@@ -408,12 +599,21 @@ where
         let _ = self.dev.write(&[byte]);
     }
 
+    /// Writes a run of bytes over the interface with DC set high, as a single SPI write
+    ///
+    /// Leaves DC set high after returning.
+    #[inline(never)]
+    fn data_bytes(&mut self, bytes: &[u8]) {
+        self.dc.set_high().unwrap();
+        let _ = self.dev.write(bytes);
+    }
+
     /// Resets the display and leaves it ready for commands
     ///
     /// We must call this before any useful interactions can happen.
     ///
     /// There are ~400ms of delays in this function.
-    fn reset<Pin>(&mut self, rst: &mut Pin, delay: &mut Delay)
+    fn reset<Pin>(&mut self, rst: &mut Pin, delay: &mut impl DelayMs<u32>)
     where
         Pin: embedded_hal::digital::OutputPin,
     {
@@ -432,7 +632,7 @@ where
     /// First-time initialization with reasonable defaults
     ///
     /// The init sequence here was ported from the sample code from the Waveshare site.
-    fn init(&mut self, delay: &mut Delay) {
+    fn init(&mut self, delay: &mut impl DelayMs<u32>) {
         self.display_off();
 
         self.set_column_addr(0);
@@ -458,12 +658,13 @@ where
         self.inverse_off();
         // multiplex ratio
         self.reg(0xA8);
-        // duty = 1/64
-        self.reg(0x3F);
+        // duty = 1/height
+        self.reg((self.height - 1) as u8);
 
         // set display offset
         self.reg(0xD3);
-        self.reg(0x60);
+        // The 64 line panel shows the middle of display RAM, and the 128 line panel shows all of it
+        self.reg(if self.height == 128 { 0x00 } else { 0x60 });
 
         // set osc division
         self.reg(0xD5);
@@ -486,3 +687,297 @@ where
         self.display_on();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock;
+
+    use embedded_graphics::mono_font::ascii;
+    use embedded_graphics::primitives::PrimitiveStyle;
+
+    /// Which rows have at least one set pixel
+    fn lit_rows<Device, DataCmdPin>(display: &SH1107Display<Device, DataCmdPin>) -> Vec<i16>
+    where
+        Device: SpiDevice,
+        DataCmdPin: OutputPin,
+    {
+        (0..display.height() as i16)
+            .filter(|&y| (0..display.width() as i16).any(|x| display.get(x, y)))
+            .collect()
+    }
+
+    #[test]
+    fn check_draw_wrapped_text() {
+        let (mut display, _log) = mock::sh1107_display();
+        let style = MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On);
+
+        // 128 - 4 pixels fits 24 characters, so this wraps into 3 lines
+        let text = "The quick brown fox jumps over the lazy dog, twice over!";
+        let end_y = display.draw_wrapped_text(text, Point::new(4, 10), style);
+        assert_eq!(end_y, 10 + 3 * 8);
+
+        // Every line has something in it, and nothing was drawn above or below them
+        let rows = lit_rows(&display);
+        for line in 0..3 {
+            let top = 10 + 8 * line;
+            assert!(
+                rows.iter().any(|y| (top..top + 8).contains(y)),
+                "line {line}: {rows:?}"
+            );
+        }
+        assert!(
+            rows.iter().all(|y| (10..end_y as i16).contains(y)),
+            "{rows:?}"
+        );
+
+        // Nothing is drawn left of the start, and narrower space wraps into more lines (10 characters each here)
+        assert!((0..display.height() as i16).all(|y| (0..4).all(|x| !display.get(x, y))));
+        display.clear_unset();
+        assert_eq!(
+            display.draw_wrapped_text(text, Point::new(78, 0), style),
+            7 * 8
+        );
+
+        // Not even one character fits this close to the edge, so nothing is drawn
+        display.clear_unset();
+        assert_eq!(
+            display.draw_wrapped_text(text, Point::new(125, 20), style),
+            20
+        );
+        assert!(lit_rows(&display).is_empty());
+    }
+
+    #[test]
+    fn check_save_and_restore_under() {
+        let (mut display, _log) = mock::sh1107_display();
+        display.fill_pattern([0x55, 0xAA, 0x33, 0xCC, 0x0F, 0xF0, 0x81, 0x7E]);
+        let before = display.framebuffer.clone();
+
+        let hud = Rectangle::new(Point::new(10, 40), Size::new(50, 20));
+        let saved = display.save_under(hud);
+        assert_eq!(saved.area(), hud);
+
+        let _ = hud
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(&mut display);
+        assert_ne!(display.framebuffer, before);
+
+        display.restore_under(&saved);
+        assert_eq!(display.framebuffer, before);
+    }
+
+    #[test]
+    fn check_save_under_clips() {
+        let (mut display, _log) = mock::sh1107_display();
+        display.fill_pattern([0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA]);
+        let before = display.framebuffer.clone();
+
+        // Hangs off the bottom right corner
+        let saved = display.save_under(Rectangle::new(Point::new(120, 60), Size::new(20, 20)));
+        assert_eq!(
+            saved.area(),
+            Rectangle::new(Point::new(120, 60), Size::new(8, 4))
+        );
+
+        display.clear_set();
+        display.restore_under(&saved);
+        for y in 0..display.height() as i16 {
+            for x in 0..display.width() as i16 {
+                let expected = if x >= 120 && y >= 60 {
+                    before.get(x, y)
+                } else {
+                    true
+                };
+                assert_eq!(display.get(x, y), expected, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn check_draw_wrapped_text_clips() {
+        let (mut display, _log) = mock::sh1107_display();
+        let style = MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On);
+
+        // 10 lines is more than fits on 64 rows, and the first one starts above the top
+        let text = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10";
+        let end_y = display.draw_wrapped_text(text, Point::new(0, -8), style);
+        assert_eq!(end_y, -8 + 10 * 8);
+
+        let rows = lit_rows(&display);
+        assert!(rows.first().unwrap() < &8, "{rows:?}");
+        assert!(rows.last().unwrap() >= &56, "{rows:?}");
+    }
+
+    #[test]
+    fn check_fill_pattern_checkerboard() {
+        let (mut display, log) = mock::sh1107_display();
+        display.fill_pattern([0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA, 0x55, 0xAA]);
+
+        let row_bytes = WIDTH as usize / 8;
+        for (i, &byte) in display.framebuffer.as_bytes().iter().enumerate() {
+            let y = i / row_bytes;
+            let expected = [0x55, 0xAA][y % 2];
+            assert_eq!(byte, expected, "byte {i} (row {y})");
+        }
+        assert_eq!(
+            display.framebuffer.count_ones(),
+            (WIDTH * DEFAULT_HEIGHT / 2) as i32
+        );
+
+        // Only the framebuffer changes until a flush
+        assert!(log.borrow().writes.is_empty());
+    }
+
+    #[test]
+    fn check_fill_pattern_sparse() {
+        let (mut display, _log) = mock::sh1107_display();
+        display.fill_pattern([0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00]);
+
+        let row_bytes = WIDTH as usize / 8;
+        for (i, &byte) in display.framebuffer.as_bytes().iter().enumerate() {
+            let expected = match (i / row_bytes) % 8 {
+                0 => 0x01,
+                4 => 0x10,
+                _ => 0x00,
+            };
+            assert_eq!(byte, expected, "byte {i}");
+        }
+
+        // The pattern tiles from the top left, so pixel (8 * n, 8 * m) is always set
+        assert!(display.get(0, 0));
+        assert!(display.get(8, 16));
+        assert!(display.get(4, 4));
+        assert!(!display.get(1, 0));
+    }
+
+    #[test]
+    fn check_present_matches_copy_and_flush() {
+        let mut image = BitGrid::new(WIDTH as usize, DEFAULT_HEIGHT as usize);
+        for i in 0..DEFAULT_HEIGHT as i16 {
+            image.set(i, i, true);
+            image.set(WIDTH as i16 - 1 - i, i, true);
+        }
+
+        let (mut expected, expected_log) = mock::sh1107_display();
+        expected.copy_image(&image);
+        expected.flush();
+
+        let (mut actual, actual_log) = mock::sh1107_display();
+        actual.present(&image);
+
+        assert!(!expected_log.borrow().writes.is_empty());
+        assert_eq!(actual_log.borrow().writes, expected_log.borrow().writes);
+        assert_eq!(actual.framebuffer, image);
+    }
+
+    #[test]
+    fn check_flush_dma_writes_once_per_page() {
+        let mut image = BitGrid::new(WIDTH as usize, DEFAULT_HEIGHT as usize);
+        for i in 0..DEFAULT_HEIGHT as i16 {
+            image.set(i, i, true);
+            image.set(3 * i % WIDTH as i16, i, true);
+        }
+
+        let (mut bytewise, bytewise_log) = mock::sh1107_display();
+        bytewise.copy_image(&image);
+        bytewise.flush();
+
+        let (mut batched, batched_log) = mock::sh1107_display();
+        batched.copy_image(&image);
+        batched.flush_dma();
+
+        let bytewise_log = bytewise_log.borrow();
+        let batched_log = batched_log.borrow();
+
+        let row_bytes = WIDTH as usize / 8;
+        let data_writes: Vec<_> = batched_log.writes.iter().filter(|w| w.dc).collect();
+        assert_eq!(data_writes.len(), DEFAULT_HEIGHT as usize);
+        assert!(data_writes.iter().all(|w| w.bytes.len() == row_bytes));
+        assert_eq!(
+            bytewise_log.writes.iter().filter(|w| w.dc).count(),
+            WIDTH as usize * DEFAULT_HEIGHT as usize / 8
+        );
+
+        // Same bytes and the same addressing, just fewer writes
+        assert_eq!(batched_log.data(), bytewise_log.data());
+        assert_eq!(batched_log.commands(), bytewise_log.commands());
+    }
+
+    #[test]
+    fn check_128_line_panel() {
+        let (spi, dc, log) = mock::spi_bus();
+        let driver = SH1107Driver::new_with_height(
+            spi,
+            dc,
+            &mut mock::MockPin::default(),
+            &mut mock::MockDelay,
+            128,
+        );
+
+        // Multiplex ratio is one less than the number of lines, and there's no need to offset into display RAM
+        let commands = log.borrow().commands();
+        let mux = commands.iter().position(|&c| c == 0xA8).unwrap();
+        assert_eq!(commands[mux + 1], 0x7F);
+        let offset = commands.iter().position(|&c| c == 0xD3).unwrap();
+        assert_eq!(commands[offset + 1], 0x00);
+
+        let mut display = SH1107Display::new(driver);
+        assert_eq!((display.width(), display.height()), (128, 128));
+        log.borrow_mut().writes.clear();
+
+        display.set(5, 127, true);
+        display.flush_dma();
+
+        let log = log.borrow();
+        let data_writes: Vec<_> = log.writes.iter().filter(|w| w.dc).collect();
+        assert_eq!(data_writes.len(), 128);
+        assert_eq!(log.data().len(), 128 * WIDTH as usize / 8);
+        // (5, 127) is in the last row, which is written last and backwards
+        assert_eq!(data_writes[127].bytes[15], 1 << (7 - 5));
+    }
+
+    #[test]
+    fn check_default_panel_is_64_lines() {
+        let (display, _log) = mock::sh1107_display();
+        assert_eq!((display.width(), display.height()), (128, 64));
+    }
+
+    #[test]
+    #[should_panic(expected = "64 or 128 lines tall")]
+    fn check_other_heights_are_rejected() {
+        let (spi, dc, _log) = mock::spi_bus();
+        let _ = SH1107Driver::new_with_height(
+            spi,
+            dc,
+            &mut mock::MockPin::default(),
+            &mut mock::MockDelay,
+            96,
+        );
+    }
+
+    #[test]
+    fn check_displayed_color_follows_inversion() {
+        let (mut display, log) = mock::sh1107_display();
+        display.set(3, 4, true);
+        assert!(!display.is_inverted());
+
+        assert_eq!(display.displayed_color(3, 4), BinaryColor::On);
+        assert_eq!(display.displayed_color(5, 4), BinaryColor::Off);
+
+        display.set_inverted(true);
+        assert!(display.is_inverted());
+        assert_eq!(log.borrow().commands(), [0xA7]);
+
+        assert!(display.get(3, 4));
+        assert!(!display.get(5, 4));
+        assert_eq!(display.displayed_color(3, 4), BinaryColor::Off);
+        assert_eq!(display.displayed_color(5, 4), BinaryColor::On);
+
+        // Going through the driver is tracked too
+        display.driver().inverse_off();
+        assert!(!display.is_inverted());
+        assert!(display.get(3, 4));
+        assert_eq!(display.displayed_color(3, 4), BinaryColor::On);
+    }
+}
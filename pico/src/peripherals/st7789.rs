@@ -1,8 +1,8 @@
 use crate::{Image, Rgb565, OHNO_PINK};
 
-use cortex_m::delay::Delay;
+use cortex_m::prelude::_embedded_hal_blocking_delay_DelayMs as DelayMs;
 use defmt::Format;
-use embedded_hal::digital::OutputPin;
+use embedded_hal::digital::{InputPin, OutputPin};
 use embedded_hal::spi::{Operation, SpiDevice};
 use proc_bitfield::{bitfield, Bitfield};
 
@@ -34,9 +34,10 @@ where
     Device: SpiDevice,
     DataCmdPin: OutputPin,
 {
-    pub fn new<Pin>(dev: Device, dc: DataCmdPin, rst: &mut Pin, delay: &mut Delay) -> Self
+    pub fn new<Pin, Delay>(dev: Device, dc: DataCmdPin, rst: &mut Pin, delay: &mut Delay) -> Self
     where
         Pin: embedded_hal::digital::OutputPin,
+        Delay: DelayMs<u32>,
     {
         let mut this = Self { dev, dc };
         this.reset(rst, delay);
@@ -54,6 +55,24 @@ where
         self.present_range(0..WIDTH, 0..HEIGHT, image);
     }
 
+    /// Updates the entire display using `image`, after waiting for the start of the panel's vertical blank
+    ///
+    /// `te` is the GPIO wired to the panel's TE (tearing effect) output. The panel must have been told to drive
+    /// it with [`Self::set_tearing_effect`], or this will wait forever.
+    ///
+    /// Writing right as the panel finishes a refresh gives us the most time before its scanout catches up with us,
+    /// which reduces visible tearing.
+    pub fn present_synced<TePin>(&mut self, te: &mut TePin, image: &Image<Rgb565>)
+    where
+        TePin: InputPin,
+    {
+        // Wait for a rising edge, so we don't start halfway through a blanking period
+        while te.is_high().unwrap() {}
+        while te.is_low().unwrap() {}
+
+        self.present(image);
+    }
+
     /// Updates the region of the display specified by the AABB quad (xs, ys) using the same region from `image`
     pub fn present_range(&mut self, xs: Range<u16>, ys: Range<u16>, image: &Image<Rgb565>) {
         // Make sure the image is actually sized right
@@ -66,6 +85,42 @@ where
         self.cmd8(0x2C, image.as_bytes());
     }
 
+    /// Updates only the rows of the display where `new` differs from `old`
+    ///
+    /// `old` should be whatever was presented last. Each run of changed rows is sent as one write, so a few small
+    /// changes cost a few short writes instead of a whole frame.
+    pub fn present_diff(&mut self, new: &Image<Rgb565>, old: &Image<Rgb565>) {
+        assert_eq!((new.width(), new.height()), (WIDTH, HEIGHT));
+        assert_eq!((old.width(), old.height()), (WIDTH, HEIGHT));
+
+        let row_len = 2 * WIDTH as usize;
+        let is_changed = |y: u16| {
+            let row = y as usize * row_len..(y as usize + 1) * row_len;
+            new.as_bytes()[row.clone()] != old.as_bytes()[row]
+        };
+
+        let mut run_start = None;
+        for y in 0..=HEIGHT {
+            if y < HEIGHT && is_changed(y) {
+                run_start.get_or_insert(y);
+            } else if let Some(start) = run_start.take() {
+                self.present_rows(start..y, new);
+            }
+        }
+    }
+
+    /// Updates the full-width rows `ys` of the display using the same rows from `image`
+    fn present_rows(&mut self, ys: Range<u16>, image: &Image<Rgb565>) {
+        let row_len = 2 * WIDTH as usize;
+        self.set_window(0, WIDTH - 1, ys.start, ys.end - 1);
+
+        // RAMWR - Memory Write
+        self.cmd8(
+            0x2C,
+            &image.as_bytes()[ys.start as usize * row_len..ys.end as usize * row_len],
+        );
+    }
+
     /// Updates the entire display to the color `color`
     pub fn clear_to_color(&mut self, color: Rgb565) {
         self.set_window(0, WIDTH - 1, 0, HEIGHT - 1);
@@ -200,6 +255,21 @@ where
         MadCtl::from(u8::from_be_bytes(buf))
     }
 
+    /// Enables or disables the panel's tearing effect output line
+    ///
+    /// When enabled, the TE line is driven high during the vertical blanking period. See [`Self::present_synced`].
+    ///
+    /// TEOFF (34h): Tearing Effect Line OFF
+    /// TEON (35h): Tearing Effect Line On
+    pub fn set_tearing_effect(&mut self, on: bool) {
+        if on {
+            // TEM = 0: V-Blanking information only
+            self.cmd8(0x35, &[0x00]);
+        } else {
+            self.cmd8(0x34, &[]);
+        }
+    }
+
     /// INVOFF (20h): Display Inversion Off
     pub fn inversion_off(&mut self) {
         self.cmd8(0x20, &[]);
@@ -224,13 +294,18 @@ where
     ///
     /// Note that `x_hi` and `y_hi` are **inclusive**.
     ///
-    /// CASET - Column Address Set
+    /// CASET (2Ah): Column Address Set, then RASET (2Bh): Row Address Set
     fn set_window(&mut self, x_lo: u16, x_hi: u16, y_lo: u16, y_hi: u16) {
         self.cmd8(
             0x2A,
             bytemuck::cast_slice(&[
                 x_lo.to_be_bytes(), // X Start
                 x_hi.to_be_bytes(), // X End
+            ]),
+        );
+        self.cmd8(
+            0x2B,
+            bytemuck::cast_slice(&[
                 y_lo.to_be_bytes(), // Y Start
                 y_hi.to_be_bytes(), // Y End
             ]),
@@ -304,7 +379,7 @@ where
         buf[1]
     }
 
-    fn init(&mut self, delay: &mut Delay) {
+    fn init(&mut self, delay: &mut impl DelayMs<u32>) {
         self.write_madctl(
             MadCtl::new()
                 .with_mv(1) //
@@ -386,7 +461,7 @@ where
         }
     }
 
-    fn reset<Pin>(&mut self, rst: &mut Pin, delay: &mut Delay)
+    fn reset<Pin>(&mut self, rst: &mut Pin, delay: &mut impl DelayMs<u32>)
     where
         Pin: embedded_hal::digital::OutputPin,
     {
@@ -402,3 +477,97 @@ where
         delay.delay_ms(100);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn make_display() -> (ST7789Display<MockSpi, MockDcPin>, SharedBusLog) {
+        let (spi, dc, log) = spi_bus();
+        let display = ST7789Display::new(spi, dc, &mut MockPin::default(), &mut MockDelay);
+        log.borrow_mut().writes.clear();
+
+        (display, log)
+    }
+
+    #[test]
+    fn check_tearing_effect_on() {
+        let (mut display, log) = make_display();
+        display.set_tearing_effect(true);
+
+        assert_eq!(log.borrow().commands(), [0x35]);
+        assert_eq!(log.borrow().data(), [0x00]);
+    }
+
+    #[test]
+    fn check_tearing_effect_off() {
+        let (mut display, log) = make_display();
+        display.set_tearing_effect(false);
+
+        assert_eq!(log.borrow().commands(), [0x34]);
+        assert_eq!(log.borrow().data(), []);
+    }
+
+    #[test]
+    fn check_present_synced_waits_for_rising_edge() {
+        let (mut display, log) = make_display();
+        let image = Image::new(WIDTH, HEIGHT);
+
+        // Mid-blank, then scanning out, then the next blank starts
+        let mut te = MockPin::with_levels([true, true, false, false, true]);
+        display.present_synced(&mut te, &image);
+
+        assert_eq!(te.reads, 5);
+        // CASET, RASET, then RAMWR
+        assert_eq!(log.borrow().commands(), [0x2A, 0x2B, 0x2C]);
+    }
+
+    #[test]
+    fn check_present_diff_sends_changed_rows() {
+        let (mut display, log) = make_display();
+        let old = Image::new(WIDTH, HEIGHT);
+        let mut new = Image::new(WIDTH, HEIGHT);
+
+        // One 4x4 big pixel from the life demo
+        new.fill_rect(crate::Rect::new(8, 12, 4, 4), crate::AOC_GOLD);
+        display.present_diff(&new, &old);
+
+        // CASET, RASET, then RAMWR
+        assert_eq!(log.borrow().commands(), [0x2A, 0x2B, 0x2C]);
+
+        let data = log.borrow().data();
+        let (window, pixels) = data.split_at(8);
+        // Columns 0 to 239, then rows 12 to 15
+        assert_eq!(window, [0, 0, 0, 239, 0, 12, 0, 15]);
+
+        // Whole rows are sent, but only the 4 that changed
+        let row_len = 2 * WIDTH as usize;
+        assert_eq!(pixels, &new.as_bytes()[12 * row_len..16 * row_len]);
+    }
+
+    #[test]
+    fn check_present_diff_sends_each_run() {
+        let (mut display, log) = make_display();
+        let old = Image::new(WIDTH, HEIGHT);
+        let mut new = Image::new(WIDTH, HEIGHT);
+
+        new.fill_rect(crate::Rect::new(0, 0, 1, 1), crate::AOC_GOLD);
+        new.fill_rect(
+            crate::Rect::new(100, HEIGHT as i32 - 2, 4, 2),
+            crate::AOC_GOLD,
+        );
+        display.present_diff(&new, &old);
+        assert_eq!(
+            log.borrow().commands(),
+            [0x2A, 0x2B, 0x2C, 0x2A, 0x2B, 0x2C]
+        );
+
+        // Nothing changed, so nothing is sent
+        log.borrow_mut().writes.clear();
+        display.present_diff(&new, &new);
+        assert_eq!(log.borrow().writes, []);
+    }
+}
@@ -32,6 +32,14 @@ impl Tmp36Sensor {
         let tmp36_voltage_24bit: u16 = self.adc.read(&mut self.adc_pin_0).unwrap();
         tmp36_f(tmp36_voltage_24bit)
     }
+
+    /// The raw 12-bit reading from the sensor's pin, for sensors other than a TMP36 wired up in its place
+    ///
+    /// Swapping the TMP36 for a light sensor (like a photoresistor divider) makes this an ambient light reading, see
+    /// [`auto_brightness()`](crate::auto_brightness).
+    pub fn read_raw(&mut self) -> u16 {
+        self.adc.read(&mut self.adc_pin_0).unwrap()
+    }
 }
 
 /// Convert the voltage from a TMP36 sensor into a temperature reading.
@@ -141,6 +141,9 @@ impl Scene for BitflipperScene {
             let dy = self.slope_for_cycle_count(ctx).1;
             let line = alloc::format!("{dx} {dy}");
 
+            let font = &ascii::FONT_5X8;
+            let text_size = crate::text_bounds(&line, font);
+
             let base_y = 48;
             let style_white_border = PrimitiveStyleBuilder::new()
                 .stroke_width(1)
@@ -152,7 +155,7 @@ impl Scene for BitflipperScene {
                 Rectangle::new(
                     Point::new(0, base_y),
                     Size::new(
-                        7 + 5 * line.len() as u32,
+                        text_size.width + 7,
                         display.height() as u32 - base_y as u32 - 2,
                     ),
                 ),
@@ -161,7 +164,7 @@ impl Scene for BitflipperScene {
             .draw_styled(&style_white_border, display);
 
             // Make sure to draw the text ONTOP of the rectangle
-            let style = MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On);
+            let style = MonoTextStyle::new(font, BinaryColor::On);
             let text = Text::new(&line, Point::new(4, base_y + 9), style);
             let _ = text.draw(display);
         }
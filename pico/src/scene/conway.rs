@@ -83,6 +83,25 @@ impl Scene for ConwayScene {
 
         // Draw!
         if needs_refresh {
+            // The board is the size of the display, so copy the whole thing over at once...
+            if let Err(err) = self.sim.copy_into_bitgrid(display.framebuffer_mut()) {
+                panic!("ConwayScene's board needs to be the size of the display: {err}");
+            }
+
+            // ...and then clear a margin for the border
+            let _ = Rectangle::new(
+                Point::new(0, self.base_y),
+                Size::new(self.view_width, self.view_height),
+            )
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(BinaryColor::Off)
+                    .stroke_width(3)
+                    .stroke_alignment(StrokeAlignment::Inside)
+                    .build(),
+            )
+            .draw(display);
+
             // Draw a nice title
             let text = Text::new(
                 "Conway's Game of Life",
@@ -91,14 +110,6 @@ impl Scene for ConwayScene {
             );
             let _ = text.draw(display);
 
-            // Draw our sim "to" the view
-            for y in (self.base_y as i16 + 3)..(self.sim.height() - 3) {
-                for x in 3..(self.sim.width() - 3) {
-                    let is_alive = self.sim.get(x, y);
-                    display.set(x, y, is_alive);
-                }
-            }
-
             // Draw border around our view
             let _ = RoundedRectangle::with_equal_corners(
                 Rectangle::new(
@@ -113,3 +124,117 @@ impl Scene for ConwayScene {
         needs_refresh
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock;
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn check_draws_board_inside_border() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(2533);
+        let mut ctx = mock::context(&mut rng);
+
+        let mut scene = ConwayScene::new(ctx.rng, &display);
+        assert!(scene.update(&mut ctx, &mut display));
+
+        let (w, h) = (display.width() as i16, display.height() as i16);
+        for y in 0..h {
+            for x in 0..w {
+                let is_inner = (3..w - 3).contains(&x) && (3..h - 3).contains(&y);
+                // Away from the rounded corners, which curve into the margin
+                let is_margin = (1..w - 1).contains(&x)
+                    && (1..h - 1).contains(&y)
+                    && ((5..w - 5).contains(&x) || (5..h - 5).contains(&y))
+                    && !is_inner;
+                if is_inner {
+                    assert_eq!(display.get(x, y), scene.sim.get(x, y), "at ({x}, {y})");
+                } else if is_margin {
+                    assert!(!display.get(x, y), "at ({x}, {y})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_frozen_sim_stops_flushing() {
+        let (mut display, log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        // A lone block is a still life, so the sim is frozen from the start
+        let mut conway = ConwayScene::new(ctx.rng, &display);
+        conway.sim =
+            simulations::Life::new(conway.sim.width() as usize, conway.sim.height() as usize);
+        for (x, y) in [(10, 10), (11, 10), (10, 11), (11, 11)] {
+            conway.sim.set(x, y, true);
+        }
+        let mut scene = SleepWhenIdle::new(conway, 5);
+
+        let mut run_frame = |scene: &mut SleepWhenIdle<ConwayScene>, ctx: &mut Context<'_>| {
+            if scene.update(ctx, &mut display) {
+                display.flush();
+            }
+            ctx.frame += 1;
+        };
+
+        for _ in 0..5 {
+            run_frame(&mut scene, &mut ctx);
+        }
+        assert!(scene.is_asleep());
+        // Dimming the panel is the only thing sent
+        assert_eq!(log.borrow().commands(), [0x81, SLEEP_CONTRAST]);
+        assert!(log.borrow().data().is_empty());
+
+        log.borrow_mut().writes.clear();
+        for _ in 0..100 {
+            run_frame(&mut scene, &mut ctx);
+        }
+        assert!(log.borrow().writes.is_empty());
+
+        // Poke the sim, and it wakes back up and starts flushing again
+        ctx.btn_b = true;
+        run_frame(&mut scene, &mut ctx);
+
+        assert!(!scene.is_asleep());
+        let log = log.borrow();
+        assert_eq!(log.commands()[..2], [0x81, 128]);
+        assert!(!log.data().is_empty());
+    }
+
+    #[test]
+    fn check_sleep_restores_contrast() {
+        let (mut display, log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        // An empty board never changes
+        let mut conway = ConwayScene::new(ctx.rng, &display);
+        conway.sim =
+            simulations::Life::new(conway.sim.width() as usize, conway.sim.height() as usize);
+        let mut scene = SleepWhenIdle::new(conway, 5);
+
+        // Awake, so it's sent straight away
+        scene.set_contrast(&mut display, 200);
+        assert_eq!(log.borrow().commands(), [0x81, 200]);
+
+        for _ in 0..5 {
+            scene.update(&mut ctx, &mut display);
+        }
+        assert!(scene.is_asleep());
+
+        // Asleep, so the panel stays dim until it wakes up
+        log.borrow_mut().writes.clear();
+        scene.set_contrast(&mut display, 60);
+        assert!(log.borrow().writes.is_empty());
+
+        ctx.btn_a = true;
+        scene.update(&mut ctx, &mut display);
+        assert!(!scene.is_asleep());
+        assert_eq!(log.borrow().commands(), [0x81, 60]);
+    }
+}
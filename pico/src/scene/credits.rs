@@ -2,7 +2,6 @@ use embedded_graphics::mono_font::{ascii, MonoTextStyle};
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::*;
-use embedded_graphics::text::Text;
 
 use super::{Context, Scene};
 use crate::peripherals::SH1107Display;
@@ -37,7 +36,7 @@ impl Default for CreditsScene {
             indoc!(
                 /*
                 Note: Too many new lines break the thing so use . for empty lines
-                Max width is 24 (used in `chunk_lines` below).
+                Max width is 24 (what `draw_wrapped_text` fits below).
                 That's this long:
                 |----------------------| */
                 r#"
@@ -115,18 +114,15 @@ impl Scene for CreditsScene {
             )
             .draw_styled(&style_white_border, display);
 
-            let mut y = self.base_y;
-            crate::chunk_lines(&self.text, 24, |line| {
-                let text = Text::new(
-                    line,
-                    Point::new(4, y),
-                    MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
-                );
-                let _ = text.draw(display);
-                y += 8
-            });
-
-            let lines = y - self.base_y;
+            // `base_y` is where the first baseline goes, and the font's baseline is 6 pixels down
+            let top = self.base_y - 6;
+            let y = display.draw_wrapped_text(
+                &self.text,
+                Point::new(4, top),
+                MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
+            );
+
+            let lines = y - top;
             self.base_y = self.base_y.max(-lines + 16);
         }
 
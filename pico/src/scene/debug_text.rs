@@ -2,7 +2,6 @@ use embedded_graphics::mono_font::{ascii, MonoTextStyle};
 use embedded_graphics::pixelcolor::BinaryColor;
 use embedded_graphics::prelude::*;
 use embedded_graphics::primitives::*;
-use embedded_graphics::text::Text;
 
 use super::{Context, Scene};
 use crate::peripherals::SH1107Display;
@@ -51,16 +50,11 @@ impl Scene for DebugTextScene {
         )
         .draw_styled(&style_white_border, display);
 
-        let mut y = 6;
-        crate::chunk_lines(&self.text, 24, |line| {
-            let text = Text::new(
-                line,
-                Point::new(4, y),
-                MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
-            );
-            let _ = text.draw(display);
-            y += 8
-        });
+        display.draw_wrapped_text(
+            &self.text,
+            Point::new(4, 0),
+            MonoTextStyle::new(&ascii::FONT_5X8, BinaryColor::On),
+        );
 
         true
     }
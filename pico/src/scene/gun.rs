@@ -0,0 +1,153 @@
+use embedded_graphics::mono_font::{ascii, MonoTextStyle};
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::*;
+use embedded_graphics::text::{Baseline, Text};
+
+use simulations::GliderCounter;
+
+use super::{Context, Scene};
+use crate::peripherals::SH1107Display;
+
+pub struct GunScene {
+    sim: simulations::Life,
+    frames_since_input: u32,
+
+    /// Counts gliders once they're clear of the first gun, for the score in the corner
+    gliders: GliderCounter,
+}
+
+impl GunScene {
+    pub fn new<Device, DataCmdPin>(display: &SH1107Display<Device, DataCmdPin>) -> Self
+    where
+        DataCmdPin: embedded_hal::digital::OutputPin,
+        Device: embedded_hal::spi::SpiDevice,
+    {
+        let mut sim = simulations::Life::new(display.width() as usize, display.height() as usize);
+        sim.write_glider_gun(1, 1);
+
+        Self {
+            sim,
+            frames_since_input: 0,
+            // The gun is 36 cells wide, so this is just past it
+            gliders: GliderCounter::new(40),
+        }
+    }
+}
+
+impl Scene for GunScene {
+    fn update<Device, DataCmdPin>(
+        &mut self,
+        ctx: &mut Context<'_>,
+        display: &mut SH1107Display<Device, DataCmdPin>,
+    ) -> bool
+    where
+        DataCmdPin: embedded_hal::digital::OutputPin,
+        Device: embedded_hal::spi::SpiDevice,
+    {
+        self.frames_since_input = self.frames_since_input.saturating_add(1);
+
+        let btn_a = (self.frames_since_input > 20) && ctx.btn_a;
+        let btn_b = (self.frames_since_input > 20) && ctx.btn_b;
+
+        // Press either button to add another gun somewhere. Gliders wrap around the edges, so the guns
+        // will eventually start shooting each other. Each new gun clears out whatever was in its spot.
+        if btn_a || btn_b {
+            use rand::Rng;
+            self.frames_since_input = 0;
+
+            let x: i16 = ctx.rng.random_range(0..self.sim.width());
+            let y: i16 = ctx.rng.random_range(0..self.sim.height());
+            self.sim.write_glider_gun(x, y);
+        }
+
+        let n_updated = self.sim.step();
+        self.gliders.update(&self.sim);
+        display.copy_image(self.sim.as_bitgrid());
+
+        // Show off how many gliders have been fired, once there are any
+        if self.gliders.count() != 0 {
+            let line = alloc::format!("{}", self.gliders.count());
+            let font = &ascii::FONT_5X8;
+            let text_size = crate::text_bounds(&line, font);
+            let corner = Point::new(
+                display.width() as i32 - text_size.width as i32,
+                display.height() as i32 - text_size.height as i32,
+            );
+
+            let style = MonoTextStyle::new(font, BinaryColor::On);
+            let _ = Text::with_baseline(&line, corner, style, Baseline::Top).draw(display);
+        }
+
+        n_updated != 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock;
+
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn check_gun_keeps_firing() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let mut scene = GunScene::new(&display);
+        for _ in 0..100 {
+            assert!(scene.update(&mut ctx, &mut display));
+        }
+
+        let before = mock::snapshot(&display);
+        // A lone gun is 36 cells, so anything more than that is gliders in flight
+        assert!(before.count_ones() > 36, "{before:?}");
+
+        assert!(scene.update(&mut ctx, &mut display));
+        let after = mock::snapshot(&display);
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn check_buttons_add_guns() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let mut scene = GunScene::new(&display);
+        let mut alone = GunScene::new(&display);
+        for _ in 0..30 {
+            scene.update(&mut ctx, &mut display);
+            alone.update(&mut ctx, &mut display);
+        }
+
+        ctx.btn_b = true;
+        scene.update(&mut ctx, &mut display);
+        ctx.btn_b = false;
+        alone.update(&mut ctx, &mut display);
+
+        assert_ne!(scene.sim.as_bitgrid(), alone.sim.as_bitgrid());
+    }
+
+    #[test]
+    fn check_gliders_are_counted() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let mut scene = GunScene::new(&display);
+        for _ in 0..100 {
+            scene.update(&mut ctx, &mut display);
+        }
+        let count = scene.gliders.count();
+        assert_ne!(count, 0);
+
+        // One more volley later, one more glider
+        for _ in 0..30 {
+            scene.update(&mut ctx, &mut display);
+        }
+        assert_eq!(scene.gliders.count(), count + 1);
+    }
+}
@@ -24,19 +24,43 @@ pub use credits::*;
 mod bad_apple;
 pub use bad_apple::*;
 
+/// A scene that fills the screen with gliders from Gosper glider guns
+mod gun;
+pub use gun::*;
+
+/// A scene that plays a recorded Life video, and then keeps simulating from where it ended
+mod replay;
+pub use replay::*;
+
+/// Wraps another scene to stop flushing and dim the panel when nothing is changing
+mod sleep;
+pub use sleep::*;
+
 /// Information passed to scens with [`Scene::update()`]
 pub struct Context<'a> {
     /// Random Number Generator
     pub rng: &'a mut SmallRng,
 
-    /// Whether or not the A button / Key1 is pressed
+    /// Whether or not the A button is pressed. See [`ButtonMap`](crate::ButtonMap) for which key that is.
     pub btn_a: bool,
 
-    /// Whether or not the B button / Key0 is pressed
+    /// Whether or not the B button is pressed. See [`ButtonMap`](crate::ButtonMap) for which key that is.
     pub btn_b: bool,
 
     /// Time in microseconds since boot, so that scenes can wait
     pub time: u64,
+
+    /// Number of frames the main loop has run, incremented once per [`Scene::update()`]
+    pub frame: u64,
+}
+
+impl Context<'_> {
+    /// Returns true once every `n` frames, for scenes that want to do something periodically
+    ///
+    /// Fires on frame 0, so periodic work also happens right away.
+    pub fn every(&self, n: u64) -> bool {
+        self.frame.is_multiple_of(n)
+    }
 }
 
 /// A trait that describes what actions a Scene might need to do in response to user input
@@ -54,3 +78,52 @@ pub trait Scene {
         DataCmdPin: embedded_hal::digital::OutputPin,
         Device: embedded_hal::spi::SpiDevice;
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock;
+
+    use rand::SeedableRng;
+
+    /// Counts how many times it was asked to do its periodic work
+    struct PeriodicScene {
+        period: u64,
+        fired_on: alloc::vec::Vec<u64>,
+    }
+
+    impl Scene for PeriodicScene {
+        fn update<Device, DataCmdPin>(
+            &mut self,
+            ctx: &mut Context<'_>,
+            _display: &mut SH1107Display<Device, DataCmdPin>,
+        ) -> bool
+        where
+            DataCmdPin: embedded_hal::digital::OutputPin,
+            Device: embedded_hal::spi::SpiDevice,
+        {
+            if ctx.every(self.period) {
+                self.fired_on.push(ctx.frame);
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn check_every_nth_frame() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let mut scene = PeriodicScene {
+            period: 20,
+            fired_on: alloc::vec![],
+        };
+        for _ in 0..100 {
+            scene.update(&mut ctx, &mut display);
+            ctx.frame += 1;
+        }
+
+        assert_eq!(scene.fired_on, [0, 20, 40, 60, 80]);
+    }
+}
@@ -0,0 +1,171 @@
+use image_tools::VideoDecoder;
+use simulations::{BitGrid, Life};
+
+use super::{Context, Scene};
+use crate::peripherals::SH1107Display;
+
+/// Plays a recorded `Life` video, then keeps simulating from its last frame
+///
+/// Each update shows the next frame of the video. Once the video runs out, its last frame becomes the board of a live
+/// [`Life`], and every update after that steps the simulation instead. Videos that aren't the size of the display are
+/// drawn in the top left corner, and the simulation stays the size of the video.
+pub struct ReplayScene {
+    decoder: VideoDecoder<'static>,
+
+    /// The live simulation, once the video has finished
+    sim: Option<Life>,
+}
+
+impl ReplayScene {
+    pub fn new(video: &'static [u8]) -> Self {
+        Self {
+            decoder: VideoDecoder::new(video),
+            sim: None,
+        }
+    }
+
+    /// Whether the video is still playing, rather than the simulation running
+    pub fn is_playing(&self) -> bool {
+        self.sim.is_none()
+    }
+
+    /// The live simulation, once the video has finished
+    pub fn sim(&self) -> Option<&Life> {
+        self.sim.as_ref()
+    }
+
+    /// Takes over from the video with a simulation of its last frame
+    ///
+    /// A video without any frames hands off to a blank board the size of the display.
+    fn hand_off(&mut self, display_dims: (i16, i16)) {
+        let sim = match self.decoder.current_frame() {
+            Some(frame) => Life::from_bitgrid(frame.bitmap.clone()),
+            None => Life::new(display_dims.0 as usize, display_dims.1 as usize),
+        };
+
+        self.sim = Some(sim);
+    }
+}
+
+/// Shows `image` in the top left corner of the display, clearing everything else
+fn draw<Device, DataCmdPin>(display: &mut SH1107Display<Device, DataCmdPin>, image: &BitGrid)
+where
+    DataCmdPin: embedded_hal::digital::OutputPin,
+    Device: embedded_hal::spi::SpiDevice,
+{
+    if image.dims() == (display.width() as i16, display.height() as i16) {
+        display.copy_image(image);
+        return;
+    }
+
+    display.clear_unset();
+    for y in 0..image.height().min(display.height() as i16) {
+        for x in 0..image.width().min(display.width() as i16) {
+            display.set(x, y, image.get(x, y));
+        }
+    }
+}
+
+impl Scene for ReplayScene {
+    fn update<Device, DataCmdPin>(
+        &mut self,
+        _ctx: &mut Context<'_>,
+        display: &mut SH1107Display<Device, DataCmdPin>,
+    ) -> bool
+    where
+        DataCmdPin: embedded_hal::digital::OutputPin,
+        Device: embedded_hal::spi::SpiDevice,
+    {
+        if let Some(sim) = &mut self.sim {
+            let n_updated = sim.step();
+            if n_updated != 0 {
+                draw(display, sim.as_bitgrid());
+            }
+
+            return n_updated != 0;
+        }
+
+        if let Some(frame) = self.decoder.next_frame() {
+            draw(display, frame.bitmap);
+            return true;
+        }
+
+        // The last frame is already on the display, so there's nothing new to show until the first step
+        self.hand_off((display.width() as i16, display.height() as i16));
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock;
+    use crate::mock::snapshot;
+
+    use alloc::vec::Vec;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    /// Records `n_frames` of a `Life` board the size of the display, and returns the video and each frame
+    fn record_life(n_frames: usize) -> (&'static [u8], Vec<BitGrid>) {
+        let mut life = Life::new(128, 64);
+        life.write_glider_gun(1, 1);
+        life.write_right_glider(80, 20);
+
+        let mut frames = Vec::new();
+        for _ in 0..n_frames {
+            frames.push(life.as_bitgrid().clone());
+            life.step();
+        }
+
+        let video = image_tools::encode(frames.iter().cloned()).unwrap();
+        (video.leak(), frames)
+    }
+
+    #[test]
+    fn check_plays_then_simulates() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let (video, frames) = record_life(10);
+        let mut scene = ReplayScene::new(video);
+
+        for frame in &frames {
+            assert!(scene.update(&mut ctx, &mut display));
+            assert!(scene.is_playing());
+            assert_eq!(&snapshot(&display), frame);
+        }
+
+        // Out of video, so the last frame becomes the board...
+        assert!(!scene.update(&mut ctx, &mut display));
+        assert!(!scene.is_playing());
+        let sim = scene.sim().expect("Should be simulating");
+        assert_eq!(sim.as_bitgrid(), frames.last().unwrap());
+        assert_eq!(sim.generation(), 0);
+
+        // ...and it carries on just like the recording would have
+        let mut expected = Life::from_bitgrid(frames.last().unwrap().clone());
+        for _ in 0..5 {
+            expected.step();
+            assert!(scene.update(&mut ctx, &mut display));
+            assert_eq!(snapshot(&display), *expected.as_bitgrid());
+        }
+        assert_eq!(scene.sim().unwrap().generation(), 5);
+    }
+
+    #[test]
+    fn check_empty_video_simulates_blank_board() {
+        let (mut display, _log) = mock::sh1107_display();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut ctx = mock::context(&mut rng);
+
+        let video = image_tools::encode([]).unwrap();
+        let mut scene = ReplayScene::new(video.leak());
+
+        assert!(!scene.update(&mut ctx, &mut display));
+        let sim = scene.sim().expect("Should be simulating");
+        assert_eq!(sim.as_bitgrid().dims(), (128, 64));
+        assert!(sim.as_bitgrid().is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use super::{Context, Scene};
+use crate::peripherals::SH1107Display;
+
+/// How bright the panel is while a scene is asleep. See [`SH1107Driver::set_contrast`](crate::peripherals::SH1107Driver::set_contrast).
+pub const SLEEP_CONTRAST: u8 = 8;
+
+/// Wraps a scene so that it stops flushing and dims the panel once it has stopped changing
+///
+/// Scenes report whether they changed with the return value of [`Scene::update()`]. After `frames_until_sleep`
+/// consecutive updates without a change, the panel is dimmed and every update reports no change, so the main loop
+/// stops flushing. Any change from the inner scene or a button press wakes it back up.
+///
+/// Change the contrast with [`Self::set_contrast()`] rather than on the panel directly, so that it's restored on waking
+/// up and doesn't undo the dimming while asleep.
+pub struct SleepWhenIdle<S> {
+    pub scene: S,
+    frames_until_sleep: u32,
+    idle_frames: u32,
+    asleep: bool,
+
+    /// The contrast to go back to on waking up
+    awake_contrast: u8,
+}
+
+impl<S: Scene> SleepWhenIdle<S> {
+    pub fn new(scene: S, frames_until_sleep: u32) -> Self {
+        Self {
+            scene,
+            frames_until_sleep,
+            idle_frames: 0,
+            asleep: false,
+            // What the panel is initialized with
+            awake_contrast: 128,
+        }
+    }
+
+    /// Sets the panel's contrast for while the scene is awake, like from [`auto_brightness()`](crate::auto_brightness)
+    ///
+    /// This is sent right away when awake. While asleep, the panel stays dimmed and this waits until the scene wakes.
+    pub fn set_contrast<Device, DataCmdPin>(
+        &mut self,
+        display: &mut SH1107Display<Device, DataCmdPin>,
+        contrast: u8,
+    ) where
+        DataCmdPin: embedded_hal::digital::OutputPin,
+        Device: embedded_hal::spi::SpiDevice,
+    {
+        self.awake_contrast = contrast;
+        if !self.asleep {
+            display.driver().set_contrast(contrast);
+        }
+    }
+
+    /// Whether the scene has been idle long enough to dim the panel
+    pub fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+}
+
+impl<S: Scene> Scene for SleepWhenIdle<S> {
+    fn update<Device, DataCmdPin>(
+        &mut self,
+        ctx: &mut Context<'_>,
+        display: &mut SH1107Display<Device, DataCmdPin>,
+    ) -> bool
+    where
+        DataCmdPin: embedded_hal::digital::OutputPin,
+        Device: embedded_hal::spi::SpiDevice,
+    {
+        let changed = self.scene.update(ctx, display);
+
+        if changed || ctx.btn_a || ctx.btn_b {
+            self.idle_frames = 0;
+            if self.asleep {
+                self.asleep = false;
+                display.driver().set_contrast(self.awake_contrast);
+            }
+            return changed;
+        }
+
+        self.idle_frames = self.idle_frames.saturating_add(1);
+        if !self.asleep && self.idle_frames >= self.frames_until_sleep {
+            self.asleep = true;
+            display.driver().set_contrast(SLEEP_CONTRAST);
+        }
+
+        false
+    }
+}
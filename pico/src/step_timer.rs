@@ -0,0 +1,133 @@
+//! Measures how long simulation steps take on the device, when built with the `profile-step` feature
+
+/// Times each call to a simulation's `step()` and reports it over defmt
+///
+/// Times are in microseconds, like the rp2040's `Timer` counter. Without the `profile-step` feature this has no fields,
+/// never reads the clock, and [`Self::time()`] just runs the step, so it's fine to leave in release builds.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepTimer {
+    /// Steps timed since the last report
+    #[cfg(feature = "profile-step")]
+    n_steps: u32,
+
+    /// Total time of every step since the last report
+    #[cfg(feature = "profile-step")]
+    total_usec: u64,
+
+    /// Slowest step since the last report
+    #[cfg(feature = "profile-step")]
+    max_usec: u64,
+}
+
+impl StepTimer {
+    /// How many steps to average over for each report, so the log doesn't slow things down itself
+    pub const REPORT_EVERY: u32 = 60;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `step`, timing it with `now` when profiling, and returns whatever it returns
+    pub fn time<R>(&mut self, now: impl Fn() -> u64, step: impl FnOnce() -> R) -> R {
+        #[cfg(feature = "profile-step")]
+        {
+            let start = now();
+            let ret = step();
+            self.record(now().saturating_sub(start));
+            ret
+        }
+
+        #[cfg(not(feature = "profile-step"))]
+        {
+            let _ = now;
+            step()
+        }
+    }
+
+    /// Average time per step since the last report, if anything has been timed
+    #[cfg(feature = "profile-step")]
+    pub fn average_usec(&self) -> Option<u64> {
+        (self.n_steps != 0).then(|| self.total_usec / self.n_steps as u64)
+    }
+
+    #[cfg(feature = "profile-step")]
+    fn record(&mut self, usec: u64) {
+        self.n_steps += 1;
+        self.total_usec += usec;
+        self.max_usec = self.max_usec.max(usec);
+
+        if self.n_steps < Self::REPORT_EVERY {
+            return;
+        }
+
+        // There's no defmt logger on the host, so tests only check the numbers
+        #[cfg(target_os = "none")]
+        defmt::debug!(
+            "step: avg {=u64} us, max {=u64} us over {=u32} steps",
+            self.total_usec / self.n_steps as u64,
+            self.max_usec,
+            self.n_steps
+        );
+
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use core::cell::Cell;
+
+    #[test]
+    #[cfg(not(feature = "profile-step"))]
+    fn check_disabled_is_a_no_op() {
+        assert_eq!(core::mem::size_of::<StepTimer>(), 0);
+
+        let mut timer = StepTimer::new();
+        let reads = Cell::new(0);
+        let now = || {
+            reads.set(reads.get() + 1);
+            0
+        };
+
+        let mut n_steps = 0;
+        for i in 0..100_u32 {
+            assert_eq!(timer.time(now, || i * 2), i * 2);
+            timer.time(now, || n_steps += 1);
+        }
+
+        assert_eq!(n_steps, 100);
+        assert_eq!(reads.get(), 0, "Read the clock without profiling enabled");
+        assert_eq!(timer, StepTimer::new());
+    }
+
+    #[test]
+    #[cfg(feature = "profile-step")]
+    fn check_enabled_averages_steps() {
+        let mut timer = StepTimer::new();
+        assert_eq!(timer.average_usec(), None);
+
+        // Steps only take time when they move the clock along
+        let clock = Cell::new(0_u64);
+        let n_reads = Cell::new(0);
+        let now = || {
+            n_reads.set(n_reads.get() + 1);
+            clock.get()
+        };
+        let step = |usec: u64| clock.set(clock.get() + usec);
+
+        assert_eq!(timer.time(now, || 7), 7);
+        timer.time(now, || step(10));
+        timer.time(now, || step(20));
+        timer.time(now, || step(30));
+        assert_eq!(timer.average_usec(), Some(15));
+        assert_eq!(n_reads.get(), 8);
+
+        // Reporting starts the averages over
+        for _ in 4..StepTimer::REPORT_EVERY {
+            timer.time(now, || ());
+        }
+        assert_eq!(timer.average_usec(), None);
+    }
+}
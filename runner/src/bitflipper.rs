@@ -1,6 +1,9 @@
 use minifb::{Key, KeyRepeat, Scale, ScaleMode, Window, WindowOptions};
 use rand::prelude::*;
 
+use runner::Playback;
+use simulations::BitGrid;
+
 pub const AOC_BLUE: u32 = 0x0f_0f_23;
 pub const AOC_GOLD: u32 = 0xff_ff_66;
 
@@ -25,14 +28,30 @@ fn rand_dxdy(width: usize, height: usize) -> (i32, i32) {
     (dx / g, dy / g)
 }
 
+/// The window title, with the slope the sim started with (like the OLED scene shows) and how fast it's running
+fn title(sim: &simulations::BitFlipper, speed: i32) -> String {
+    let (dx, dy) = sim.slope();
+    let (width, height) = sim.bits.dims();
+    format!(
+        "👾 Pico BitFlipper~! {width}x{height} ({dx}, {dy}) speed={speed}",
+        dx = dx.abs(),
+        dy = dy.abs()
+    )
+}
+
 fn main() {
     // TODO: Drive these with clap
     const WIDTH: usize = 128;
     const HEIGHT: usize = 64;
 
+    let (dx, dy) = rand_dxdy(WIDTH, HEIGHT);
+    println!("New Sim: {dx}, {dy}");
+    let mut sim = simulations::BitFlipper::new(WIDTH as i32, HEIGHT as i32, dx, dy);
+
     let mut pixels = vec![AOC_BLUE; WIDTH * HEIGHT];
+    let mut frame = BitGrid::new(WIDTH, HEIGHT);
     let mut window = Window::new(
-        &format!("👾 Pico BitFlipper~! {WIDTH}x{HEIGHT}"),
+        &title(&sim, 1),
         WIDTH,
         HEIGHT,
         WindowOptions {
@@ -49,27 +68,25 @@ fn main() {
     // TODO: We should query the display's preferred refresh rate instead of assuming 60
     window.set_target_fps(60);
 
-    let (dx, dy) = rand_dxdy(WIDTH, HEIGHT);
-    println!("New Sim: {dx}, {dy}");
-    let mut sim = simulations::BitFlipper::new(WIDTH as i32, HEIGHT as i32, dx, dy);
-
     let palette = [
         AOC_BLUE, // dead
         AOC_GOLD, // alive
     ];
 
-    let mut is_running = true;
+    let mut playback = Playback::new();
     let mut speed: i32 = 1;
 
     while window.is_open() {
         // We don't want to update the framebuffer unless the sim changed.
         let mut cells_were_updated = false;
+        let old_speed = speed;
 
         if window.is_key_pressed(Key::Escape, KeyRepeat::No) {
             break;
         }
-        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
-            is_running ^= true;
+        let was_running = playback.is_running();
+        playback.poll(&window);
+        if playback.is_running() != was_running {
             println!("+ speed={speed}");
             cells_were_updated = true;
         }
@@ -81,6 +98,7 @@ fn main() {
             let (dx, dy) = rand_dxdy(WIDTH, HEIGHT);
             sim = simulations::BitFlipper::new(WIDTH as i32, HEIGHT as i32, dx, dy);
             println!("New Sim: {dx}, {dy}");
+            window.set_title(&title(&sim, speed));
             cells_were_updated = true;
         }
 
@@ -103,9 +121,20 @@ fn main() {
             }
         }
 
-        if is_running {
+        if speed != old_speed {
+            window.set_title(&title(&sim, speed));
+        }
+
+        if playback.should_step() {
+            // A single step while paused flips just once, in whichever direction we're going
+            let n_steps = if playback.is_running() {
+                speed.abs()
+            } else {
+                1
+            };
+
             // TODO: We should update every N ms, not every frame.
-            for _ in 0..speed.abs() {
+            for _ in 0..n_steps {
                 sim.flip_and_advance(speed.signum());
             }
             cells_were_updated = true;
@@ -115,10 +144,11 @@ fn main() {
         if cells_were_updated {
             // TODO: We could dirty track ranges to speed up low-life simulation frames.
             //       This quickly turns into quad-tree dirty state tracking.
-            for y in 0..sim.bits.height() {
-                for x in 0..sim.bits.width() {
+            sim.render_into(&mut frame);
+            for y in 0..frame.height() {
+                for x in 0..frame.width() {
                     let idx = (x as usize) + (y as usize) * WIDTH;
-                    pixels[idx] = palette[sim.bits.get(x, y) as usize];
+                    pixels[idx] = palette[frame.get(x, y) as usize];
                 }
             }
         }
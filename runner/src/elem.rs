@@ -1,14 +1,28 @@
 use minifb::{Key, KeyRepeat, Scale, ScaleMode, Window, WindowOptions};
-use rand::{rngs::SmallRng, RngCore, SeedableRng};
+use rand::{rngs::SmallRng, SeedableRng};
+use simulations::{BitGrid, Seed};
+
+use runner::record::{save_recording, Recorder};
+use runner::Playback;
 
 pub const AOC_BLUE: u32 = 0x0f_0f_23;
 pub const AOC_GOLD: u32 = 0xff_ff_66;
 
 fn main() {
     // TODO: Drive these with clap
-    let width: usize = 192;
-    // With rule 90 + wrapping this rocks!
-    // let width: usize = 192 + 3;
+    // Extra cells on top of the usual width, from the second argument: `elem 90 3`
+    //
+    // The row wraps around, so rule 90's triangle grows around the back and its two edges meet again. On an even width
+    // they land on the same cell and cancel out, which keeps the picture tidy (and a power of two wide dies out
+    // entirely). On an odd width they land one cell apart and keep tangling with each other instead, which is much
+    // more fun to watch.
+    let extra_width: usize = std::env::args()
+        .nth(2)
+        .as_deref()
+        .unwrap_or("0")
+        .parse()
+        .unwrap();
+    let width: usize = 192 + extra_width;
     let height: usize = 128;
     let scale = Scale::X8;
 
@@ -54,13 +68,14 @@ fn main() {
         AOC_GOLD, // alive
     ];
 
-    let mut is_running = true;
+    let mut playback = Playback::new();
     let mut rng = SmallRng::from_seed(core::array::from_fn(|_| 7));
 
     let mut curr_y = 0;
+    let mut recorder = Recorder::new();
 
     // Initial state sets 1 cell
-    sim.set(width as i16 / 2, true);
+    sim.seed(Seed::SingleCenter, &mut rng);
     pixels[width / 2] = palette[1];
 
     while window.is_open() {
@@ -70,8 +85,20 @@ fn main() {
             break;
         }
 
-        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
-            is_running ^= true;
+        playback.poll(&window);
+
+        // Shift+R records, plain R resets to random cells
+        let shift = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        if shift && window.is_key_pressed(Key::R, KeyRepeat::No) {
+            if let Some(frames) = recorder.toggle() {
+                let n_frames = frames.len();
+                match save_recording(".", &format!("elem-{rule}"), frames) {
+                    Ok(path) => println!("[INFO] Saved {n_frames} frames to {}", path.display()),
+                    Err(err) => println!("[ERROR] Failed to save recording: {err}"),
+                }
+            } else {
+                println!("[INFO] Recording started");
+            }
         }
 
         // We don't want to update the framebuffer unless the sim changed.
@@ -89,31 +116,28 @@ fn main() {
 
             cells_were_updated = true;
             curr_y = 0;
-        } else if window.is_key_pressed(Key::R, KeyRepeat::No) {
-            for x in 0..sim.width() {
-                sim.set(x, rng.next_u32() % 2 == 0);
-            }
-            pixels.fill(palette[0]);
-
-            cells_were_updated = true;
-            curr_y = 0;
-        } else if window.is_key_pressed(Key::G, KeyRepeat::No) {
-            sim.clear();
+        } else if let Some(preset) = [
+            (Key::R, Seed::Random(0.5)),
+            (Key::G, Seed::SingleCenter),
+            (Key::L, Seed::SingleLeft),
+            (Key::A, Seed::Alternating),
+        ]
+        .into_iter()
+        .find(|&(key, _)| !shift && window.is_key_pressed(key, KeyRepeat::No))
+        .map(|(_, preset)| preset)
+        {
+            sim.seed(preset, &mut rng);
             pixels.fill(palette[0]);
 
-            sim.set(width as i16 / 2, true);
-            pixels[width / 2] = palette[1];
-
             cells_were_updated = true;
             curr_y = 0;
         }
 
-        if is_running && !cells_were_updated {
+        if !cells_were_updated && playback.should_step() {
             // TODO: We should update every N ms, not every frame.
             let updated = sim.step();
 
             cells_were_updated |= updated != 0;
-            is_running |= updated != 0;
             curr_y += 1;
         }
 
@@ -149,6 +173,11 @@ fn main() {
             }
         }
 
+        // Rule sims only have one row, so record what's on screen instead
+        if recorder.is_recording() && cells_were_updated {
+            recorder.push(&framebuffer_to_bitgrid(&pixels, width, height, palette[1]));
+        }
+
         // Present the framebuffer, updated or otherwise, to the screen
         match window.update_with_buffer(&pixels, width, height) {
             Ok(()) => {}
@@ -158,3 +187,13 @@ fn main() {
         }
     }
 }
+
+fn framebuffer_to_bitgrid(pixels: &[u32], width: usize, height: usize, alive: u32) -> BitGrid {
+    let mut grid = BitGrid::new(width, height);
+    for (idx, &pixel) in pixels.iter().enumerate() {
+        let x = (idx % width) as i16;
+        let y = (idx / width) as i16;
+        grid.set(x, y, pixel == alive);
+    }
+    grid
+}
@@ -0,0 +1,9 @@
+//! Bits shared between the desktop runners
+//!
+//! The runners themselves are all window plumbing, so anything worth testing lives here.
+
+pub mod playback;
+pub use playback::{Playback, PlaybackKey};
+
+pub mod record;
+pub use record::Recorder;
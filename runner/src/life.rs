@@ -1,6 +1,9 @@
 use minifb::{Key, KeyRepeat, Scale, ScaleMode, Window, WindowOptions};
 use rand::{rngs::SmallRng, SeedableRng};
 
+use runner::record::{save_recording, Recorder};
+use runner::Playback;
+
 pub const AOC_BLUE: u32 = 0x0f_0f_23;
 pub const AOC_GOLD: u32 = 0xff_ff_66;
 
@@ -40,8 +43,9 @@ fn main() {
         AOC_GOLD, // alive
     ];
 
-    let mut is_running = true;
+    let mut playback = Playback::new();
     let mut rng = SmallRng::from_seed(core::array::from_fn(|_| 7));
+    let mut recorder = Recorder::new();
 
     while window.is_open() {
         if window.is_key_pressed(Key::Escape, KeyRepeat::No)
@@ -50,8 +54,12 @@ fn main() {
             break;
         }
 
-        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
-            is_running ^= true;
+        playback.poll(&window);
+
+        // Shift+R records, plain R resets to random cells
+        let shift = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        if shift && window.is_key_pressed(Key::R, KeyRepeat::No) {
+            toggle_recording(&mut recorder, &life);
         }
 
         // We don't want to update the framebuffer unless the sim changed.
@@ -61,9 +69,16 @@ fn main() {
             life.clear();
 
             cells_were_updated = true;
-        } else if window.is_key_pressed(Key::R, KeyRepeat::No) {
+        } else if !shift && window.is_key_pressed(Key::R, KeyRepeat::No) {
             life.clear_random(&mut rng);
 
+            cells_were_updated = true;
+        } else if shift && window.is_key_pressed(Key::G, KeyRepeat::No) {
+            life.clear();
+
+            // Gliders everywhere, spaced out enough that they don't interfere
+            life.fill_glider_field(8);
+
             cells_were_updated = true;
         } else if window.is_key_pressed(Key::G, KeyRepeat::No) {
             life.clear();
@@ -76,9 +91,10 @@ fn main() {
             cells_were_updated = true;
         }
 
-        if is_running {
+        if playback.should_step() {
             // TODO: We should update every N ms, not every frame.
             cells_were_updated |= life.step() != 0;
+            recorder.push(life.as_bitgrid());
         }
 
         // Copy any updated cells to the framebuffer
@@ -102,3 +118,20 @@ fn main() {
         }
     }
 }
+
+fn toggle_recording(recorder: &mut Recorder, life: &simulations::Life) {
+    match recorder.toggle() {
+        None => {
+            // Start with the frame we're looking at, not the one after it
+            recorder.push(life.as_bitgrid());
+            println!("[INFO] Recording started");
+        }
+        Some(frames) => {
+            let n_frames = frames.len();
+            match save_recording(".", "life", frames) {
+                Ok(path) => println!("[INFO] Saved {n_frames} frames to {}", path.display()),
+                Err(err) => println!("[ERROR] Failed to save recording: {err}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+/// Input a runner forwards to [`Playback`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlaybackKey {
+    /// Pauses a running sim, or resumes a paused one
+    TogglePause,
+
+    /// Advances a paused sim by exactly one step
+    Step,
+}
+
+/// Tracks whether a runner should step its sim this frame
+///
+/// Runners forward key presses with [`Playback::press()`], then ask [`Playback::should_step()`] once per frame.
+#[derive(Clone, Debug)]
+pub struct Playback {
+    is_running: bool,
+    step_queued: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Playback {
+    /// Starts running, like every runner does
+    pub fn new() -> Self {
+        Self {
+            is_running: true,
+            step_queued: false,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.is_running
+    }
+
+    pub fn press(&mut self, key: PlaybackKey) {
+        match key {
+            PlaybackKey::TogglePause => {
+                self.is_running ^= true;
+                // A step asked for before resuming would be lost in the crowd anyway
+                self.step_queued = false;
+            }
+            // Running sims already step every frame, so there's nothing to queue
+            PlaybackKey::Step => self.step_queued = !self.is_running,
+        }
+    }
+
+    /// Presses whichever playback keys went down this frame
+    ///
+    /// Space pauses and resumes, and `.` or `N` single-steps (holding either keeps stepping).
+    pub fn poll(&mut self, window: &minifb::Window) {
+        use minifb::{Key, KeyRepeat};
+
+        if window.is_key_pressed(Key::Space, KeyRepeat::No) {
+            self.press(PlaybackKey::TogglePause);
+        }
+        if window.is_key_pressed(Key::Period, KeyRepeat::Yes)
+            || window.is_key_pressed(Key::N, KeyRepeat::Yes)
+        {
+            self.press(PlaybackKey::Step);
+        }
+    }
+
+    /// Whether to step the sim this frame
+    ///
+    /// This consumes a queued single step, so call it at most once per frame.
+    pub fn should_step(&mut self) -> bool {
+        self.is_running || core::mem::take(&mut self.step_queued)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    use PlaybackKey::*;
+
+    /// Runs one frame per entry of `frames`, pressing each key in it before asking whether to step
+    fn run(frames: &[&[PlaybackKey]]) -> Vec<bool> {
+        let mut playback = Playback::new();
+        frames
+            .iter()
+            .map(|keys| {
+                for &key in *keys {
+                    playback.press(key);
+                }
+                playback.should_step()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_runs_until_paused() {
+        assert_eq!(
+            run(&[&[], &[], &[TogglePause], &[], &[TogglePause], &[]]),
+            [true, true, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn check_step_while_paused() {
+        assert_eq!(
+            run(&[&[TogglePause], &[Step], &[], &[], &[Step], &[Step], &[]]),
+            [false, true, false, false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn check_step_only_once_per_frame() {
+        assert_eq!(
+            run(&[&[TogglePause], &[Step, Step, Step], &[]]),
+            [false, true, false]
+        );
+    }
+
+    #[test]
+    fn check_step_while_running_is_ignored() {
+        // The step doesn't wait around to fire after pausing
+        assert_eq!(run(&[&[Step], &[TogglePause], &[]]), [true, false, false]);
+    }
+
+    #[test]
+    fn check_resume_drops_queued_step() {
+        assert_eq!(
+            run(&[&[TogglePause], &[Step, TogglePause, TogglePause], &[]]),
+            [false, false, false]
+        );
+    }
+}
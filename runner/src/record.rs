@@ -0,0 +1,134 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use simulations::BitGrid;
+
+/// Collects frames from a running simulation, to be encoded with [`image_tools::VideoEncoder`] when it stops
+///
+/// Frames are only kept while recording, so a runner can call [`Recorder::push()`] every step unconditionally.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    frames: Option<Vec<BitGrid>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.frames.is_some()
+    }
+
+    /// Number of frames recorded so far, or `0` when not recording
+    pub fn frame_count(&self) -> usize {
+        self.frames.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Starts recording, or stops and returns everything recorded since it started
+    ///
+    /// Returns `None` when this call started a recording.
+    pub fn toggle(&mut self) -> Option<Vec<BitGrid>> {
+        match self.frames.take() {
+            Some(frames) => Some(frames),
+            None => {
+                self.frames = Some(vec![]);
+                None
+            }
+        }
+    }
+
+    /// Records a copy of `frame`, if we're recording
+    pub fn push(&mut self, frame: &BitGrid) {
+        if let Some(frames) = &mut self.frames {
+            frames.push(frame.clone());
+        }
+    }
+}
+
+/// Encodes `frames` into a new `.bin` video in `dir`, named after the runner and the current time
+///
+/// Returns the path that was written.
+pub fn save_recording(
+    dir: impl AsRef<Path>,
+    name: &str,
+    frames: Vec<BitGrid>,
+) -> io::Result<PathBuf> {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = dir.as_ref().join(format!("{name}-{secs}.bin"));
+
+    let mut encoder = image_tools::VideoEncoder::new();
+    for frame in frames {
+        encoder.push(frame);
+    }
+    encoder.encode_to_path(&path)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn frame(n: i16) -> BitGrid {
+        let mut grid = BitGrid::new(8, 4);
+        grid.set(n, 0, true);
+        grid
+    }
+
+    #[test]
+    fn check_ignores_frames_until_started() {
+        let mut recorder = Recorder::new();
+        recorder.push(&frame(0));
+        recorder.push(&frame(1));
+
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.frame_count(), 0);
+    }
+
+    #[test]
+    fn check_toggle_collects_frames_in_order() {
+        let mut recorder = Recorder::new();
+        recorder.push(&frame(0));
+
+        assert_eq!(recorder.toggle(), None);
+        assert!(recorder.is_recording());
+
+        recorder.push(&frame(1));
+        recorder.push(&frame(2));
+        recorder.push(&frame(3));
+        assert_eq!(recorder.frame_count(), 3);
+
+        let frames = recorder.toggle().unwrap();
+        assert_eq!(frames, vec![frame(1), frame(2), frame(3)]);
+
+        // Stopping leaves it ready for a fresh recording
+        assert!(!recorder.is_recording());
+        recorder.push(&frame(4));
+        assert_eq!(recorder.toggle(), None);
+        recorder.push(&frame(5));
+        assert_eq!(recorder.toggle(), Some(vec![frame(5)]));
+    }
+
+    #[test]
+    fn check_save_recording_decodes() {
+        let out_dir = "./target/test-output";
+        std::fs::create_dir_all(out_dir).unwrap();
+
+        let frames = vec![frame(1), frame(2), frame(3)];
+        let path = save_recording(out_dir, "check_save_recording", frames.clone()).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let mut decoder = image_tools::VideoDecoder::new(&bytes);
+        let mut decoded = vec![];
+        while let Some(frame) = decoder.next_frame() {
+            decoded.push(frame.bitmap.clone());
+        }
+
+        assert_eq!(decoded, frames);
+    }
+}
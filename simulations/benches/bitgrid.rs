@@ -0,0 +1,90 @@
+//! Compares `BitGrid`'s checked and unchecked accessors
+//!
+//! Run with `cargo bench --bench bitgrid`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use simulations::BitGrid;
+
+const WIDTH: i16 = 128;
+const HEIGHT: i16 = 64;
+const ROUNDS: u32 = 500;
+
+fn time(label: &str, mut f: impl FnMut() -> u32) -> Duration {
+    // Warm up
+    black_box(f());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{label:>16}: {:>10.2?} per pass over {WIDTH}x{HEIGHT}",
+        elapsed / ROUNDS
+    );
+    elapsed
+}
+
+fn main() {
+    let mut grid = BitGrid::new(WIDTH as usize, HEIGHT as usize);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            grid.set(x, y, (x ^ y) % 3 == 0);
+        }
+    }
+    let grid = black_box(grid);
+
+    let checked = time("get", || {
+        let mut count = 0;
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                count += grid.get(x, y) as u32;
+            }
+        }
+        count
+    });
+
+    let unchecked = time("get_unchecked", || {
+        let mut count = 0;
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                // SAFETY: x and y are in bounds
+                count += unsafe { grid.get_unchecked(x, y) } as u32;
+            }
+        }
+        count
+    });
+
+    let mut out = BitGrid::new(WIDTH as usize, HEIGHT as usize);
+    let set_checked = time("set", || {
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                out.set(x, y, (x & y) != 0);
+            }
+        }
+        out.count_ones() as u32
+    });
+
+    let set_unchecked = time("set_unchecked", || {
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                // SAFETY: x and y are in bounds
+                unsafe { out.set_unchecked(x, y, (x & y) != 0) };
+            }
+        }
+        out.count_ones() as u32
+    });
+
+    println!();
+    println!(
+        "get speedup: {:.2}x",
+        checked.as_secs_f64() / unchecked.as_secs_f64()
+    );
+    println!(
+        "set speedup: {:.2}x",
+        set_checked.as_secs_f64() / set_unchecked.as_secs_f64()
+    );
+}
@@ -0,0 +1,31 @@
+//! Times `Life::step()` on a board full of gliders
+//!
+//! Run with `cargo bench --bench life`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use simulations::Life;
+
+const WIDTH: usize = 128;
+const HEIGHT: usize = 64;
+const STEPS: u32 = 500;
+
+fn main() {
+    let mut life = Life::new(WIDTH, HEIGHT);
+    let n_gliders = life.fill_glider_field(8);
+
+    // Warm up
+    black_box(life.step());
+
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        black_box(life.step());
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "step: {:>10.2?} per step over {WIDTH}x{HEIGHT} with {n_gliders} gliders",
+        elapsed / STEPS
+    );
+}
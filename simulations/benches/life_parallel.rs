@@ -0,0 +1,49 @@
+//! Times `Life::step_parallel()` against `Life::step()` on a big random board, at a few thread counts
+//!
+//! Run with `cargo bench --bench life_parallel --features rayon`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::SmallRng, SeedableRng};
+use simulations::Life;
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 256;
+const STEPS: u32 = 100;
+
+fn time_steps(life: &mut Life, mut step: impl FnMut(&mut Life) -> u32) -> Duration {
+    // Warm up
+    black_box(step(life));
+
+    let start = Instant::now();
+    for _ in 0..STEPS {
+        black_box(step(life));
+    }
+
+    start.elapsed() / STEPS
+}
+
+fn main() {
+    let mut board = Life::new(WIDTH, HEIGHT);
+    board.clear_random(&mut SmallRng::seed_from_u64(2527));
+
+    let serial = time_steps(&mut board.clone(), Life::step);
+    println!("step:          {serial:>10.2?} per step over {WIDTH}x{HEIGHT}");
+
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut n_threads = 1;
+    while n_threads <= max_threads {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .build()
+            .unwrap();
+        let parallel = pool.install(|| time_steps(&mut board.clone(), Life::step_parallel));
+        println!(
+            "step_parallel: {parallel:>10.2?} per step with {n_threads:>2} threads ({:.2}x)",
+            serial.as_secs_f64() / parallel.as_secs_f64()
+        );
+
+        n_threads *= 2;
+    }
+}
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# Checks that `simulations` builds for every target we use it on.
+#
+# Run from anywhere: ./simulations/scripts/ci_checks.sh
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+# The pico build, without std
+cargo build --no-default-features --target thumbv6m-none-eabi
+
+# The browser build, as a library and as a loadable .wasm
+cargo build --target wasm32-unknown-unknown --features wasm
+cargo rustc --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+
+cargo test --features wasm,serde
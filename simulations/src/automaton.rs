@@ -0,0 +1,223 @@
+use rand::RngCore;
+
+use crate::{BitFlipper, BitGrid, Elementry, Life};
+
+/// The parts of every simulation that a scene or runner needs, so one of them can host any simulation
+///
+/// This is object safe, so a `Vec<Box<dyn Automaton>>` can hold a mix of simulations to switch between.
+pub trait Automaton {
+    /// Advances the simulation once, returning how many cells changed
+    fn step(&mut self) -> u32;
+
+    /// Replaces every cell with random ones from `rng`
+    fn reset_random(&mut self, rng: &mut dyn RngCore);
+
+    /// Marks every cell as unset
+    fn clear(&mut self);
+
+    /// Draws the current state into `dst`, starting from the top left corner
+    ///
+    /// Cells that don't fit are clipped, and any part of `dst` past the simulation is left alone.
+    fn render_into(&self, dst: &mut BitGrid);
+}
+
+/// Copies as much of `src` as fits into the top left corner of `dst`
+pub(crate) fn copy_clipped(src: &BitGrid, dst: &mut BitGrid) {
+    if dst.copy_from(src).is_ok() {
+        return;
+    }
+
+    for y in 0..src.height().min(dst.height()) {
+        for x in 0..src.width().min(dst.width()) {
+            dst.set(x, y, src.get(x, y));
+        }
+    }
+}
+
+impl Automaton for Life {
+    fn step(&mut self) -> u32 {
+        Life::step(self)
+    }
+
+    fn reset_random(&mut self, mut rng: &mut dyn RngCore) {
+        self.clear_random(&mut rng);
+    }
+
+    fn clear(&mut self) {
+        Life::clear(self);
+    }
+
+    fn render_into(&self, dst: &mut BitGrid) {
+        copy_clipped(self.as_bitgrid(), dst);
+    }
+}
+
+/// An `Elementry` is only one row, so rendering scrolls everything in `dst` up a row and draws the current generation
+/// along the bottom. Rendering after every step builds up the usual picture of the rule's history.
+impl Automaton for Elementry {
+    fn step(&mut self) -> u32 {
+        Elementry::step(self)
+    }
+
+    fn reset_random(&mut self, mut rng: &mut dyn RngCore) {
+        self.clear_random(&mut rng);
+    }
+
+    fn clear(&mut self) {
+        Elementry::clear(self);
+    }
+
+    fn render_into(&self, dst: &mut BitGrid) {
+        if dst.height() == 0 {
+            return;
+        }
+
+        // Rows never share a byte, so scrolling is one big move
+        let stride = (dst.width() as usize).div_ceil(8);
+        let bytes = dst.as_mut_bytes();
+        bytes.copy_within(stride.., 0);
+
+        // The old bottom row is still there too, and `dst` may be wider than the sim
+        let len = bytes.len();
+        bytes[len - stride..].fill(0);
+
+        let bottom = dst.height() - 1;
+        for x in 0..self.width().min(dst.width()) {
+            dst.set(x, bottom, self.get(x));
+        }
+    }
+}
+
+/// Each step flips one pixel, and resetting puts the beam back in the top left corner
+impl Automaton for BitFlipper {
+    fn step(&mut self) -> u32 {
+        self.flip_and_advance(1);
+        1
+    }
+
+    fn reset_random(&mut self, rng: &mut dyn RngCore) {
+        self.x = 0;
+        self.y = 0;
+        rng.fill_bytes(self.bits.as_mut_bytes());
+        self.bits.normalize();
+    }
+
+    fn clear(&mut self) {
+        self.x = 0;
+        self.y = 0;
+        self.bits.clear();
+    }
+
+    fn render_into(&self, dst: &mut BitGrid) {
+        BitFlipper::render_into(self, dst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn check_step_and_render_any() {
+        let mut rng = SmallRng::seed_from_u64(2542);
+
+        let mut sims: Vec<Box<dyn Automaton>> = vec![
+            Box::new(Life::new(16, 8)),
+            Box::new(Elementry::new(90, 16)),
+            Box::new(BitFlipper::new(16, 8, 1, 2)),
+        ];
+
+        for sim in &mut sims {
+            sim.reset_random(&mut rng);
+            let mut before = BitGrid::new(16, 8);
+            sim.render_into(&mut before);
+
+            let mut n_changed = 0;
+            for _ in 0..4 {
+                n_changed += sim.step();
+            }
+            assert_ne!(n_changed, 0);
+
+            let mut after = BitGrid::new(16, 8);
+            sim.render_into(&mut after);
+            assert_ne!(before, after);
+
+            sim.clear();
+            let mut cleared = BitGrid::new(16, 8);
+            cleared.invert_in_place();
+            sim.render_into(&mut cleared);
+            // The bottom row is the one that every simulation draws into, and it should come out blank
+            assert_eq!(cleared.as_bytes()[cleared.as_bytes().len() - 2..], [0, 0]);
+        }
+    }
+
+    #[test]
+    fn check_render_life_clips() {
+        let mut life = Life::new(4, 4);
+        life.set(0, 0, true);
+        life.set(3, 3, true);
+
+        // Too small: only the top left fits
+        let mut small = BitGrid::new(2, 2);
+        life.render_into(&mut small);
+        assert!(small.get(0, 0));
+        assert_eq!(small.count_ones(), 1);
+
+        // Too big: the rest is left alone
+        let mut big = BitGrid::new(6, 6);
+        big.set(5, 5, true);
+        life.render_into(&mut big);
+        assert!(big.get(0, 0) && big.get(3, 3) && big.get(5, 5));
+        assert_eq!(big.count_ones(), 3);
+    }
+
+    #[test]
+    fn check_render_elementry_scrolls() {
+        let mut sim = Elementry::new(90, 5);
+        sim.set(2, true);
+
+        let mut grid = BitGrid::new(5, 3);
+        for _ in 0..3 {
+            sim.render_into(&mut grid);
+            sim.step();
+        }
+
+        let rows: Vec<Vec<bool>> = (0..3)
+            .map(|y| (0..5).map(|x| grid.get(x, y)).collect())
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                [false, false, true, false, false],
+                [false, true, false, true, false],
+                [true, false, false, false, true],
+            ]
+        );
+    }
+
+    #[test]
+    fn check_render_elementry_wider_than_sim() {
+        let mut sim = Elementry::new(90, 4);
+        sim.set(1, true);
+
+        // Past the sim's width, the bottom row should be blank rather than a copy of the row above
+        let mut grid = BitGrid::new(12, 2);
+        grid.as_mut_bytes().fill(0xFF);
+        grid.normalize();
+        sim.render_into(&mut grid);
+
+        let bottom: Vec<bool> = (0..12).map(|x| grid.get(x, 1)).collect();
+        let mut expected = vec![false; 12];
+        expected[1] = true;
+        assert_eq!(bottom, expected);
+
+        // The row above is what used to be on the bottom
+        assert!((0..12).all(|x| grid.get(x, 0)));
+    }
+}
@@ -1,7 +1,24 @@
 use crate::BitGrid;
 
+/// Bounces a "beam" around a grid at a fixed slope, flipping every pixel it passes through
+///
+/// # How it moves
+/// The beam travels `dir_x` pixels across for every `dir_y` pixels down. To keep everything in integers, its position
+/// is tracked in a scaled space where each pixel is `|dir_y|` units wide and `|dir_x|` units tall. In that space the
+/// beam always moves at 45 degrees: one unit along x for every unit along y.
+///
+/// Each call to [`BitFlipper::flip_and_advance()`] flips the pixel the beam is about to leave, then moves it to the
+/// next pixel boundary it hits, on either axis. When the beam reaches an edge of the grid it reflects off it like a
+/// billiard ball, by flipping the sign of its direction along that axis. A pixel crossed twice (like right at a bounce)
+/// is flipped back.
+///
+/// # Running backwards
+/// `flip_and_advance(-1)` retraces the previous `flip_and_advance(1)` exactly, so stepping forward `N` times and then
+/// backward `N` times restores both the position and the grid.
 pub struct BitFlipper {
+    /// Position of the beam along x, in scaled units (`|dir_y|` per pixel)
     pub x: i32,
+    /// Position of the beam along y, in scaled units (`|dir_x|` per pixel)
     pub y: i32,
     dir_x: i32,
     dir_y: i32,
@@ -9,6 +26,9 @@ pub struct BitFlipper {
 }
 
 impl BitFlipper {
+    /// Creates a blank `view_width` x `view_height` grid, with the beam in the top left corner
+    ///
+    /// The beam's slope is `dir_x : dir_y`. Only the ratio and signs matter, so reduce it first to keep steps small.
     pub fn new(view_width: i32, view_height: i32, dir_x: i32, dir_y: i32) -> Self {
         let bits = BitGrid::new(view_width as usize, view_height as usize);
 
@@ -21,7 +41,26 @@ impl BitFlipper {
         }
     }
 
+    /// The beam's current direction, as `(dir_x, dir_y)`
+    ///
+    /// This is the slope from [`Self::new()`], but the signs change every time the beam bounces off an edge.
+    pub fn slope(&self) -> (i32, i32) {
+        (self.dir_x, self.dir_y)
+    }
+
+    /// Draws the grid into the top left corner of `dst`
+    ///
+    /// Pixels that don't fit are clipped, and any part of `dst` past the grid is left alone.
+    pub fn render_into(&self, dst: &mut BitGrid) {
+        crate::automaton::copy_clipped(&self.bits, dst);
+    }
+
+    /// Flips the current pixel and moves the beam to the next pixel boundary
+    ///
+    /// `dir` is `1` to move forward or `-1` to move backward along the same path.
     pub fn flip_and_advance(&mut self, dir: i32) {
+        // Reflect off any edges we're touching. Multiplying by `dir` makes "away from the edge" point the other way
+        // when running backwards, so that we retrace our steps.
         if self.x <= 0 {
             self.dir_x = self.dir_x.abs() * dir;
         }
@@ -40,6 +79,7 @@ impl BitFlipper {
 
         self.flip_bit(dir);
 
+        // Move until we cross into the next pixel along either axis, whichever comes first
         let next_x = Self::next_multiple_of_n_in_direction(self.x, self.dir_y, self.dir_x * dir);
         let next_y = Self::next_multiple_of_n_in_direction(self.y, self.dir_x, self.dir_y * dir);
 
@@ -52,6 +92,7 @@ impl BitFlipper {
         self.y += move_amount * dir * self.dir_y.signum();
     }
 
+    /// The next multiple of `n` strictly past `i`, looking in the direction of `dir`'s sign
     fn next_multiple_of_n_in_direction(i: i32, n: i32, dir: i32) -> i32 {
         if dir < 0 {
             return -Self::next_multiple_of_n_in_direction(-i, -n, -dir);
@@ -64,6 +105,10 @@ impl BitFlipper {
         (n.abs() + (i % n.abs())) % n.abs()
     }
 
+    /// Flips the pixel the beam is about to move through
+    ///
+    /// The beam sits on pixel boundaries, so when it's heading in a negative direction the pixel it's about to
+    /// cross is the one before the boundary.
     fn flip_bit(&mut self, dir: i32) {
         let x_pixel = (self.x + if self.dir_x * dir >= 0 { 0 } else { -1 }) / self.dir_y.abs();
         let y_pixel = (self.y + if self.dir_y * dir >= 0 { 0 } else { -1 }) / self.dir_x.abs();
@@ -130,6 +175,103 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn check_known_path() {
+        // Each pixel is 2 units wide and 1 unit tall, so the beam covers 2 rows per column
+        let mut bit_flipper = BitFlipper::new(5, 3, 1, 2);
+
+        let mut path = vec![];
+        for _ in 0..6 {
+            path.push((bit_flipper.x, bit_flipper.y));
+            bit_flipper.flip_and_advance(1);
+        }
+        path.push((bit_flipper.x, bit_flipper.y));
+
+        // Bouncing off the bottom edge at (3, 3)
+        assert_eq!(
+            path,
+            [(0, 0), (1, 1), (2, 2), (3, 3), (4, 2), (5, 1), (6, 0)]
+        );
+
+        // Pixel (1, 2) is crossed on the way down and again on the bounce, so it's flipped back
+        let mut expected = BitGrid::new(5, 3);
+        for (x, y) in [(0, 0), (0, 1), (2, 0), (2, 1)] {
+            expected.set(x, y, true);
+        }
+        save_test_image("known_path", "expected", &expected);
+        save_test_image("known_path", "actual", &bit_flipper.bits);
+
+        assert_eq!(bit_flipper.bits, expected);
+    }
+
+    #[rstest]
+    #[case::diagonal(4, 4, 1, 1)]
+    #[case::steep(5, 3, 1, 2)]
+    #[case::shallow(6, 4, 3, 2)]
+    #[case::odd_sizes(13, 7, 5, 3)]
+    fn check_reversible(
+        #[case] width: i32,
+        #[case] height: i32,
+        #[case] dir_x: i32,
+        #[case] dir_y: i32,
+    ) {
+        let mut bit_flipper = BitFlipper::new(width, height, dir_x, dir_y);
+
+        for _ in 0..50 {
+            let before = (bit_flipper.x, bit_flipper.y, bit_flipper.bits.clone());
+
+            // One step forward and back again is a no-op
+            bit_flipper.flip_and_advance(1);
+            bit_flipper.flip_and_advance(-1);
+            assert_eq!(
+                (bit_flipper.x, bit_flipper.y, bit_flipper.bits.clone()),
+                before
+            );
+
+            bit_flipper.flip_and_advance(1);
+        }
+
+        // And so is a lot of steps forward and then back
+        for _ in 0..50 {
+            bit_flipper.flip_and_advance(-1);
+        }
+        assert_eq!((bit_flipper.x, bit_flipper.y), (0, 0));
+        assert!(bit_flipper.bits.is_empty());
+    }
+
+    #[rstest]
+    #[case::same_size(13, 7)]
+    #[case::smaller(5, 4)]
+    #[case::bigger(20, 9)]
+    fn check_render_into(#[case] width: i16, #[case] height: i16) {
+        let mut bit_flipper = BitFlipper::new(13, 7, 5, 3);
+        for _ in 0..40 {
+            bit_flipper.flip_and_advance(1);
+        }
+
+        let mut dst = BitGrid::new(width as usize, height as usize);
+        bit_flipper.render_into(&mut dst);
+
+        for y in 0..height {
+            for x in 0..width {
+                let expected = x < 13 && y < 7 && bit_flipper.bits.get(x, y);
+                assert_eq!(dst.get(x, y), expected, "Mismatch at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn check_slope_follows_bounces() {
+        let mut bit_flipper = BitFlipper::new(5, 3, 1, 2);
+        assert_eq!(bit_flipper.slope(), (1, 2));
+
+        // Same path as check_known_path, which bounces off the bottom edge on the 4th step
+        for _ in 0..4 {
+            bit_flipper.flip_and_advance(1);
+        }
+        assert_eq!(bit_flipper.slope(), (1, -2));
+    }
+
     fn save_test_image(scope: &str, label: &str, frame: &BitGrid) {
         use image::{imageops, Luma};
         eprintln!("+ Saving {scope}_{label}:");
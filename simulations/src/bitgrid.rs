@@ -2,7 +2,18 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 
+/// A 2D grid of bits, packed 8 to a byte, stored on the heap
+///
+/// Each row starts on a new byte. See [`BitGridN`] for a fixed-capacity version that doesn't need an allocator.
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::serde_impls::BitGridRepr",
+        try_from = "crate::serde_impls::BitGridRepr"
+    )
+)]
 pub struct BitGrid {
     buf: Vec<u8>,
     width: i16,
@@ -20,133 +31,770 @@ impl Debug for BitGrid {
 }
 
 impl BitGrid {
+    /// The longest a side of a grid can be, since coordinates are `i16`s
+    pub const MAX_SIDE: usize = i16::MAX as usize;
+
+    /// The most bytes a single grid can take up
+    ///
+    /// Nothing here needs anywhere near this much (a 4096x4096 grid is 2 MiB), so bigger grids are almost certainly a
+    /// bug, and it's better to say so than to try to allocate them.
+    pub const MAX_BYTES: usize = 16 * 1024 * 1024;
+
+    /// Creates a new grid with all bits unset
+    ///
+    /// # Panics
+    /// Panics if the grid is too big, see [`Self::try_new()`].
+    #[track_caller]
     pub fn new(width: usize, height: usize) -> Self {
-        let buf = vec![0; ((width + 7) / 8) * height];
+        Self::try_new(width, height).unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        Self {
-            buf,
+    /// Creates a new grid with all bits unset, if a `width` x `height` grid isn't too big
+    ///
+    /// Each side can be at most [`Self::MAX_SIDE`], and the whole grid at most [`Self::MAX_BYTES`].
+    pub fn try_new(width: usize, height: usize) -> Result<Self, DimsError> {
+        check_dims(width, height, Self::MAX_BYTES)?;
+
+        Ok(Self {
+            buf: vec![0; Self::byte_len_for(width, height)],
             width: width as i16,
             height: height as i16,
-        }
+        })
     }
 
-    pub fn parse<const N: usize>(text: &str, set: [char; N]) -> Option<Self> {
-        let dim_y = text.lines().count() - 1;
-        let dim_x = text.lines().next().map(|l| l.len() - 1).unwrap_or(0);
+    /// The number of bytes needed to store a `width` x `height` grid
+    ///
+    /// This is handy for sizing a [`BitGridN`]:
+    /// ```rust
+    /// # use simulations::{BitGrid, BitGridN};
+    /// let grid = BitGridN::<{ BitGrid::byte_len_for(128, 64) }>::new(128, 64);
+    /// assert_eq!(grid.as_bytes().len(), 1024);
+    /// ```
+    pub const fn byte_len_for(width: usize, height: usize) -> usize {
+        width.div_ceil(8) * height
+    }
 
-        let mut grid = Self::new(dim_x, dim_y);
-        let mut y = 0;
-        for line in text.lines() {
-            let (line, _) = line.split_once("#").unwrap_or((line, ""));
+    /// Copies the `width` x `height` region with its top left corner at `(x, y)` into a new grid
+    ///
+    /// Unlike [`Self::get()`], this doesn't wrap: the region is clipped to the grid first, so the new grid is only as
+    /// big as the part of the region that overlaps this one (and may be empty).
+    pub fn subgrid(&self, x: i16, y: i16, width: usize, height: usize) -> Self {
+        let clip = |start: i16, len: usize, max: i16| {
+            let start = start as i32;
+            let end = (start + len as i32).min(max as i32);
+            let start = start.clamp(0, max as i32);
+            (start as i16, end.max(start) as usize - start as usize)
+        };
+        let (x, width) = clip(x, width, self.width());
+        let (y, height) = clip(y, height, self.height());
 
-            if line.is_empty() {
-                continue;
-            }
-            for (x, c) in line.chars().enumerate() {
-                if set.contains(&c) {
-                    grid.set(x as _, y as _, true);
-                }
+        let mut sub = Self::new(width, height);
+        for dy in 0..height as i16 {
+            for dx in 0..width as i16 {
+                sub.set(dx, dy, self.get(x + dx, y + dy));
             }
-            y += 1;
         }
 
-        Some(grid)
+        sub
     }
+}
 
-    pub fn width(&self) -> i16 {
-        self.width
+/// Checks that a `width` x `height` grid fits in our `i16` coordinates and in `max_bytes` bytes
+fn check_dims(width: usize, height: usize, max_bytes: usize) -> Result<(), DimsError> {
+    if width > BitGrid::MAX_SIDE || height > BitGrid::MAX_SIDE {
+        return Err(DimsError::TooLong { width, height });
     }
 
-    pub fn height(&self) -> i16 {
-        self.height
+    // Both sides fit in an i16, so this can't overflow
+    let bytes = BitGrid::byte_len_for(width, height);
+    if bytes > max_bytes {
+        return Err(DimsError::TooManyBytes {
+            width,
+            height,
+            bytes,
+            max_bytes,
+        });
     }
 
-    pub fn dims(&self) -> (i16, i16) {
-        (self.width(), self.height())
-    }
+    Ok(())
+}
 
-    pub fn is_empty(&self) -> bool {
-        self.buf.iter().all(|&byte| byte == 0)
-    }
+/// Returned when a grid can't be made the size it was asked to be, see [`BitGrid::try_new()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DimsError {
+    /// A side is longer than [`BitGrid::MAX_SIDE`]
+    TooLong { width: usize, height: usize },
 
-    pub fn count_ones(&self) -> i32 {
-        self.buf.iter().map(|&byte| byte.count_ones() as i32).sum()
-    }
+    /// Each side fits, but the whole grid needs more than `max_bytes`
+    TooManyBytes {
+        width: usize,
+        height: usize,
+        bytes: usize,
+        max_bytes: usize,
+    },
 
-    #[track_caller]
-    pub fn get(&self, x: i16, y: i16) -> bool {
-        let (idx, bit) = self.idx(x, y);
-        let mask = 1 << bit;
+    /// A [`Life`](crate::Life) board has to have at least one cell
+    Empty { width: usize, height: usize },
+}
 
-        (self.buf[idx] & mask) != 0
+impl core::fmt::Display for DimsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooLong { width, height } => write!(
+                f,
+                "a {width}x{height} grid is too big, since each side can be at most {}",
+                BitGrid::MAX_SIDE
+            ),
+            Self::TooManyBytes {
+                width,
+                height,
+                bytes,
+                max_bytes,
+            } => write!(
+                f,
+                "a {width}x{height} grid needs {bytes} bytes, but there's only room for {max_bytes}"
+            ),
+            Self::Empty { width, height } => {
+                write!(f, "a {width}x{height} Life board has no cells")
+            }
+        }
     }
+}
 
-    #[track_caller]
-    pub fn set(&mut self, x: i16, y: i16, elem: bool) -> bool {
-        let (idx, bit) = self.idx(x, y);
-        let mask = 1 << bit;
+impl core::error::Error for DimsError {}
+
+/// Returned when two grids were expected to have the same dimensions, but didn't
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DimsMismatch {
+    /// Dimensions of the grid being written to
+    pub expected: (i16, i16),
+    /// Dimensions of the grid that was provided
+    pub actual: (i16, i16),
+}
+
+impl core::fmt::Display for DimsMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "expected a {}x{} grid, but got {}x{}",
+            self.expected.0, self.expected.1, self.actual.0, self.actual.1
+        )
+    }
+}
 
-        let old = (self.buf[idx] & mask) != 0;
+impl core::error::Error for DimsMismatch {}
 
-        self.buf[idx] &= !mask;
-        self.buf[idx] |= (elem as u8) << bit;
+/// Returned when a flat buffer doesn't hold exactly one entry per cell (or byte, for packed buffers)
+///
+/// Buffers for [`BitGrid::pack_into()`] and [`BitGrid::unpack_from()`] only need to be at least `expected` long.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LenMismatch {
+    /// How long the buffer needs to be for the grid
+    pub expected: usize,
+    /// Length of the buffer that was provided
+    pub actual: usize,
+}
 
-        old
+impl core::fmt::Display for LenMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "expected a buffer of length {}, but got {}",
+            self.expected, self.actual
+        )
     }
+}
 
-    #[track_caller]
-    pub fn flip(&mut self, x: i16, y: i16) -> bool {
-        let (idx, bit) = self.idx(x, y);
-        let mask = 1 << bit;
+impl core::error::Error for LenMismatch {}
 
-        let old = (self.buf[idx] & mask) != 0;
+/// Returned by [`BitGrid::unpack_from()`] when the packed grid doesn't fit
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnpackError {
+    /// The buffer ends before the packed grid does
+    Len(LenMismatch),
 
-        self.buf[idx] ^= 1 << bit;
+    /// The packed grid isn't the same size as the grid unpacking it
+    Dims(DimsMismatch),
+}
 
-        old
+impl From<LenMismatch> for UnpackError {
+    fn from(err: LenMismatch) -> Self {
+        Self::Len(err)
     }
+}
 
-    pub fn clear(&mut self) {
-        self.as_mut_bytes().fill(0b0000_0000_u8);
+impl From<DimsMismatch> for UnpackError {
+    fn from(err: DimsMismatch) -> Self {
+        Self::Dims(err)
     }
+}
 
-    pub fn as_bytes(&self) -> &[u8] {
-        &self.buf
+impl core::fmt::Display for UnpackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Len(err) => write!(f, "{err}"),
+            Self::Dims(err) => write!(f, "{err}"),
+        }
     }
+}
 
-    pub fn as_mut_bytes(&mut self) -> &mut [u8] {
-        &mut self.buf
+impl core::error::Error for UnpackError {}
+
+#[cfg(feature = "serde")]
+impl From<BitGrid> for crate::serde_impls::BitGridRepr {
+    fn from(grid: BitGrid) -> Self {
+        Self {
+            width: grid.width,
+            height: grid.height,
+            bytes: grid.buf,
+        }
     }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<crate::serde_impls::BitGridRepr> for BitGrid {
+    type Error = alloc::string::String;
+
+    fn try_from(repr: crate::serde_impls::BitGridRepr) -> Result<Self, Self::Error> {
+        use alloc::string::ToString;
 
-    pub fn idx(&self, mut x: i16, mut y: i16) -> (usize, u8) {
-        // Wrap x and y along their axis
-        x = (x + self.width()) % self.width();
-        y = (y + self.height()) % self.height();
+        let (width, height) = (repr.width, repr.height);
+        if width < 0 || height < 0 {
+            return Err(alloc::format!(
+                "a {width}x{height} grid can't have negative sides"
+            ));
+        }
+        check_dims(width as usize, height as usize, Self::MAX_BYTES)
+            .map_err(|err| err.to_string())?;
 
-        let x = x as usize;
-        let y = y as usize;
+        let expected = Self::byte_len_for(width as usize, height as usize);
+        if repr.bytes.len() != expected {
+            let err = LenMismatch {
+                expected,
+                actual: repr.bytes.len(),
+            };
+            return Err(err.to_string());
+        }
 
-        let idx = (x / 8) + y * ((self.width() as usize + 7) / 8);
-        let bit = x % 8;
+        let mut grid = Self {
+            buf: repr.bytes,
+            width: repr.width,
+            height: repr.height,
+        };
+        grid.normalize();
 
-        (idx, bit as u8)
+        Ok(grid)
     }
+}
 
-    pub fn diff_with(&self, other: &BitGrid) -> BitGrid {
-        assert_eq!(self.width(), other.width());
-        assert_eq!(self.height(), other.height());
+/// A 2D grid of bits with a fixed capacity of `BYTES` bytes, stored inline
+///
+/// This has the same methods as [`BitGrid`], but never allocates.
+/// Use [`BitGrid::byte_len_for`] to pick `BYTES` for a given size.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitGridN<const BYTES: usize> {
+    buf: [u8; BYTES],
+    width: i16,
+    height: i16,
+}
 
-        let mut diff = Self::new(self.width() as _, self.height() as _);
-        let bytes = diff.as_mut_bytes();
+impl<const BYTES: usize> Debug for BitGridN<BYTES> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BitGridN")
+            .field("capacity", &BYTES)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("number of bits set", &self.count_ones())
+            .finish()
+    }
+}
 
-        for (i, (a, b)) in self.as_bytes().iter().zip(other.as_bytes()).enumerate() {
-            bytes[i] = a ^ b;
+impl<const BYTES: usize> BitGridN<BYTES> {
+    /// Creates a new grid with all bits unset
+    ///
+    /// # Panics
+    /// Panics if a `width` x `height` grid doesn't fit in `BYTES` bytes, or a side is longer than
+    /// [`BitGrid::MAX_SIDE`].
+    #[track_caller]
+    pub fn new(width: usize, height: usize) -> Self {
+        if let Err(err) = check_dims(width, height, BYTES) {
+            panic!("{err}");
         }
 
-        diff
+        Self {
+            buf: [0; BYTES],
+            width: width as i16,
+            height: height as i16,
+        }
     }
 }
 
+/// A line to mirror a grid across, see [`BitGrid::is_symmetric()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// A horizontal line through the middle, so the top half mirrors the bottom half
+    Horizontal,
+
+    /// A vertical line through the middle, so the left half mirrors the right half
+    Vertical,
+
+    /// The diagonal from the top left corner, so `(x, y)` mirrors `(y, x)`. Only square grids can have this one.
+    Diagonal,
+}
+
+/// Wraps `coord` onto `0..len`, the way every grid and board here wraps out of bounds coordinates
+///
+/// Negative coordinates count back from the far edge, so `-1` is the last cell. `len` must be positive.
+pub(crate) fn wrap_coord(coord: i32, len: i16) -> i16 {
+    coord.rem_euclid(len as i32) as i16
+}
+
+/// Everything that only needs the packed buffer, shared between [`BitGrid`] and [`BitGridN`]
+///
+/// Implementors need `buf`, `width`, and `height` fields and a `new(width, height)` constructor.
+macro_rules! impl_bitgrid_common {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)*> $ty {
+            pub fn parse<const N: usize>(text: &str, set: [char; N]) -> Option<Self> {
+                let dim_y = text.lines().count() - 1;
+                let dim_x = text.lines().next().map(|l| l.len() - 1).unwrap_or(0);
+
+                let mut grid = Self::new(dim_x, dim_y);
+                let mut y = 0;
+                for line in text.lines() {
+                    let (line, _) = line.split_once("#").unwrap_or((line, ""));
+
+                    if line.is_empty() {
+                        continue;
+                    }
+                    for (x, c) in line.chars().enumerate() {
+                        if set.contains(&c) {
+                            grid.set(x as _, y as _, true);
+                        }
+                    }
+                    y += 1;
+                }
+
+                Some(grid)
+            }
+
+            pub fn width(&self) -> i16 {
+                self.width
+            }
+
+            pub fn height(&self) -> i16 {
+                self.height
+            }
+
+            pub fn dims(&self) -> (i16, i16) {
+                (self.width(), self.height())
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.as_bytes().iter().all(|&byte| byte == 0)
+            }
+
+            pub fn count_ones(&self) -> i32 {
+                self.as_bytes().iter().map(|&byte| byte.count_ones() as i32).sum()
+            }
+
+            /// A 64-bit FNV-1a hash of the packed cells
+            ///
+            /// This only depends on which cells are set (and the dimensions, through the packing), so it's the same on
+            /// every platform and can be used to spot repeated states.
+            pub fn fnv1a_hash(&self) -> u64 {
+                const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+                const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+                self.as_bytes()
+                    .iter()
+                    .fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+            }
+
+            #[track_caller]
+            pub fn get(&self, x: i16, y: i16) -> bool {
+                let (idx, bit) = self.idx(x, y);
+                let mask = 1 << bit;
+
+                (self.buf[idx] & mask) != 0
+            }
+
+            #[track_caller]
+            pub fn set(&mut self, x: i16, y: i16, elem: bool) -> bool {
+                let (idx, bit) = self.idx(x, y);
+                let mask = 1 << bit;
+
+                let old = (self.buf[idx] & mask) != 0;
+
+                self.buf[idx] &= !mask;
+                self.buf[idx] |= (elem as u8) << bit;
+
+                old
+            }
+
+            #[track_caller]
+            pub fn flip(&mut self, x: i16, y: i16) -> bool {
+                let (idx, bit) = self.idx(x, y);
+                let mask = 1 << bit;
+
+                let old = (self.buf[idx] & mask) != 0;
+
+                self.buf[idx] ^= 1 << bit;
+
+                old
+            }
+
+            /// The packed byte holding cells `8 * byte_x` through `8 * byte_x + 7` of row `y`
+            ///
+            /// Bit `n` (counting from the least significant bit) is the cell at `x = 8 * byte_x + n`. Bits past the end
+            /// of the row are always `0`.
+            ///
+            /// # Panics
+            /// Unlike [`Self::get()`], this doesn't wrap. Panics if `byte_x` is past the end of the row or `y` is
+            /// outside of `0..height`.
+            #[track_caller]
+            pub fn get_byte(&self, byte_x: usize, y: i16) -> u8 {
+                self.buf[self.byte_idx(byte_x, y)]
+            }
+
+            /// Overwrites the packed byte holding cells `8 * byte_x` through `8 * byte_x + 7` of row `y`
+            ///
+            /// This uses the same bit order as [`Self::get_byte()`]. Bits past the end of the row are ignored.
+            ///
+            /// # Panics
+            /// Unlike [`Self::set()`], this doesn't wrap. Panics if `byte_x` is past the end of the row or `y` is
+            /// outside of `0..height`.
+            #[track_caller]
+            pub fn set_byte(&mut self, byte_x: usize, y: i16, v: u8) {
+                let idx = self.byte_idx(byte_x, y);
+
+                // Keep the padding at the end of the row cleared
+                let cells_in_byte = (self.width() as usize - 8 * byte_x).min(8);
+                let mask = (0xFF_u16 >> (8 - cells_in_byte)) as u8;
+
+                self.buf[idx] = v & mask;
+            }
+
+            /// Index of the `byte_x`-th byte of row `y` in `as_bytes()`, or a panic if that's outside of the grid
+            #[track_caller]
+            fn byte_idx(&self, byte_x: usize, y: i16) -> usize {
+                let stride = (self.width() as usize).div_ceil(8);
+                assert!(
+                    byte_x < stride && (0..self.height()).contains(&y),
+                    "Byte ({byte_x}, {y}) is outside of a {}x{} grid, which has {stride} bytes per row",
+                    self.width(),
+                    self.height(),
+                );
+
+                byte_x + y as usize * stride
+            }
+
+            /// Like [`Self::get()`], but without wrapping or bounds checks
+            ///
+            /// # Safety
+            /// `x` must be in `0..width` and `y` must be in `0..height`.
+            #[inline]
+            pub unsafe fn get_unchecked(&self, x: i16, y: i16) -> bool {
+                debug_assert!((0..self.width()).contains(&x) && (0..self.height()).contains(&y));
+
+                let (idx, bit) = self.idx_unchecked(x, y);
+                // SAFETY: The caller promised (x, y) is in bounds, so idx is within `as_bytes()`
+                (unsafe { *self.buf.get_unchecked(idx) } & (1 << bit)) != 0
+            }
+
+            /// Like [`Self::set()`], but without wrapping or bounds checks
+            ///
+            /// # Safety
+            /// `x` must be in `0..width` and `y` must be in `0..height`.
+            #[inline]
+            pub unsafe fn set_unchecked(&mut self, x: i16, y: i16, elem: bool) -> bool {
+                debug_assert!((0..self.width()).contains(&x) && (0..self.height()).contains(&y));
+
+                let (idx, bit) = self.idx_unchecked(x, y);
+                let mask = 1 << bit;
+
+                // SAFETY: The caller promised (x, y) is in bounds, so idx is within `as_bytes()`
+                let byte = unsafe { self.buf.get_unchecked_mut(idx) };
+                let old = (*byte & mask) != 0;
+
+                *byte &= !mask;
+                *byte |= (elem as u8) << bit;
+
+                old
+            }
+
+            /// [`Self::idx()`] for coordinates that are already in bounds
+            #[inline]
+            fn idx_unchecked(&self, x: i16, y: i16) -> (usize, u8) {
+                let x = x as usize;
+                let y = y as usize;
+
+                let idx = (x / 8) + y * (self.width() as usize).div_ceil(8);
+                (idx, (x % 8) as u8)
+            }
+
+            /// Every run of same-colored cells as `(is_set, length)`, in row-major order
+            ///
+            /// Runs continue from the end of one row onto the start of the next, and are as long as they can be, so
+            /// neighboring runs always alternate colors.
+            pub fn runs(&self) -> impl Iterator<Item = (bool, u32)> + '_ {
+                let width = self.width().max(0) as usize;
+                let n_cells = width * self.height().max(0) as usize;
+                let cell = move |i: usize| self.get((i % width) as i16, (i / width) as i16);
+
+                let mut i = 0;
+                core::iter::from_fn(move || {
+                    if i >= n_cells {
+                        return None;
+                    }
+
+                    let color = cell(i);
+                    let start = i;
+                    while i < n_cells && cell(i) == color {
+                        i += 1;
+                    }
+
+                    Some((color, (i - start) as u32))
+                })
+            }
+
+            pub fn clear(&mut self) {
+                self.as_mut_bytes().fill(0b0000_0000_u8);
+            }
+
+            /// Flips every cell in the grid.
+            ///
+            /// Padding bits at the end of each row are left cleared, so `is_empty()` and `count_ones()` still agree
+            /// with what `get()` reports.
+            pub fn invert_in_place(&mut self) {
+                for byte in self.as_mut_bytes() {
+                    *byte = !*byte;
+                }
+                self.normalize();
+            }
+
+            /// Clears the padding bits at the end of each row, which aren't cells
+            ///
+            /// Every method here keeps padding bits cleared, and `count_ones()`, `fnv1a_hash()`, and `==` all count on
+            /// it. Writing whole bytes through [`Self::as_mut_bytes()`] can set them though, so call this afterwards.
+            pub fn normalize(&mut self) {
+                let stride = (self.width() as usize).div_ceil(8);
+                if stride == 0 {
+                    return;
+                }
+
+                // Only the low `width % 8` bits of the last byte in a row are real cells
+                let tail_mask = match self.width() as usize % 8 {
+                    0 => return,
+                    n => (1_u8 << n) - 1,
+                };
+
+                for row in self.as_mut_bytes().chunks_exact_mut(stride) {
+                    row[stride - 1] &= tail_mask;
+                }
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                let len = BitGrid::byte_len_for(self.width() as _, self.height() as _);
+                &self.buf[..len]
+            }
+
+            /// The packed cells, for writing whole bytes at a time
+            ///
+            /// Call [`Self::normalize()`] after writing anything that might set padding bits, like `fill(0xFF)`.
+            pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+                let len = BitGrid::byte_len_for(self.width() as _, self.height() as _);
+                &mut self.buf[..len]
+            }
+
+            /// The bytes for each band of `page_height` rows, in order from the top of the grid
+            ///
+            /// Rows never share a byte, so each page is one contiguous slice that can be handed to a block transfer
+            /// as-is. The last page is shorter when `height` isn't a multiple of `page_height`.
+            ///
+            /// # Panics
+            /// Panics if `page_height` is 0.
+            pub fn page_slices(&self, page_height: u8) -> impl Iterator<Item = &[u8]> + '_ {
+                assert!(page_height > 0, "Pages need at least one row");
+
+                let stride = (self.width() as usize).div_ceil(8);
+                // A zero-width grid has no bytes at all, but `chunks()` still needs a non-zero size
+                let page_len = (stride * page_height as usize).max(1);
+                self.as_bytes().chunks(page_len)
+            }
+
+            pub fn idx(&self, x: i16, y: i16) -> (usize, u8) {
+                // Wrap x and y along their axis, no matter how far out of bounds they are
+                let x = wrap_coord(x as i32, self.width()) as usize;
+                let y = wrap_coord(y as i32, self.height()) as usize;
+
+                let idx = (x / 8) + y * (self.width() as usize).div_ceil(8);
+                let bit = x % 8;
+
+                (idx, bit as u8)
+            }
+
+            /// How many bytes [`Self::pack_into()`] writes: a 4 byte header, then [`Self::as_bytes()`]
+            pub fn packed_len(&self) -> usize {
+                4 + self.as_bytes().len()
+            }
+
+            /// Writes the width and height (as little-endian `u16`s) and then the packed cells to the start of `out`
+            ///
+            /// This never allocates, so it's good for sending grids over a serial port. Returns how many bytes were
+            /// written, which is [`Self::packed_len()`], or an error if `out` is shorter than that.
+            pub fn pack_into(&self, out: &mut [u8]) -> Result<usize, LenMismatch> {
+                let len = self.packed_len();
+                let Some(out) = out.get_mut(..len) else {
+                    return Err(LenMismatch {
+                        expected: len,
+                        actual: out.len(),
+                    });
+                };
+
+                out[0..2].copy_from_slice(&(self.width() as u16).to_le_bytes());
+                out[2..4].copy_from_slice(&(self.height() as u16).to_le_bytes());
+                out[4..].copy_from_slice(self.as_bytes());
+
+                Ok(len)
+            }
+
+            /// Reads a grid written by [`Self::pack_into()`] from the start of `bytes`, which must be the same size
+            ///
+            /// Returns how many bytes were read, so anything after the grid can be read next.
+            pub fn unpack_from(&mut self, bytes: &[u8]) -> Result<usize, UnpackError> {
+                let len = self.packed_len();
+                let too_short = LenMismatch {
+                    expected: len,
+                    actual: bytes.len(),
+                };
+
+                let Some(header) = bytes.get(..4) else {
+                    return Err(too_short.into());
+                };
+                let width = u16::from_le_bytes([header[0], header[1]]) as i16;
+                let height = u16::from_le_bytes([header[2], header[3]]) as i16;
+                if (width, height) != self.dims() {
+                    return Err(DimsMismatch {
+                        expected: self.dims(),
+                        actual: (width, height),
+                    }
+                    .into());
+                }
+
+                let Some(cells) = bytes.get(4..len) else {
+                    return Err(too_short.into());
+                };
+                self.as_mut_bytes().copy_from_slice(cells);
+                self.normalize();
+
+                Ok(len)
+            }
+
+            /// Copies every cell from `src`, which must be the same size as `self`
+            pub fn copy_from(&mut self, src: &Self) -> Result<(), DimsMismatch> {
+                if self.dims() != src.dims() {
+                    return Err(DimsMismatch {
+                        expected: self.dims(),
+                        actual: src.dims(),
+                    });
+                }
+
+                self.as_mut_bytes().copy_from_slice(src.as_bytes());
+                Ok(())
+            }
+
+            /// Every cell that differs between `self` and `other`, as `(x, y, other's value)` in row-major order
+            ///
+            /// Applying each change with [`Self::set()`] turns `self` into `other`.
+            pub fn diff(&self, other: &Self) -> Result<Vec<(i16, i16, bool)>, DimsMismatch> {
+                if self.dims() != other.dims() {
+                    return Err(DimsMismatch {
+                        expected: self.dims(),
+                        actual: other.dims(),
+                    });
+                }
+
+                let stride = (self.width() as usize).div_ceil(8);
+                let mut changes = vec![];
+                for (i, (a, b)) in self.as_bytes().iter().zip(other.as_bytes()).enumerate() {
+                    // Padding bits are always clear in both, so they never show up here
+                    let mut changed = a ^ b;
+                    while changed != 0 {
+                        let bit = changed.trailing_zeros() as usize;
+                        changed &= changed - 1;
+
+                        let x = (8 * (i % stride) + bit) as i16;
+                        let y = (i / stride) as i16;
+                        changes.push((x, y, (b >> bit) & 1 != 0));
+                    }
+                }
+
+                Ok(changes)
+            }
+
+            pub fn diff_with(&self, other: &Self) -> Self {
+                assert_eq!(self.width(), other.width());
+                assert_eq!(self.height(), other.height());
+
+                let mut diff = Self::new(self.width() as _, self.height() as _);
+                let bytes = diff.as_mut_bytes();
+
+                for (i, (a, b)) in self.as_bytes().iter().zip(other.as_bytes()).enumerate() {
+                    bytes[i] = a ^ b;
+                }
+
+                diff
+            }
+
+            /// Searches for `needle` as a sub-pattern of this grid, returning the top-left corner of the first match.
+            ///
+            /// Every cell of `needle` must match, set or unset. Matches do not wrap around the edges.
+            pub fn contains_pattern(&self, needle: &Self) -> Option<(i16, i16)> {
+                if needle.width() > self.width() || needle.height() > self.height() {
+                    return None;
+                }
+
+                for y in 0..=(self.height() - needle.height()) {
+                    for x in 0..=(self.width() - needle.width()) {
+                        let is_match = (0..needle.height()).all(|dy| {
+                            (0..needle.width()).all(|dx| self.get(x + dx, y + dy) == needle.get(dx, dy))
+                        });
+
+                        if is_match {
+                            return Some((x, y));
+                        }
+                    }
+                }
+
+                None
+            }
+
+            /// Whether mirroring the grid across `axis` leaves every cell the same
+            pub fn is_symmetric(&self, axis: Axis) -> bool {
+                let (w, h) = self.dims();
+                if axis == Axis::Diagonal && w != h {
+                    return false;
+                }
+
+                (0..h).all(|y| {
+                    (0..w).all(|x| {
+                        let (mx, my) = match axis {
+                            Axis::Horizontal => (x, h - 1 - y),
+                            Axis::Vertical => (w - 1 - x, y),
+                            Axis::Diagonal => (y, x),
+                        };
+                        self.get(x, y) == self.get(mx, my)
+                    })
+                })
+            }
+        }
+    };
+}
+
+impl_bitgrid_common!([] BitGrid);
+impl_bitgrid_common!([const BYTES: usize] BitGridN<BYTES>);
+
 #[cfg(test)]
 mod tests {
     #![allow(non_snake_case, clippy::bool_assert_comparison)]
@@ -154,6 +802,7 @@ mod tests {
 
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
     use rstest::*;
 
     #[test]
@@ -274,6 +923,21 @@ mod tests {
         assert_eq!(maybe_grid, Some(expected));
     }
 
+    #[rstest]
+    #[case::plus(&["..#..", "..#..", "#####", "..#..", "..#.."], [true, true, true])]
+    #[case::arrow(&[".#..", "####", ".#.."], [true, false, false])]
+    #[case::cup(&["#..#", "#..#", "####"], [false, true, false])]
+    #[case::corner(&["###", "#..", "#.."], [false, false, true])]
+    #[case::glider(&[".#.", "..#", "###"], [false, false, false])]
+    #[case::empty_wide(&["......", "......"], [true, true, false])]
+    fn check_is_symmetric(#[case] rows: &[&str], #[case] expected: [bool; 3]) {
+        let grid = grid_from_rows(rows);
+        let axes = [Axis::Horizontal, Axis::Vertical, Axis::Diagonal];
+        for (axis, expected) in axes.into_iter().zip(expected) {
+            assert_eq!(grid.is_symmetric(axis), expected, "{axis:?}");
+        }
+    }
+
     #[test]
     fn check_get_set() {
         let mut grid = BitGrid::new(16, 16);
@@ -330,4 +994,624 @@ mod tests {
         assert_eq!(grid.as_bytes().len(), byte_len);
         assert_eq!(grid.as_bytes(), vec![0b1111_1111; byte_len]);
     }
+
+    #[test]
+    fn check_fixed_matches_heap() {
+        const W: usize = 13;
+        const H: usize = 7;
+
+        let mut heap = BitGrid::new(W, H);
+        let mut fixed = BitGridN::<{ BitGrid::byte_len_for(W, H) }>::new(W, H);
+        assert_eq!(heap.dims(), fixed.dims());
+
+        // Poke at cells in an uneven pattern, including some wrapped coordinates
+        for i in 0..200_i16 {
+            let x = (i * 7) % 26 - 13;
+            let y = (i * 3) % 14 - 7;
+
+            match i % 3 {
+                0 => assert_eq!(heap.set(x, y, true), fixed.set(x, y, true)),
+                1 => assert_eq!(heap.flip(x, y), fixed.flip(x, y)),
+                _ => assert_eq!(heap.set(x, y, false), fixed.set(x, y, false)),
+            }
+            assert_eq!(heap.get(x, y), fixed.get(x, y));
+            assert_eq!(heap.idx(x, y), fixed.idx(x, y));
+        }
+
+        assert_eq!(heap.as_bytes(), fixed.as_bytes());
+        assert_eq!(heap.count_ones(), fixed.count_ones());
+        assert_eq!(heap.is_empty(), fixed.is_empty());
+
+        heap.clear();
+        fixed.clear();
+        assert!(heap.is_empty());
+        assert!(fixed.is_empty());
+    }
+
+    #[test]
+    fn check_fixed_spare_capacity() {
+        // Extra capacity isn't visible through the byte accessors
+        let mut grid = BitGridN::<64>::new(16, 2);
+        assert_eq!(grid.as_bytes().len(), 4);
+        assert_eq!(grid.as_mut_bytes().len(), 4);
+    }
+
+    #[test]
+    fn check_try_new() {
+        let grid = BitGrid::try_new(4096, 4096).unwrap();
+        assert_eq!(grid.dims(), (4096, 4096));
+        assert_eq!(grid.as_bytes().len(), 2 * 1024 * 1024);
+
+        // Sides have to fit in an i16
+        assert_eq!(
+            BitGrid::try_new(40_000, 1),
+            Err(DimsError::TooLong {
+                width: 40_000,
+                height: 1
+            })
+        );
+        assert_eq!(
+            BitGrid::try_new(1, BitGrid::MAX_SIDE + 1),
+            Err(DimsError::TooLong {
+                width: 1,
+                height: 32_768
+            })
+        );
+        assert!(BitGrid::try_new(BitGrid::MAX_SIDE, 1).is_ok());
+
+        // And the whole thing has to be a sensible size
+        assert_eq!(
+            BitGrid::try_new(BitGrid::MAX_SIDE, BitGrid::MAX_SIDE),
+            Err(DimsError::TooManyBytes {
+                width: 32_767,
+                height: 32_767,
+                bytes: 4096 * 32_767,
+                max_bytes: BitGrid::MAX_BYTES,
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "each side can be at most 32767")]
+    fn check_new_too_long() {
+        let _ = BitGrid::new(40_000, 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "each side can be at most 32767")]
+    fn check_fixed_too_long() {
+        // Only 5000 bytes, so it would fit if it weren't for the width
+        let _ = BitGridN::<5000>::new(40_000, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_fixed_too_small() {
+        let _ = BitGridN::<3>::new(16, 2);
+    }
+
+    #[test]
+    fn check_contains_pattern_glider() {
+        let mut life = crate::Life::new(3, 3);
+        life.write_right_glider(0, 0);
+        let glider = life.as_bitgrid().clone();
+
+        let mut grid = BitGrid::new(16, 12);
+        assert_eq!(grid.contains_pattern(&glider), None);
+
+        // Same shape as the needle, with its corner at (5, 7)
+        for (x, y) in [(6, 7), (7, 8), (5, 9), (6, 9), (7, 9)] {
+            grid.set(x, y, true);
+        }
+        assert_eq!(grid.contains_pattern(&glider), Some((5, 7)));
+
+        // Something bigger than the haystack never matches
+        let huge = BitGrid::new(32, 32);
+        assert_eq!(grid.contains_pattern(&huge), None);
+    }
+
+    fn make_patterned(width: usize, height: usize) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height as i16 {
+            for x in 0..width as i16 {
+                grid.set(x, y, (3 * x + 5 * y) % 7 < 3);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn check_subgrid() {
+        let grid = make_patterned(20, 10);
+
+        let sub = grid.subgrid(3, 2, 11, 4);
+        assert_eq!(sub.dims(), (11, 4));
+        for y in 0..4 {
+            for x in 0..11 {
+                assert_eq!(sub.get(x, y), grid.get(x + 3, y + 2), "({x}, {y})");
+            }
+        }
+
+        // The top left of the pattern, worked out by hand
+        let mut expected = BitGrid::new(4, 2);
+        for (x, y) in [(0, 0), (3, 0), (1, 1), (3, 1)] {
+            expected.set(x, y, true);
+        }
+        assert_eq!(grid.subgrid(0, 0, 4, 2), expected);
+
+        // The whole grid is a copy
+        assert_eq!(grid.subgrid(0, 0, 20, 10), grid);
+    }
+
+    #[rstest]
+    #[case::past_right(15, 0, 10, 3, (5, 3), (15, 0))]
+    #[case::past_bottom(0, 8, 2, 4, (2, 2), (0, 8))]
+    #[case::negative(-2, -3, 5, 5, (3, 2), (0, 0))]
+    #[case::all_around(-1, -1, 30, 30, (20, 10), (0, 0))]
+    #[case::outside(25, 0, 4, 4, (0, 4), (20, 0))]
+    fn check_subgrid_clips(
+        #[case] x: i16,
+        #[case] y: i16,
+        #[case] width: usize,
+        #[case] height: usize,
+        #[case] expected_dims: (i16, i16),
+        #[case] expected_corner: (i16, i16),
+    ) {
+        let grid = make_patterned(20, 10);
+
+        let sub = grid.subgrid(x, y, width, height);
+        assert_eq!(sub.dims(), expected_dims);
+
+        let (cx, cy) = expected_corner;
+        for dy in 0..sub.height() {
+            for dx in 0..sub.width() {
+                assert_eq!(sub.get(dx, dy), grid.get(cx + dx, cy + dy));
+            }
+        }
+    }
+
+    #[test]
+    fn check_diff() {
+        // 13 wide, so rows straddle bytes and have padding
+        let mut a = make_patterned(13, 4);
+        let mut b = a.clone();
+        assert_eq!(a.diff(&b), Ok(vec![]));
+
+        b.flip(0, 0);
+        b.flip(9, 1);
+        b.flip(12, 3);
+        b.flip(3, 3);
+        assert_eq!(
+            a.diff(&b),
+            Ok(vec![
+                (0, 0, b.get(0, 0)),
+                (9, 1, b.get(9, 1)),
+                (3, 3, b.get(3, 3)),
+                (12, 3, b.get(12, 3)),
+            ])
+        );
+
+        for (x, y, is_set) in a.diff(&b).unwrap() {
+            a.set(x, y, is_set);
+        }
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn check_diff_dims_mismatch() {
+        let a = BitGrid::new(8, 4);
+        let b = BitGrid::new(4, 8);
+        assert_eq!(
+            a.diff(&b),
+            Err(DimsMismatch {
+                expected: (8, 4),
+                actual: (4, 8),
+            })
+        );
+    }
+
+    #[test]
+    fn check_invert_in_place() {
+        // 13 is deliberately not a multiple of 8, so each row has padding
+        let mut grid = BitGrid::new(13, 5);
+        grid.invert_in_place();
+        assert_eq!(grid.count_ones(), 13 * 5);
+        assert!(!grid.is_empty());
+
+        grid.invert_in_place();
+        assert_eq!(grid.count_ones(), 0);
+        assert!(grid.is_empty());
+
+        grid.set(3, 1, true);
+        grid.set(12, 4, true);
+        let original = grid.clone();
+
+        grid.invert_in_place();
+        assert!(!grid.get(3, 1));
+        assert!(grid.get(4, 1));
+        assert_eq!(grid.count_ones(), 13 * 5 - 2);
+
+        grid.invert_in_place();
+        assert_eq!(grid, original);
+    }
+
+    fn make_packable() -> BitGrid {
+        let mut grid = BitGrid::new(13, 5);
+        grid.set(0, 0, true);
+        grid.set(9, 2, true);
+        grid.set(12, 4, true);
+        grid
+    }
+
+    #[test]
+    fn check_pack_into() {
+        let grid = make_packable();
+        assert_eq!(grid.packed_len(), 4 + 2 * 5);
+
+        let mut buf = [0xAA; 4 + 2 * 5];
+        assert_eq!(grid.pack_into(&mut buf), Ok(buf.len()));
+        assert_eq!(buf[..4], [13, 0, 5, 0]);
+        assert_eq!(buf[4..], *grid.as_bytes());
+    }
+
+    #[test]
+    fn check_pack_into_too_small() {
+        let grid = make_packable();
+
+        let mut buf = [0xAA; 13];
+        assert_eq!(
+            grid.pack_into(&mut buf),
+            Err(LenMismatch {
+                expected: 14,
+                actual: 13,
+            })
+        );
+        // Nothing is written if it doesn't all fit
+        assert_eq!(buf, [0xAA; 13]);
+
+        let mut dst = BitGrid::new(13, 5);
+        let mut buf = [0; 32];
+        grid.pack_into(&mut buf).unwrap();
+        assert_eq!(
+            dst.unpack_from(&buf[..13]),
+            Err(UnpackError::Len(LenMismatch {
+                expected: 14,
+                actual: 13,
+            }))
+        );
+        assert_eq!(
+            dst.unpack_from(&buf[..3]),
+            Err(UnpackError::Len(LenMismatch {
+                expected: 14,
+                actual: 3,
+            }))
+        );
+        assert!(dst.is_empty());
+    }
+
+    #[test]
+    fn check_pack_round_trip() {
+        let grid = make_packable();
+
+        // Extra room after the grid is left alone
+        let mut buf = [0xAA; 32];
+        let len = grid.pack_into(&mut buf).unwrap();
+        assert_eq!(buf[len..], [0xAA; 32 - 14]);
+
+        let mut dst = BitGrid::new(13, 5);
+        assert_eq!(dst.unpack_from(&buf), Ok(len));
+        assert_eq!(dst, grid);
+
+        // Fixed size grids are the point, since they never allocate
+        let mut fixed = BitGridN::<{ BitGrid::byte_len_for(13, 5) }>::new(13, 5);
+        assert_eq!(fixed.unpack_from(&buf), Ok(len));
+        assert_eq!(fixed.as_bytes(), grid.as_bytes());
+
+        let mut again = [0; 32];
+        assert_eq!(fixed.pack_into(&mut again), Ok(len));
+        assert_eq!(again[..len], buf[..len]);
+
+        // Grids only unpack into grids of the same size
+        let mut wrong = BitGrid::new(16, 5);
+        assert_eq!(
+            wrong.unpack_from(&buf),
+            Err(UnpackError::Dims(DimsMismatch {
+                expected: (16, 5),
+                actual: (13, 5),
+            }))
+        );
+    }
+
+    #[test]
+    fn check_copy_from() {
+        let mut src = BitGrid::new(13, 5);
+        src.set(0, 0, true);
+        src.set(12, 4, true);
+
+        let mut dst = BitGrid::new(13, 5);
+        dst.set(6, 2, true);
+        assert_eq!(dst.copy_from(&src), Ok(()));
+        assert_eq!(dst, src);
+
+        // Same number of bytes, different shape
+        let mut wrong = BitGrid::new(16, 5);
+        assert_eq!(
+            wrong.copy_from(&src),
+            Err(DimsMismatch {
+                expected: (16, 5),
+                actual: (13, 5),
+            })
+        );
+        assert!(wrong.is_empty());
+
+        let mut fixed = BitGridN::<{ BitGrid::byte_len_for(13, 5) }>::new(13, 5);
+        let mut fixed_src = BitGridN::<{ BitGrid::byte_len_for(13, 5) }>::new(13, 5);
+        fixed_src.set(3, 3, true);
+        assert_eq!(fixed.copy_from(&fixed_src), Ok(()));
+        assert!(fixed.get(3, 3));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_serde_round_trip() {
+        let mut grid = BitGrid::new(11, 3);
+        grid.set(0, 0, true);
+        grid.set(10, 1, true);
+        grid.set(5, 2, true);
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(json, r#"{"width":11,"height":3,"bytes":[1,0,0,4,32,0]}"#);
+
+        let round_tripped: BitGrid = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, grid);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_serde_rejects_wrong_len() {
+        let json = r#"{"width":11,"height":3,"bytes":[1,0,0,4,32]}"#;
+        let err = serde_json::from_str::<BitGrid>(json).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected a buffer of length 6, but got 5"),
+            "{err}"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_serde_rejects_negative_size() {
+        let json = r#"{"width":-5,"height":3,"bytes":[]}"#;
+        let err = serde_json::from_str::<BitGrid>(json).unwrap_err();
+        assert!(err.to_string().contains("negative sides"), "{err}");
+    }
+
+    #[test]
+    fn check_unchecked_matches_checked() {
+        let mut rng = SmallRng::seed_from_u64(2484);
+
+        let mut checked = BitGrid::new(13, 7);
+        let mut unchecked = BitGrid::new(13, 7);
+        let mut unchecked_n = BitGridN::<{ BitGrid::byte_len_for(13, 7) }>::new(13, 7);
+
+        for _ in 0..500 {
+            let x = rng.random_range(0..13);
+            let y = rng.random_range(0..7);
+            let elem = rng.random_bool(0.5);
+
+            // SAFETY: x and y are in bounds
+            unsafe {
+                assert_eq!(unchecked.set_unchecked(x, y, elem), checked.set(x, y, elem));
+                unchecked_n.set_unchecked(x, y, elem);
+            }
+        }
+        assert_eq!(unchecked, checked);
+        assert_eq!(unchecked_n.as_bytes(), checked.as_bytes());
+
+        for y in 0..7 {
+            for x in 0..13 {
+                // SAFETY: x and y are in bounds
+                unsafe {
+                    assert_eq!(checked.get_unchecked(x, y), checked.get(x, y));
+                    assert_eq!(unchecked_n.get_unchecked(x, y), checked.get(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_fnv1a_hash() {
+        // Known FNV-1a values, for buffers of one 0x00 byte and one 'a' (0x61) byte
+        let mut grid = BitGrid::new(8, 1);
+        assert_eq!(grid.fnv1a_hash(), 0xaf63_bd4c_8601_b7df);
+        grid.as_mut_bytes()[0] = b'a';
+        assert_eq!(grid.fnv1a_hash(), 0xaf63_dc4c_8601_ec8c);
+
+        // Same cells, same hash, no matter the storage
+        let mut n = BitGridN::<4>::new(8, 1);
+        n.as_mut_bytes()[0] = b'a';
+        assert_eq!(n.fnv1a_hash(), grid.fnv1a_hash());
+
+        grid.flip(3, 0);
+        assert_ne!(n.fnv1a_hash(), grid.fnv1a_hash());
+    }
+
+    /// Builds a grid from rows of text, where `#` is set
+    fn grid_from_rows(rows: &[&str]) -> BitGrid {
+        let mut grid = BitGrid::new(rows.first().map_or(0, |row| row.len()), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid.set(x as i16, y as i16, c == '#');
+            }
+        }
+        grid
+    }
+
+    #[rstest]
+    #[case::empty(&[], &[])]
+    #[case::all_unset(&[".....", ".....", "....."], &[(false, 15)])]
+    #[case::one_row(&[".##.#."], &[(false, 1), (true, 2), (false, 1), (true, 1), (false, 1)])]
+    #[case::across_rows(
+        &[
+            "......",
+            "....##",
+            "###...",
+            "......",
+        ],
+        &[(false, 10), (true, 5), (false, 9)],
+    )]
+    #[case::ends_set(&["#..", "..#"], &[(true, 1), (false, 4), (true, 1)])]
+    fn check_runs(#[case] rows: &[&str], #[case] expected: &[(bool, u32)]) {
+        let grid = grid_from_rows(rows);
+        let runs: Vec<(bool, u32)> = grid.runs().collect();
+        assert_eq!(runs, expected);
+
+        // Rebuild the grid from its runs
+        let mut rebuilt = BitGrid::new(grid.width() as usize, grid.height() as usize);
+        let mut i = 0;
+        for (color, len) in runs {
+            for _ in 0..len {
+                rebuilt.set(i % grid.width(), i / grid.width(), color);
+                i += 1;
+            }
+        }
+        assert_eq!(i as i32, grid.width() as i32 * grid.height() as i32);
+        assert_eq!(rebuilt.as_bytes(), grid.as_bytes());
+        assert_eq!(rebuilt, grid);
+    }
+
+    #[rstest]
+    #[case::sh1107_pages(128, 64, 8)]
+    #[case::uneven_height(37, 11, 4)]
+    #[case::one_row_pages(13, 5, 1)]
+    #[case::one_big_page(16, 3, 8)]
+    fn check_page_slices(#[case] width: usize, #[case] height: usize, #[case] page_height: u8) {
+        let mut rng = SmallRng::seed_from_u64(2491);
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height as i16 {
+            for x in 0..width as i16 {
+                grid.set(x, y, rng.random_bool(0.5));
+            }
+        }
+
+        let stride = width.div_ceil(8);
+        let pages: Vec<&[u8]> = grid.page_slices(page_height).collect();
+        assert_eq!(pages.len(), height.div_ceil(page_height as usize));
+
+        // Pages are consecutive and cover the buffer exactly once
+        let mut offset = 0;
+        for (i, page) in pages.iter().enumerate() {
+            let rows = (height - i * page_height as usize).min(page_height as usize);
+            assert_eq!(page.len(), rows * stride, "page {i}");
+            assert_eq!(
+                *page,
+                &grid.as_bytes()[offset..offset + page.len()],
+                "page {i}"
+            );
+            offset += page.len();
+        }
+        assert_eq!(offset, grid.as_bytes().len());
+        assert_eq!(pages.concat(), grid.as_bytes());
+    }
+
+    #[test]
+    #[should_panic]
+    fn check_page_slices_rejects_empty_pages() {
+        let grid = BitGrid::new(8, 8);
+        let _ = grid.page_slices(0);
+    }
+
+    #[test]
+    fn check_set_byte_reads_back_as_bits() {
+        let mut grid = BitGrid::new(20, 3);
+        grid.set_byte(0, 1, 0b1000_0101);
+        grid.set_byte(1, 2, 0b0000_0001);
+
+        assert_eq!(grid.get_byte(0, 1), 0b1000_0101);
+        assert_eq!(grid.get_byte(1, 2), 0b0000_0001);
+        assert_eq!(grid.get_byte(0, 0), 0);
+
+        let set: Vec<(i16, i16)> = (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| grid.get(x, y))
+            .collect();
+        assert_eq!(set, [(0, 1), (2, 1), (7, 1), (8, 2)]);
+
+        // Bytes see per-cell writes too
+        grid.set(9, 2, true);
+        assert_eq!(grid.get_byte(1, 2), 0b0000_0011);
+    }
+
+    #[test]
+    fn check_set_byte_ignores_padding() {
+        // 20 cells per row only uses the low 4 bits of the last byte
+        let mut grid = BitGrid::new(20, 2);
+        grid.set_byte(2, 0, 0xFF);
+
+        assert_eq!(grid.get_byte(2, 0), 0x0F);
+        assert_eq!(grid.count_ones(), 4);
+        assert!((16..20).all(|x| grid.get(x, 0)));
+        assert!(!grid.get(0, 1));
+    }
+
+    #[rstest]
+    #[case::past_row_end(3, 0)]
+    #[case::negative_row(0, -1)]
+    #[case::past_last_row(0, 2)]
+    #[should_panic(expected = "outside of a 20x2 grid")]
+    fn check_byte_access_is_bounds_checked(#[case] byte_x: usize, #[case] y: i16) {
+        let mut grid = BitGrid::new(20, 2);
+        grid.set_byte(byte_x, y, 1);
+    }
+
+    #[rstest]
+    #[case::narrow(3, 4)]
+    #[case::just_over_a_byte(9, 2)]
+    #[case::wide(37, 11)]
+    #[case::whole_bytes(16, 3)]
+    fn check_normalize(#[case] width: usize, #[case] height: usize) {
+        let mut grid = BitGrid::new(width, height);
+        grid.as_mut_bytes().fill(0xFF);
+        grid.normalize();
+
+        assert_eq!(grid.count_ones(), (width * height) as i32);
+
+        // Now it's indistinguishable from a grid that had every cell set one at a time
+        let mut expected = BitGrid::new(width, height);
+        for y in 0..height as i16 {
+            for x in 0..width as i16 {
+                expected.set(x, y, true);
+            }
+        }
+        assert_eq!(grid, expected);
+        assert_eq!(grid.fnv1a_hash(), expected.fnv1a_hash());
+
+        grid.invert_in_place();
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn check_runs_random_round_trip() {
+        let mut rng = SmallRng::seed_from_u64(2487);
+        let mut grid = BitGridN::<{ BitGrid::byte_len_for(37, 11) }>::new(37, 11);
+        for y in 0..11 {
+            for x in 0..37 {
+                grid.set(x, y, rng.random_bool(0.3));
+            }
+        }
+
+        let mut rebuilt = BitGridN::<{ BitGrid::byte_len_for(37, 11) }>::new(37, 11);
+        let mut i = 0;
+        let mut last_color = None;
+        for (color, len) in grid.runs() {
+            assert_ne!(Some(color), last_color, "Neighboring runs should alternate");
+            last_color = Some(color);
+
+            for _ in 0..len {
+                rebuilt.set(i % 37, i / 37, color);
+                i += 1;
+            }
+        }
+        assert_eq!(rebuilt, grid);
+    }
 }
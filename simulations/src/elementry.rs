@@ -1,5 +1,24 @@
 use crate::BitGrid;
 
+/// Starting conditions for [`Elementry::seed()`]
+///
+/// Different rules show off best from different seeds: rule 90 grows a Sierpinski triangle from a single cell, but
+/// turns a random row into a completely different texture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Seed {
+    /// One live cell in the middle
+    SingleCenter,
+
+    /// One live cell at the far left
+    SingleLeft,
+
+    /// Each cell is alive with this probability, from `0.0` to `1.0`
+    Random(f32),
+
+    /// Every other cell is alive, starting with the leftmost
+    Alternating,
+}
+
 #[derive(Clone)]
 pub struct Elementry {
     /// Current state of the simulation
@@ -10,17 +29,46 @@ pub struct Elementry {
 
     rule: u8,
     width: i16,
+
+    /// How often each neighborhood came up in the rows before this one, most recent at `history_next - 1`, for
+    /// [`Elementry::transition_entropy()`]
+    history: [[u32; 8]; Elementry::ENTROPY_HISTORY],
+
+    /// How many entries of `history` hold a row, up to [`Elementry::ENTROPY_HISTORY`]
+    history_len: usize,
+
+    /// Where the next row's counts go in `history`
+    history_next: usize,
+}
+
+impl Default for Elementry {
+    /// Rule 90, 64 cells wide, where every cell is **dead**
+    fn default() -> Self {
+        Self::new(90, 64)
+    }
 }
 
 /// Basic Usage
 impl Elementry {
+    /// How many earlier rows [`Elementry::transition_entropy()`] looks at, on top of the current one
+    pub const ENTROPY_HISTORY: usize = 8;
+
     /// Creates a new `Elementry` simulation with the given dimensions where all cells are initially **dead**.
+    ///
+    /// # Panics
+    /// Panics if `width` is `0`. The row wraps around, which needs at least one cell.
+    #[track_caller]
     pub fn new(rule: u8, width: usize) -> Self {
+        assert!(width > 0, "An Elementry simulation needs at least one cell");
+
         Self {
             cells: BitGrid::new(width, 1),
             shadow: BitGrid::new(width, 1),
             rule,
             width: width as i16,
+            history: [[0; 8]; Self::ENTROPY_HISTORY],
+            history_len: 0,
+            history_next: 0,
         }
     }
 
@@ -46,6 +94,7 @@ impl Elementry {
     /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
     pub fn step(&mut self) -> u32 {
         let mut count = 0;
+        let mut neighborhoods = [0_u32; 8];
 
         for x in 0..self.width() {
             let old = self.get(x);
@@ -53,6 +102,7 @@ impl Elementry {
                 | ((self.get(x + 0) as u8) << 1)
                 | ((self.get(x + 1) as u8) << 0);
             let mask = 1 << c;
+            neighborhoods[c as usize] += 1;
 
             let is_alive = (self.rule & mask) != 0;
             self.shadow.set(x, 1, is_alive);
@@ -62,27 +112,138 @@ impl Elementry {
 
         core::mem::swap(&mut self.cells, &mut self.shadow);
 
+        self.history[self.history_next] = neighborhoods;
+        self.history_next = (self.history_next + 1) % Self::ENTROPY_HISTORY;
+        self.history_len = (self.history_len + 1).min(Self::ENTROPY_HISTORY);
+
         count
     }
 
     /// Marks all cells as **dead**
     pub fn clear(&mut self) {
         self.cells.as_mut_bytes().fill(0);
+        self.forget_history();
     }
 
     /// Marks all cells as **alive**
     pub fn clear_alive(&mut self) {
         self.cells.as_mut_bytes().fill(0xff);
+        self.cells.normalize();
+        self.forget_history();
     }
 
     /// Set all cells to **alive** or **dead** using the provided rng.
     pub fn clear_random(&mut self, rng: &mut impl rand::Rng) {
+        self.forget_history();
         let bytes: &mut [u8] = self.cells.as_mut_bytes();
         for chunk in bytes.chunks_mut(4) {
             let rand_bytes = rng.next_u32().to_le_bytes();
             chunk.copy_from_slice(&rand_bytes[..chunk.len()]);
         }
+        self.cells.normalize();
+    }
+
+    /// Replaces every cell with the starting condition described by `preset`
+    ///
+    /// Only [`Seed::Random`] uses `rng`.
+    pub fn seed(&mut self, preset: Seed, rng: &mut impl rand::Rng) {
+        self.clear();
+
+        match preset {
+            Seed::SingleCenter => self.set(self.width() / 2, true),
+            Seed::SingleLeft => self.set(0, true),
+            Seed::Random(density) => {
+                let density = density.clamp(0.0, 1.0) as f64;
+                for x in 0..self.width() {
+                    self.set(x, rng.random_bool(density));
+                }
+            }
+            Seed::Alternating => {
+                for x in (0..self.width()).step_by(2) {
+                    self.set(x, true);
+                }
+            }
+        }
+    }
+
+    /// Replaces every cell with the bits of `bytes`, so any starting row can be drawn (or spelled out)
+    ///
+    /// Bits are read LSB-first, so bit `n` of `bytes[0]` is cell `n`, and bit `n` of `bytes[1]` is cell `8 + n`. Bits
+    /// past the end of the row are ignored, and cells past the end of `bytes` are **dead**.
+    pub fn seed_from_bytes(&mut self, bytes: &[u8]) {
+        self.clear();
+
+        let n_cells = (self.width() as usize).min(8 * bytes.len());
+        for x in 0..n_cells {
+            let is_alive = (bytes[x / 8] >> (x % 8)) & 1 != 0;
+            self.set(x as i16, is_alive);
+        }
+    }
+}
+
+/// Metrics
+impl Elementry {
+    /// The fraction of cells that are **alive**, from `0.0` to `1.0`
+    ///
+    /// Rules that die out head to `0.0` and rules that saturate head to `1.0`, neither of which is much to look at.
+    pub fn density(&self) -> f32 {
+        let alive = self.cells().filter(|&is_alive| is_alive).count();
+        alive as f32 / self.width() as f32
+    }
+
+    /// How evenly recent rows use the 8 neighborhoods that the rule maps to new cells, from `0.0` to `1.0`
+    ///
+    /// This is the Shannon entropy of the neighborhoods in the current row and up to
+    /// [`Elementry::ENTROPY_HISTORY`] rows before it, scaled so that using every neighborhood equally often is `1.0`.
+    /// Uniform rows only ever hit one neighborhood and score `0.0`, so whatever the rule does next, it does the same
+    /// thing everywhere. Clearing or seeding the row starts the history over.
+    pub fn transition_entropy(&self) -> f32 {
+        let mut counts = [0_u32; 8];
+        for x in 0..self.width() {
+            let c = ((self.get(x - 1) as usize) << 2)
+                | ((self.get(x + 0) as usize) << 1)
+                | ((self.get(x + 1) as usize) << 0);
+            counts[c] += 1;
+        }
+        for row in &self.history[..self.history_len] {
+            for (count, n) in counts.iter_mut().zip(row) {
+                *count += n;
+            }
+        }
+
+        let n = counts.iter().sum::<u32>() as f32;
+        let bits: f32 = counts
+            .iter()
+            .filter(|&&count| count != 0)
+            .map(|&count| {
+                let p = count as f32 / n;
+                -p * log2(p)
+            })
+            .sum();
+
+        // There are 8 neighborhoods, so the most entropy we can have is 3 bits
+        bits / 3.0
     }
+
+    fn forget_history(&mut self) {
+        self.history_len = 0;
+        self.history_next = 0;
+    }
+}
+
+/// `f32::log2()` needs `std`, so approximate it from the float's exponent and mantissa when we don't have it
+#[cfg(feature = "std")]
+fn log2(x: f32) -> f32 {
+    x.log2()
+}
+
+#[cfg(not(feature = "std"))]
+fn log2(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as f32 - 127.0;
+    // m is in [1, 2), and this quadratic is within ~0.005 of log2(m) there
+    let m = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+    exponent + (-0.344_848_43 * m + 2.024_665_8) * m - 1.674_877_6
 }
 
 /// `std`-only functions
@@ -99,6 +260,223 @@ impl Elementry {
 mod test {
     use super::*;
 
+    use rand::{rngs::SmallRng, SeedableRng};
+    use rstest::*;
+
+    #[rstest]
+    #[case::single_center(Seed::SingleCenter, "....O....")]
+    #[case::single_left(Seed::SingleLeft, "O........")]
+    #[case::alternating(Seed::Alternating, "O.O.O.O.O")]
+    #[case::random_empty(Seed::Random(0.0), ".........")]
+    #[case::random_full(Seed::Random(1.0), "OOOOOOOOO")]
+    fn check_seed(#[case] preset: Seed, #[case] expected: &str) {
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        // Start from a mess to make sure seeding replaces everything
+        let mut sim = Elementry::new(90, 9);
+        sim.clear_alive();
+
+        sim.seed(preset, &mut rng);
+        assert_eq!(sim.to_ascii(), expected);
+    }
+
+    #[test]
+    fn check_seed_random_density() {
+        let mut rng = SmallRng::seed_from_u64(0xE1E);
+
+        let mut sim = Elementry::new(90, 1000);
+        sim.seed(Seed::Random(0.25), &mut rng);
+
+        let alive = sim.cells().filter(|&is_alive| is_alive).count();
+        assert!((200..300).contains(&alive), "{alive} cells alive");
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn check_rule_90_from_single_center() {
+        let mut sim = Elementry::new(90, 15);
+        sim.seed(Seed::SingleCenter, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(sim.to_ascii(), ".......O.......");
+        sim.step();
+        assert_eq!(sim.to_ascii(), "......O.O......");
+        sim.step();
+        assert_eq!(sim.to_ascii(), ".....O...O.....");
+        sim.step();
+        assert_eq!(sim.to_ascii(), "....O.O.O.O....");
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn check_rule_90_wrapping_depends_on_width() {
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        // On an even width, the edges of the triangle meet on the same cell around the back and cancel out
+        let mut even = Elementry::new(90, 8);
+        even.seed(Seed::SingleCenter, &mut rng);
+        for _ in 0..3 {
+            even.step();
+        }
+        assert_eq!(even.to_ascii(), ".O.O.O.O");
+        even.step();
+        assert_eq!(even.to_ascii(), "........");
+
+        // Three more cells make it odd, so they meet one cell apart and keep going
+        let mut odd = Elementry::new(90, 8 + 3);
+        odd.seed(Seed::SingleCenter, &mut rng);
+        for _ in 0..3 {
+            odd.step();
+        }
+        assert_eq!(odd.to_ascii(), "..O.O.O.O..");
+        odd.step();
+        assert_eq!(odd.to_ascii(), ".O.......O.");
+        odd.step();
+        assert_eq!(odd.to_ascii(), "O.O.....O.O");
+        odd.step();
+        assert_eq!(odd.to_ascii(), "O..O...O..O");
+        odd.step();
+        assert_eq!(odd.to_ascii(), "OOO.O.O.OOO");
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn check_seed_from_bytes() {
+        let mut sim = Elementry::new(90, 12);
+        sim.clear_alive();
+
+        // Only the low 4 bits of the second byte fit
+        sim.seed_from_bytes(&[0b1000_0011, 0b1111_0001]);
+        assert_eq!(sim.to_ascii(), "OO.....OO...");
+
+        // Each cell becomes the XOR of its neighbors, wrapping around the ends
+        sim.step();
+        assert_eq!(sim.to_ascii(), "OOO...OOOO.O");
+
+        let mut again = Elementry::new(90, 12);
+        again.seed_from_bytes(&[0b1000_0011, 0b0000_0001]);
+        again.step();
+        for _ in 0..20 {
+            assert_eq!(again.to_ascii(), sim.to_ascii());
+            sim.step();
+            again.step();
+        }
+
+        // Too few bytes leaves the rest of the row dead
+        sim.seed_from_bytes(b"A");
+        assert_eq!(sim.to_ascii(), "O.....O.....");
+        sim.seed_from_bytes(&[]);
+        assert_eq!(sim.to_ascii(), "............");
+    }
+
+    #[test]
+    fn check_density() {
+        let mut sim = Elementry::new(90, 13);
+        assert_eq!(sim.density(), 0.0);
+
+        sim.clear_alive();
+        assert_eq!(sim.density(), 1.0);
+
+        sim.seed(Seed::Alternating, &mut SmallRng::seed_from_u64(0));
+        assert_eq!(sim.density(), 7.0 / 13.0);
+    }
+
+    #[test]
+    fn check_rule_90_density_grows() {
+        let mut sim = Elementry::new(90, 64);
+        sim.seed(Seed::SingleCenter, &mut SmallRng::seed_from_u64(0));
+
+        let mut alive = vec![];
+        for _ in 0..4 {
+            alive.push((sim.density() * sim.width() as f32).round() as u32);
+            sim.step();
+        }
+        assert_eq!(alive, [1, 2, 2, 4]);
+        assert!(alive[0] < alive[1] && alive[1] <= alive[2] && alive[2] < alive[3]);
+    }
+
+    #[test]
+    fn check_transition_entropy() {
+        let mut rng = SmallRng::seed_from_u64(0xE27);
+
+        // Uniform rows only see one neighborhood
+        let mut sim = Elementry::new(90, 64);
+        assert_eq!(sim.transition_entropy(), 0.0);
+        sim.clear_alive();
+        assert_eq!(sim.transition_entropy(), 0.0);
+
+        // `101` and `010`, half and half, is exactly one bit
+        sim.seed(Seed::Alternating, &mut rng);
+        let alternating = sim.transition_entropy();
+        assert!((alternating - 1.0 / 3.0).abs() < 0.01, "{alternating}");
+
+        // Noise uses every neighborhood about equally
+        let mut sim = Elementry::new(90, 4096);
+        sim.seed(Seed::Random(0.5), &mut rng);
+        let noise = sim.transition_entropy();
+        assert!(noise > 0.99 && noise <= 1.01, "{noise}");
+    }
+
+    #[test]
+    fn check_transition_entropy_remembers_recent_rows() {
+        let mut rng = SmallRng::seed_from_u64(0xE28);
+
+        // Rule 0 kills everything, so the current row alone would say `0.0`
+        let mut sim = Elementry::new(0, 64);
+        sim.seed(Seed::Alternating, &mut rng);
+        sim.step();
+        assert!(sim.cells().all(|is_alive| !is_alive));
+
+        // 32 `101`s and 32 `010`s from before, and 64 `000`s now, is 1.5 bits
+        let entropy = sim.transition_entropy();
+        assert!((entropy - 0.5).abs() < 0.01, "{entropy}");
+
+        // The alternating row eventually falls out of the history
+        for _ in 0..Elementry::ENTROPY_HISTORY {
+            sim.step();
+        }
+        assert_eq!(sim.transition_entropy(), 0.0);
+
+        // Seeding starts over too
+        sim.seed(Seed::Alternating, &mut rng);
+        sim.step();
+        sim.seed(Seed::Random(0.0), &mut rng);
+        assert_eq!(sim.transition_entropy(), 0.0);
+    }
+
+    #[test]
+    fn check_default() {
+        let sim = Elementry::default();
+        assert_eq!(sim.width(), 64);
+        assert_eq!(sim.to_ascii(), ".".repeat(64));
+    }
+
+    #[test]
+    #[should_panic(expected = "needs at least one cell")]
+    fn check_new_rejects_zero_width() {
+        let _ = Elementry::new(90, 0);
+    }
+
+    #[test]
+    fn check_step_counts_changes() {
+        // Rule 4 only keeps isolated live cells alive, so a lone seed is already a fixed point
+        let mut frozen = Elementry::new(4, 16);
+        frozen.set(8, true);
+        assert_eq!(frozen.step(), 0);
+        assert_eq!(frozen.step(), 0);
+        assert_eq!(frozen.to_ascii(), "........O.......");
+
+        // Rule 30 grows a lone seed into three cells, so exactly two cells changed
+        let mut growing = Elementry::new(30, 16);
+        growing.set(8, true);
+        assert_eq!(growing.step(), 2);
+        assert_ne!(growing.step(), 0);
+
+        // Rule 255 turns everything on, and then has nothing left to do
+        let mut filling = Elementry::new(255, 16);
+        assert_eq!(filling.step(), 16);
+        assert_eq!(filling.step(), 0);
+    }
+
     #[test]
     #[rustfmt::skip]
     fn check_single_seed() {
@@ -8,13 +8,37 @@
 extern crate alloc;
 
 mod life;
-pub use life::Life;
+#[cfg(feature = "rayon")]
+pub use life::par_step_many;
+pub use life::{
+    Classification, GliderCounter, Life, Neighborhood, Placed, ReseedPolicy, StepStats, PATTERNS,
+};
 
 mod elementry;
-pub use elementry::Elementry;
+pub use elementry::{Elementry, Seed};
 
 mod bitgrid;
-pub use bitgrid::BitGrid;
+pub use bitgrid::{Axis, BitGrid, BitGridN, DimsError, DimsMismatch, LenMismatch, UnpackError};
 
 mod bitflipper;
 pub use bitflipper::BitFlipper;
+
+mod automaton;
+pub use automaton::Automaton;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// The desktop runner moves simulations onto worker threads, so make sure they stay `Send` and `Sync`
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Life>();
+    assert_send_sync::<Elementry>();
+    assert_send_sync::<BitGrid>();
+    assert_send_sync::<BitGridN<16>>();
+    assert_send_sync::<BitFlipper>();
+};
@@ -1,6 +1,23 @@
-use crate::BitGrid;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
 
+use crate::bitgrid::wrap_coord;
+use crate::{BitGrid, DimsError, DimsMismatch, LenMismatch};
+
+/// Conway's Game of Life, on a board that wraps around at the edges
+///
+/// With the `serde` feature, this serializes as its cells and generation. Aging (see [`Life::enable_aging()`]), undo
+/// history (see [`Life::enable_undo()`]), and the [`Neighborhood`] are not saved.
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "crate::serde_impls::LifeRepr",
+        try_from = "crate::serde_impls::LifeRepr"
+    )
+)]
 pub struct Life {
     /// Current state of the simulation
     cells: BitGrid,
@@ -11,18 +28,406 @@ pub struct Life {
 
     width: i16,
     height: i16,
+
+    /// Number of times `step()` has run since the board was last cleared
+    generation: u64,
+
+    /// Per-cell fade out timers, see [`Life::enable_aging()`]. `None` when aging is disabled.
+    ages: Option<Vec<u8>>,
+    max_age: u8,
+
+    /// Which cells `step()` counts as neighbors
+    neighborhood: Neighborhood,
+
+    /// Cells changed by recent steps, see [`Life::enable_undo()`]. `None` when undo is disabled.
+    undo: Option<UndoLog>,
+
+    /// How many calls to `step()` advance one generation, see [`Life::set_slow_motion()`]
+    calls_per_generation: u32,
+    calls_since_generation: u32,
+}
+
+/// A cell that [`Life::step()`] changed, and what it was before
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct CellDiff {
+    x: i16,
+    y: i16,
+    was_alive: bool,
+}
+
+#[derive(Clone, Debug)]
+struct UndoLog {
+    /// One entry per step, oldest first
+    steps: VecDeque<Vec<CellDiff>>,
+    max_steps: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<Life> for crate::serde_impls::LifeRepr {
+    fn from(life: Life) -> Self {
+        Self {
+            cells: life.cells,
+            generation: life.generation,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<crate::serde_impls::LifeRepr> for Life {
+    type Error = &'static str;
+
+    fn try_from(repr: crate::serde_impls::LifeRepr) -> Result<Self, Self::Error> {
+        let (width, height) = repr.cells.dims();
+        if width <= 0 || height <= 0 {
+            return Err("a Life board needs at least one cell in each direction");
+        }
+
+        let mut life = Life::from_bitgrid(repr.cells);
+        life.generation = repr.generation;
+        Ok(life)
+    }
+}
+
+impl Default for Life {
+    /// A 64x64 board where every cell is **dead**
+    fn default() -> Self {
+        Self::new(64, 64)
+    }
+}
+
+/// What happened during one [`Life::step_stats()`]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StepStats {
+    /// Cells that were dead and are now alive
+    pub births: u32,
+    /// Cells that were alive and are now dead
+    pub deaths: u32,
+}
+
+impl StepStats {
+    /// The total number of cells that changed
+    pub fn changed(&self) -> u32 {
+        self.births + self.deaths
+    }
+}
+
+/// What a board settles into, see [`Life::classify()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Classification {
+    /// Every cell died
+    Extinct,
+
+    /// The board stopped changing. Holds how many steps [`Life::classify()`] took to get there.
+    Stable(u64),
+
+    /// The board repeats itself every this many steps (at least 2)
+    Oscillator(u64),
+
+    /// Nothing repeated, but the population kept climbing
+    Growing,
+
+    /// Nothing repeated, and the population didn't clearly grow. Try more steps?
+    Unknown,
+}
+
+/// When to give up on a board that's gotten boring, and start over with a fresh random one
+///
+/// Feed it every step's change count along with the generation after that step, and reseed (e.g. with
+/// [`Life::clear_random()`]) when it says to.
+///
+/// ```rust
+/// # use simulations::{Life, ReseedPolicy};
+/// let policy = ReseedPolicy::new(1000);
+/// let mut life = Life::new(8, 8);
+/// let n_changed = life.step();
+///
+/// // An empty board never changes, so it's time for a new one
+/// assert!(policy.should_reseed(n_changed, life.generation()));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReseedPolicy {
+    /// Reseed once the board has run this many generations. `None` lets it run forever.
+    pub max_generations: Option<u64>,
+
+    /// Reseed as soon as a step changes nothing, since a frozen board stays frozen
+    ///
+    /// Oscillators never freeze like this, see [`Life::classify()`] to spot those.
+    pub on_freeze: bool,
+}
+
+impl ReseedPolicy {
+    /// Reseeds on freezing, or after `max_generations`
+    pub const fn new(max_generations: u64) -> Self {
+        Self {
+            max_generations: Some(max_generations),
+            on_freeze: true,
+        }
+    }
+
+    /// Whether to reseed, after a step that changed `n_changed` cells and left the board at `generation`
+    pub fn should_reseed(&self, n_changed: u32, generation: u64) -> bool {
+        let frozen = self.on_freeze && n_changed == 0;
+        let too_old = self
+            .max_generations
+            .is_some_and(|max_generations| generation >= max_generations);
+
+        frozen || too_old
+    }
+}
+
+/// Counts gliders as they cross a vertical line, like the stream a glider gun fires
+///
+/// Feed it the board after every step. Each glider heading down and to the right (see [`Life::write_right_glider()`])
+/// is counted once, in the generation where it's in the phase that writes and its left edge is on `line_x`. Gliders
+/// touching anything else right then, like ones in the middle of a collision, aren't counted.
+///
+/// ```rust
+/// # use simulations::{GliderCounter, Life};
+/// let mut life = Life::new(16, 16);
+/// life.write_right_glider(0, 0);
+/// let mut counter = GliderCounter::new(4);
+///
+/// // A glider moves one cell across every 4 generations
+/// for _ in 0..16 {
+///     life.step();
+///     counter.update(&life);
+/// }
+/// assert_eq!(counter.count(), 1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GliderCounter {
+    /// Column that gliders have to reach to be counted
+    pub line_x: i16,
+
+    count: u32,
+
+    /// Generation of the board last checked, so checking it again doesn't count anything twice
+    last_generation: Option<u64>,
+}
+
+impl GliderCounter {
+    pub const fn new(line_x: i16) -> Self {
+        Self {
+            line_x,
+            count: 0,
+            last_generation: None,
+        }
+    }
+
+    /// How many gliders have crossed the line so far
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Looks for gliders on the line, and returns how many were just counted
+    ///
+    /// Checking the same generation again (like in slow motion) counts nothing, but skipping generations may miss
+    /// gliders, since each one is only on the line in the right phase for a single generation.
+    pub fn update(&mut self, life: &Life) -> u32 {
+        if self.last_generation == Some(life.generation()) {
+            return 0;
+        }
+        self.last_generation = Some(life.generation());
+
+        let n_new = (0..life.height())
+            .filter(|&y| life.is_lone_right_glider(self.line_x, y))
+            .count() as u32;
+        self.count += n_new;
+
+        n_new
+    }
 }
 
+/// Whether a pattern written to a [`Life`] fit on the board, see [`Life::write_right_glider()`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Placed {
+    /// Every cell of the pattern landed where it was asked to
+    Whole,
+
+    /// Part of the pattern hung off an edge and wrapped around to the other side of the board
+    Clipped,
+}
+
+impl Placed {
+    pub fn is_clipped(self) -> bool {
+        self == Self::Clipped
+    }
+}
+
+/// Which cells count as neighbors when stepping a [`Life`]
+///
+/// Births and survival always use Conway's counts (born with 3, survives with 2 or 3), so with only 4 neighbors to
+/// count, [`Neighborhood::VonNeumann`] boards behave very differently.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The 8 cells around a cell, including diagonals
+    #[default]
+    Moore,
+
+    /// The 4 cells directly above, below, left, and right of a cell
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// Offsets to every neighbor of a cell (but not the cell itself)
+    pub fn offsets(self) -> &'static [(i16, i16)] {
+        match self {
+            Self::Moore => &MOORE_OFFSETS,
+            Self::VonNeumann => &VON_NEUMANN_OFFSETS,
+        }
+    }
+}
+
+const MOORE_OFFSETS: [(i16, i16); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const VON_NEUMANN_OFFSETS: [(i16, i16); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
 /// Basic Usage
 impl Life {
     /// Creates a new `Life` simulation with the given dimensions where all cells are initially **dead**.
+    ///
+    /// # Panics
+    /// Panics if `width` or `height` is `0`. The board wraps around, which needs at least one cell in each direction.
+    /// Also panics if the board is too big, see [`Life::try_new()`].
+    #[track_caller]
     pub fn new(width: usize, height: usize) -> Self {
-        Self {
-            cells: BitGrid::new(width, height),
-            shadow: BitGrid::new(width, height),
+        Self::try_new(width, height).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Creates a new `Life` simulation with all cells **dead**, if a `width` x `height` board can exist
+    ///
+    /// Boards need at least one cell, and have the same limits on their size as [`BitGrid::try_new()`].
+    pub fn try_new(width: usize, height: usize) -> Result<Self, DimsError> {
+        if width == 0 || height == 0 {
+            return Err(DimsError::Empty { width, height });
+        }
+
+        Ok(Self {
+            cells: BitGrid::try_new(width, height)?,
+            shadow: BitGrid::try_new(width, height)?,
             width: width as i16,
             height: height as i16,
+            generation: 0,
+            ages: None,
+            max_age: 0,
+            neighborhood: Neighborhood::Moore,
+            undo: None,
+            calls_per_generation: 1,
+            calls_since_generation: 0,
+        })
+    }
+
+    /// Creates a new `Life` simulation the same size as `cells`, with the same cells **alive**
+    ///
+    /// # Panics
+    /// Panics if `cells` has no cells, like [`Life::new()`].
+    #[track_caller]
+    pub fn from_bitgrid(cells: BitGrid) -> Self {
+        let (width, height) = cells.dims();
+
+        let mut life = Self::new(width as usize, height as usize);
+        life.cells = cells;
+        life
+    }
+
+    /// Creates a new `Life` simulation from an 8-bit grayscale image, where cells brighter than `threshold` are **alive**
+    ///
+    /// `luma` is one byte per pixel in row-major order, and must be exactly `width * height` long.
+    ///
+    /// # Panics
+    /// Panics if `width` or `height` is `0`, like [`Life::new()`].
+    ///
+    /// ```rust
+    /// # use simulations::Life;
+    /// let luma = [
+    ///     0x00, 0xFF, 0x00,
+    ///     0x40, 0x80, 0xC0,
+    /// ];
+    /// let life = Life::from_luma(3, 2, &luma, 0x80).unwrap();
+    /// assert!(life.get(1, 0));
+    /// assert!(!life.get(1, 1));
+    /// assert!(life.get(2, 1));
+    /// ```
+    #[track_caller]
+    pub fn from_luma(
+        width: usize,
+        height: usize,
+        luma: &[u8],
+        threshold: u8,
+    ) -> Result<Self, LenMismatch> {
+        let mut life = Self::new(width, height);
+        life.check_buffer_len(luma.len())?;
+
+        for (i, &px) in luma.iter().enumerate() {
+            life.set((i % width) as i16, (i / width) as i16, px > threshold);
+        }
+
+        Ok(life)
+    }
+
+    /// Changes the size of the board, keeping cells where they are relative to the top left corner
+    ///
+    /// Cells that no longer fit are dropped, and new cells start out **dead**. The generation counter carries on, but
+    /// the undo history is forgotten, and cell ages restart as if aging had just been enabled.
+    ///
+    /// # Panics
+    /// Panics if `width` or `height` is `0`, like [`Life::new()`].
+    #[track_caller]
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.resize_with_offset(width, height, 0, 0);
+    }
+
+    /// Like [`Self::resize()`], but keeps the middle of the board in the middle
+    ///
+    /// Growing adds dead cells evenly around every edge, and shrinking drops them evenly from every edge. When a
+    /// dimension can't be split evenly, the content ends up half a cell up or left of center.
+    ///
+    /// # Panics
+    /// Panics if `width` or `height` is `0`, like [`Life::new()`].
+    #[track_caller]
+    pub fn resize_centered(&mut self, width: usize, height: usize) {
+        let dx = (width / 2) as i32 - (self.width() / 2) as i32;
+        let dy = (height / 2) as i32 - (self.height() / 2) as i32;
+        self.resize_with_offset(width, height, dx, dy);
+    }
+
+    /// Resizes the board, moving each cell at `(x, y)` to `(x + dx, y + dy)`
+    #[track_caller]
+    fn resize_with_offset(&mut self, width: usize, height: usize, dx: i32, dy: i32) {
+        let mut resized = Self::new(width, height);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if self.get(x, y)
+                    && (0..width as i32).contains(&nx)
+                    && (0..height as i32).contains(&ny)
+                {
+                    resized.set(nx as i16, ny as i16, true);
+                }
+            }
+        }
+
+        resized.generation = self.generation;
+        resized.neighborhood = self.neighborhood;
+        resized.calls_per_generation = self.calls_per_generation;
+        resized.calls_since_generation = self.calls_since_generation;
+        if self.is_aging() {
+            resized.enable_aging(self.max_age);
+        }
+        if let Some(undo) = &self.undo {
+            resized.enable_undo(undo.max_steps);
         }
+
+        *self = resized;
     }
 
     /// The width of the simulation
@@ -35,6 +440,19 @@ impl Life {
         self.height
     }
 
+    /// The number of times this simulation has been stepped since it was created or last cleared
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// A hash of every cell on the board, for checking that two copies of a simulation are still in sync
+    ///
+    /// This is [`BitGrid::fnv1a_hash()`] of the cells, so it's cheap enough to compare every step. Only the cells go
+    /// into it, not [`Self::generation()`] or cell ages.
+    pub fn state_hash(&self) -> u64 {
+        self.cells.fnv1a_hash()
+    }
+
     /// Checks whether the cell at `(x, y)` is **alive** or **dead**.
     ///
     /// Out of bounds access wrap around.
@@ -43,6 +461,16 @@ impl Life {
         self.cells.get(x, y)
     }
 
+    /// Checks whether the cell at `(x, y)` is **alive**, wrapping any coordinate onto the board
+    ///
+    /// This is [`Life::get()`] for coordinates that don't fit in an `i16`, so a render loop can tile the board across a
+    /// viewport bigger than it, and see the wrapping continue from one copy into the next.
+    pub fn sample_wrapped(&self, x: i32, y: i32) -> bool {
+        let x = wrap_coord(x, self.width);
+        let y = wrap_coord(y, self.height);
+        self.cells.get(x, y)
+    }
+
     /// Sets the cell at `(x, y)` to either **alive** or **dead**.
     ///
     /// Out of bounds access wrap around.
@@ -67,54 +495,173 @@ impl Life {
     /// ```
     #[track_caller]
     pub fn set(&mut self, x: i16, y: i16, is_alive: bool) -> bool {
+        if is_alive {
+            if let Some(idx) = self.age_idx(x, y) {
+                self.ages.as_mut().unwrap()[idx] = self.max_age;
+            }
+        }
         self.cells.set(x, y, is_alive)
     }
 
     /// Steps the simulation once, returning the number of cells updated
     ///
-    /// Note: If this ever returns `0`, the simulation will henceforth never change, because nothing is changing anymore.
+    /// This is `births + deaths` from [`Life::step_stats()`]. In slow motion (see [`Life::set_slow_motion()`]), calls
+    /// between generations don't step and return `0`.
+    ///
+    /// Note: If this ever returns `0` outside of slow motion, the simulation will henceforth never change, because
+    /// nothing is changing anymore.
     pub fn step(&mut self) -> u32 {
-        let mut count = 0;
+        self.calls_since_generation += 1;
+        if self.calls_since_generation < self.calls_per_generation {
+            return 0;
+        }
+        self.calls_since_generation = 0;
+
+        self.step_stats().changed()
+    }
+
+    /// Steps the simulation once, returning how many cells were born and how many died
+    pub fn step_stats(&mut self) -> StepStats {
+        // Births need 3 live neighbors, so an empty board stays empty and there's no need to look at every cell
+        if self.cells.count_ones() == 0 {
+            if let Some(ages) = &mut self.ages {
+                for age in ages {
+                    *age = age.saturating_sub(1);
+                }
+            }
+            self.generation += 1;
+            self.record_undo(Some(vec![]));
+
+            return StepStats::default();
+        }
+
+        let mut stats = StepStats::default();
+        let mut diffs = self.undo.as_ref().map(|_| vec![]);
 
         for y in 0..self.height() {
             for x in 0..self.width() {
-                let mut live_count = 0;
+                let is_alive = self.next_state(x, y);
+                self.shadow.set(x, y, is_alive);
 
-                live_count += self.get(x - 1, y - 1) as u8;
-                live_count += self.get(x - 1, y + 0) as u8;
-                live_count += self.get(x - 1, y + 1) as u8;
+                if let Some(ages) = &mut self.ages {
+                    let age = &mut ages[y as usize * self.width as usize + x as usize];
+                    *age = if is_alive {
+                        self.max_age
+                    } else {
+                        age.saturating_sub(1)
+                    };
+                }
+
+                match (self.get(x, y), is_alive) {
+                    (false, true) => stats.births += 1,
+                    (true, false) => stats.deaths += 1,
+                    _ => {}
+                }
 
-                live_count += self.get(x + 0, y - 1) as u8;
-                // Don't count itself
-                live_count += self.get(x + 0, y + 1) as u8;
+                if let Some(diffs) = &mut diffs {
+                    if self.get(x, y) != is_alive {
+                        diffs.push(CellDiff {
+                            x,
+                            y,
+                            was_alive: !is_alive,
+                        });
+                    }
+                }
+            }
+        }
 
-                live_count += self.get(x + 1, y - 1) as u8;
-                live_count += self.get(x + 1, y + 0) as u8;
-                live_count += self.get(x + 1, y + 1) as u8;
+        core::mem::swap(&mut self.cells, &mut self.shadow);
+        self.generation += 1;
+        self.record_undo(diffs);
 
-                let is_alive = if self.get(x, y) {
-                    // Continues to live
-                    (live_count == 2) || (live_count == 3)
-                } else {
-                    // lives, as if by reproduction
-                    live_count == 3
-                };
+        stats
+    }
 
-                self.shadow.set(x, y, is_alive);
+    /// Steps only the cells inside the `width` x `height` rectangle at `(x, y)`, returning the number of cells updated
+    ///
+    /// Cells inside the region come out exactly as a full [`Life::step()`] would leave them. Their neighbors are read
+    /// from the board as it is, wrapping like usual, even when they're outside the region. Cells outside the region
+    /// never change, so anything crossing the edge of the region sees a frozen world on the other side.
+    ///
+    /// The region is clipped to the board rather than wrapped. This counts as a generation, and can be undone like
+    /// any other step.
+    pub fn step_region(&mut self, x: i16, y: i16, width: usize, height: usize) -> u32 {
+        let (xs, ys) = self.clip_region(x, y, width, height);
+
+        let mut changes = vec![];
+        for y in ys.clone() {
+            for x in xs.clone() {
+                let is_alive = self.next_state(x, y);
+                if let Some(ages) = &mut self.ages {
+                    let age = &mut ages[y as usize * self.width as usize + x as usize];
+                    *age = if is_alive {
+                        self.max_age
+                    } else {
+                        age.saturating_sub(1)
+                    };
+                }
 
                 if self.get(x, y) != is_alive {
-                    count += 1;
+                    changes.push(CellDiff {
+                        x,
+                        y,
+                        was_alive: !is_alive,
+                    });
                 }
             }
         }
 
-        core::mem::swap(&mut self.cells, &mut self.shadow);
+        // Every cell has been decided from the old board, so it's safe to change them now
+        for diff in &changes {
+            self.cells.set(diff.x, diff.y, !diff.was_alive);
+        }
+
+        let n_changed = changes.len() as u32;
+        self.generation += 1;
+        self.record_undo(Some(changes));
+
+        n_changed
+    }
 
-        count
+    /// Whether the cell at `(x, y)` will be **alive** after the next step
+    fn next_state(&self, x: i16, y: i16) -> bool {
+        // Every neighbor goes through the same wrapping `get()`, so edges and corners need no special cases
+        let live_count = self
+            .neighborhood
+            .offsets()
+            .iter()
+            .filter(|&&(dx, dy)| self.get(x + dx, y + dy))
+            .count();
+
+        if self.get(x, y) {
+            // Continues to live
+            (live_count == 2) || (live_count == 3)
+        } else {
+            // lives, as if by reproduction
+            live_count == 3
+        }
+    }
+
+    /// Saves the cells one step changed, if undo is enabled
+    fn record_undo(&mut self, diffs: Option<Vec<CellDiff>>) {
+        if let (Some(undo), Some(diffs)) = (&mut self.undo, diffs) {
+            if undo.max_steps == 0 {
+                return;
+            }
+            while undo.steps.len() >= undo.max_steps {
+                undo.steps.pop_front();
+            }
+            undo.steps.push_back(diffs);
+        }
     }
 
-    /// Marks all cells as **dead**
+    /// Marks all cells as **dead**, and resets the generation counter and undo history
     pub fn clear(&mut self) {
+        self.generation = 0;
+        self.forget_undo();
+        if let Some(ages) = &mut self.ages {
+            ages.fill(0);
+        }
         for y in 0..self.height() {
             for x in 0..self.width() {
                 self.set(x, y, false);
@@ -122,97 +669,1474 @@ impl Life {
         }
     }
 
-    /// Set all cells to **alive** or **dead** using the provided rng.
+    /// The columns and rows of the `width` x `height` rectangle at `(x, y)` that are on the board, without wrapping
+    fn clip_region(
+        &self,
+        x: i16,
+        y: i16,
+        width: usize,
+        height: usize,
+    ) -> (core::ops::Range<i16>, core::ops::Range<i16>) {
+        let clip = |start: i16, len: usize, max: i16| {
+            let end = (start as i32 + len as i32).clamp(0, max as i32) as i16;
+            start.clamp(0, max)..end
+        };
+
+        (clip(x, width, self.width()), clip(y, height, self.height()))
+    }
+
+    /// Set all cells to **alive** or **dead** using the provided rng, and resets the generation counter and undo
+    /// history
     pub fn clear_random(&mut self, rng: &mut impl rand::Rng) {
+        self.generation = 0;
+        self.forget_undo();
         let bytes: &mut [u8] = self.cells.as_mut_bytes();
         for chunk in bytes.chunks_mut(4) {
             let rand_bytes = rng.next_u32().to_le_bytes();
             chunk.copy_from_slice(&rand_bytes[..chunk.len()]);
         }
+        self.cells.normalize();
+        self.reset_ages();
+    }
+
+    /// Kills every cell, then brings each cell in the `width` x `height` rectangle at `(x, y)` to **life** with
+    /// probability `density`, from `0.0` to `1.0`
+    ///
+    /// A soup with empty margins gives whatever grows out of it room to spread. The region is clipped to the board
+    /// rather than wrapped. Like [`Life::clear_random()`], this resets the generation counter and undo history.
+    pub fn clear_random_region(
+        &mut self,
+        rng: &mut impl rand::Rng,
+        x: i16,
+        y: i16,
+        width: usize,
+        height: usize,
+        density: f32,
+    ) {
+        self.clear();
+
+        let density = density.clamp(0.0, 1.0) as f64;
+        let (xs, ys) = self.clip_region(x, y, width, height);
+        for y in ys {
+            for x in xs.clone() {
+                self.cells.set(x, y, rng.random_bool(density));
+            }
+        }
+        self.reset_ages();
     }
 
     pub fn as_bitgrid(&self) -> &BitGrid {
         &self.cells
     }
-}
 
-/// Patterns
-impl Life {
-    /// Writes right-facing glider with its corner at `(x, y)`
+    /// Copies every cell into `dst` a byte at a time, with set meaning **alive**
     ///
-    /// # Cell info
-    /// A right-facing glider looks like this:
-    /// ```txt
-    /// .O.
-    /// ..O
-    /// OOO
-    /// ```
+    /// `dst` must be the same size as the board, and is left unchanged if it isn't.
+    pub fn copy_into_bitgrid(&self, dst: &mut BitGrid) -> Result<(), DimsMismatch> {
+        dst.copy_from(&self.cells)
+    }
+
+    /// Writes every cell into `out` in row-major order, with `true` meaning **alive**
     ///
-    /// Where the top left is `(x, y)`.
-    #[track_caller]
-    pub fn write_right_glider(&mut self, x: i16, y: i16) {
-        self.set(x + 0, y + 0, false);
-        self.set(x + 1, y + 0, true);
-        self.set(x + 2, y + 0, false);
+    /// `out` must be exactly `width * height` long.
+    pub fn copy_to_bool_buffer(&self, out: &mut [bool]) -> Result<(), LenMismatch> {
+        self.check_buffer_len(out.len())?;
 
-        self.set(x + 0, y + 1, false);
-        self.set(x + 1, y + 1, false);
-        self.set(x + 2, y + 1, true);
+        let width = self.width() as usize;
+        for (i, cell) in out.iter_mut().enumerate() {
+            *cell = self.get((i % width) as i16, (i / width) as i16);
+        }
 
-        self.set(x + 0, y + 2, true);
-        self.set(x + 1, y + 2, true);
-        self.set(x + 2, y + 2, true);
+        Ok(())
     }
 
-    /// Writes left-facing glider with its corner at `(x, y)`
-    ///
-    /// # Cell info
-    /// A left-facing glider looks like this:
-    /// ```txt
-    /// .O.
-    /// O.
-    /// OOO
-    /// ```
+    /// Sets every cell from `cells` in row-major order, with `true` meaning **alive**
     ///
-    /// Where the top left is `(x, y)`.
-    #[track_caller]
-    pub fn write_left_glider(&mut self, x: i16, y: i16) {
-        self.set(x + 0, y + 0, false);
-        self.set(x + 1, y + 0, true);
-        self.set(x + 2, y + 0, false);
+    /// `cells` must be exactly `width * height` long. The board is left unchanged if it isn't.
+    pub fn set_from_bool_buffer(&mut self, cells: &[bool]) -> Result<(), LenMismatch> {
+        self.check_buffer_len(cells.len())?;
 
-        self.set(x + 0, y + 1, true);
-        self.set(x + 1, y + 1, false);
-        self.set(x + 2, y + 1, false);
+        let width = self.width() as usize;
+        for (i, &is_alive) in cells.iter().enumerate() {
+            self.set((i % width) as i16, (i / width) as i16, is_alive);
+        }
 
-        self.set(x + 0, y + 2, true);
-        self.set(x + 1, y + 2, true);
-        self.set(x + 2, y + 2, true);
+        Ok(())
+    }
+
+    fn check_buffer_len(&self, len: usize) -> Result<(), LenMismatch> {
+        let expected = self.width() as usize * self.height() as usize;
+        if len == expected {
+            Ok(())
+        } else {
+            Err(LenMismatch {
+                expected,
+                actual: len,
+            })
+        }
     }
 }
 
-/// `std`-only functions
-#[cfg(feature = "std")]
+/// Neighborhoods
 impl Life {
-    /// Prints the state of the board to `stdout`
-    pub fn print_ascii(&self) {
-        for y in 0..self.height() {
-            for x in 0..self.width() {
-                if self.get(x, y) {
-                    print!("O");
-                } else {
-                    print!(".");
-                }
-            }
-            println!();
-        }
-        println!();
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Changes which cells count as neighbors, starting with the next step
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+        self.neighborhood = neighborhood;
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Slow motion
+impl Life {
+    /// How many calls to [`Life::step()`] it takes to advance one generation
+    pub fn slow_motion(&self) -> u32 {
+        self.calls_per_generation
+    }
+
+    /// Makes [`Life::step()`] only advance the board on every `calls_per_generation`th call, and return `0` in between
+    ///
+    /// This slows a simulation down for presentation without changing the loop that drives it. Counting starts over
+    /// from here, so the `calls_per_generation`th call from now is the next to step. Use `1` to step on every call
+    /// again, which is the default. [`Life::step_parallel()`] shares the count with `step()`, but other ways of stepping,
+    /// like [`Life::step_stats()`], aren't slowed down.
+    ///
+    /// Anything that takes a `0` from `step()` to mean the board froze, like [`ReseedPolicy`], will be fooled by the
+    /// calls in between generations.
+    ///
+    /// # Panics
+    /// Panics if `calls_per_generation` is `0`.
+    #[track_caller]
+    pub fn set_slow_motion(&mut self, calls_per_generation: u32) {
+        assert!(
+            calls_per_generation > 0,
+            "Slow motion needs at least 1 call per generation"
+        );
+
+        self.calls_per_generation = calls_per_generation;
+        self.calls_since_generation = 0;
+    }
+}
+
+/// Aging
+///
+/// Aging is an opt-in way to render trails behind cells. Every live cell has the maximum age, and once a cell dies
+/// its age counts down by one each step until it reaches `0`. Map ages to a palette to get cells that fade out.
+impl Life {
+    /// Starts tracking cell ages, with live cells at `max_age`
+    ///
+    /// Cells that are already dead start at `0`, as if they'd been dead forever.
+    pub fn enable_aging(&mut self, max_age: u8) {
+        self.max_age = max_age;
+        self.ages = Some(vec![0; self.width as usize * self.height as usize]);
+        self.reset_ages();
+    }
+
+    /// Stops tracking cell ages, and frees their storage
+    pub fn disable_aging(&mut self) {
+        self.ages = None;
+    }
+
+    pub fn is_aging(&self) -> bool {
+        self.ages.is_some()
+    }
+
+    /// How recently the cell at `(x, y)` was alive: `max_age` while alive, counting down to `0` after it dies
+    ///
+    /// Out of bounds access wrap around. Without aging, this is `u8::MAX` for live cells and `0` for dead ones.
+    pub fn cell_age(&self, x: i16, y: i16) -> u8 {
+        match (&self.ages, self.age_idx(x, y)) {
+            (Some(ages), Some(idx)) => ages[idx],
+            _ => {
+                if self.get(x, y) {
+                    u8::MAX
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    fn age_idx(&self, x: i16, y: i16) -> Option<usize> {
+        self.ages.as_ref()?;
+
+        let x = wrap_coord(x as i32, self.width) as usize;
+        let y = wrap_coord(y as i32, self.height) as usize;
+        Some(y * self.width as usize + x)
+    }
+
+    /// Sets live cells to `max_age`, and dead cells to `0`
+    fn reset_ages(&mut self) {
+        let Some(mut ages) = self.ages.take() else {
+            return;
+        };
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                ages[y as usize * self.width as usize + x as usize] =
+                    if self.get(x, y) { self.max_age } else { 0 };
+            }
+        }
+        self.ages = Some(ages);
+    }
+}
+
+/// Undo
+///
+/// Undo is opt-in, and only remembers the cells each step changed rather than whole boards. Sparse boards change
+/// very few cells per step, so this is much cheaper than keeping snapshots around.
+impl Life {
+    /// Starts remembering what the last `max_steps` steps changed, so that they can be undone
+    ///
+    /// Only steps are recorded. Cells changed with [`Life::set()`] in between steps stay changed after an undo. A
+    /// `max_steps` of `0` remembers nothing at all.
+    pub fn enable_undo(&mut self, max_steps: usize) {
+        self.undo = Some(UndoLog {
+            steps: VecDeque::with_capacity(max_steps),
+            max_steps,
+        });
+    }
+
+    /// Stops recording steps, and frees the undo history
+    pub fn disable_undo(&mut self) {
+        self.undo = None;
+    }
+
+    /// How many steps [`Life::undo()`] can currently take back
+    pub fn undo_depth(&self) -> usize {
+        self.undo.as_ref().map_or(0, |undo| undo.steps.len())
+    }
+
+    /// How many changed cells are remembered across the whole undo history
+    pub fn undo_cell_count(&self) -> usize {
+        self.undo
+            .as_ref()
+            .map_or(0, |undo| undo.steps.iter().map(Vec::len).sum())
+    }
+
+    /// Takes back the most recent step, restoring every cell it changed and the generation counter
+    ///
+    /// Returns `false` if there was nothing to undo. Cell ages (see [`Life::enable_aging()`]) are not restored.
+    pub fn undo(&mut self) -> bool {
+        let Some(diffs) = self.undo.as_mut().and_then(|undo| undo.steps.pop_back()) else {
+            return false;
+        };
+
+        for diff in diffs.iter().rev() {
+            self.cells.set(diff.x, diff.y, diff.was_alive);
+        }
+        self.generation = self.generation.saturating_sub(1);
+
+        true
+    }
+
+    /// Drops the undo history, but keeps recording
+    fn forget_undo(&mut self) {
+        if let Some(undo) = &mut self.undo {
+            undo.steps.clear();
+        }
+    }
+}
+
+/// Parallel stepping
+///
+/// Each cell's next state only reads the current board, so rows can be worked out on separate threads and written
+/// straight into the shadow board.
+#[cfg(feature = "rayon")]
+impl Life {
+    /// Steps the simulation once like [`Life::step()`], but splits the rows across rayon's thread pool
+    ///
+    /// The board, generation, ages, and undo history all come out exactly as `step()` would leave them. That includes
+    /// slow motion (see [`Life::set_slow_motion()`]), where calls between generations don't step and return `0`.
+    pub fn step_parallel(&mut self) -> u32 {
+        use rayon::prelude::*;
+
+        self.calls_since_generation += 1;
+        if self.calls_since_generation < self.calls_per_generation {
+            return 0;
+        }
+        self.calls_since_generation = 0;
+
+        let stride = (self.width as usize).div_ceil(8);
+        let record_undo = self.undo.is_some();
+
+        // Borrow the shadow board separately, so every thread can read `self` while writing to its own row
+        let mut shadow = core::mem::replace(&mut self.shadow, BitGrid::new(0, 0));
+        let this = &*self;
+        let rows: Vec<(u32, Vec<CellDiff>)> = shadow
+            .as_mut_bytes()
+            .par_chunks_mut(stride)
+            .enumerate()
+            .map(|(y, row)| {
+                let y = y as i16;
+                let mut n_changed = 0;
+                let mut diffs = vec![];
+
+                for x in 0..this.width {
+                    let is_alive = this.next_state(x, y);
+                    let (byte, bit) = (x as usize / 8, x % 8);
+                    if is_alive {
+                        row[byte] |= 1 << bit;
+                    } else {
+                        row[byte] &= !(1 << bit);
+                    }
+
+                    if this.get(x, y) != is_alive {
+                        n_changed += 1;
+                        if record_undo {
+                            diffs.push(CellDiff {
+                                x,
+                                y,
+                                was_alive: !is_alive,
+                            });
+                        }
+                    }
+                }
+
+                (n_changed, diffs)
+            })
+            .collect();
+        self.shadow = shadow;
+
+        core::mem::swap(&mut self.cells, &mut self.shadow);
+        self.generation += 1;
+
+        if let Some(ages) = &mut self.ages {
+            let (width, max_age, cells) = (self.width, self.max_age, &self.cells);
+            ages.par_chunks_mut(width as usize)
+                .enumerate()
+                .for_each(|(y, ages)| {
+                    for (x, age) in ages.iter_mut().enumerate() {
+                        *age = if cells.get(x as i16, y as i16) {
+                            max_age
+                        } else {
+                            age.saturating_sub(1)
+                        };
+                    }
+                });
+        }
+
+        let n_changed = rows.iter().map(|(n, _)| n).sum();
+        let diffs = record_undo.then(|| rows.into_iter().flat_map(|(_, diffs)| diffs).collect());
+        self.record_undo(diffs);
+
+        n_changed
+    }
+}
+
+/// Analysis
+impl Life {
+    /// Steps the board up to `max_steps` times to work out what it settles into
+    ///
+    /// Repeats are spotted by hashing every board (see [`BitGrid::fnv1a_hash()`]), so a hash collision could
+    /// misreport a period, but that's astronomically unlikely at these sizes. The board is left wherever it stopped.
+    ///
+    /// Note that the board wraps, so gliders eventually come back around. Boards that would grow forever on an infinite
+    /// plane may still end up classified as something else given enough steps.
+    pub fn classify(&mut self, max_steps: u32) -> Classification {
+        // (hash, generation) of every board we've seen, to look for repeats
+        let mut seen: Vec<(u64, u64)> = vec![];
+        let mut populations: Vec<i32> = vec![];
+        let start = self.generation;
+
+        for _ in 0..=max_steps {
+            let population = self.cells.count_ones();
+            if population == 0 {
+                return Classification::Extinct;
+            }
+
+            let hash = self.cells.fnv1a_hash();
+            if let Some(&(_, then)) = seen.iter().find(|&&(h, _)| h == hash) {
+                let period = self.generation - then;
+                return if period == 1 {
+                    Classification::Stable(then - start)
+                } else {
+                    Classification::Oscillator(period)
+                };
+            }
+
+            seen.push((hash, self.generation));
+            populations.push(population);
+
+            if populations.len() <= max_steps as usize {
+                // Not `step()`, so slow motion doesn't look like a still life
+                self.step_stats();
+            }
+        }
+
+        // Compare the biggest population early on with the biggest one at the end, since oscillating parts of
+        // growing patterns (like a glider gun) make the population wobble from step to step
+        let third = populations.len() / 3;
+        let early = populations[..third].iter().max();
+        let late = populations[populations.len() - third..].iter().max();
+        match (early, late) {
+            (Some(early), Some(late)) if late > early => Classification::Growing,
+            _ => Classification::Unknown,
+        }
+    }
+
+    /// Whether there's a glider exactly like [`Life::write_right_glider()`] writes with its top left at `(x, y)`, with
+    /// nothing alive in the ring of cells around it
+    ///
+    /// Coordinates wrap like [`Life::sample_wrapped()`].
+    pub fn is_lone_right_glider(&self, x: i16, y: i16) -> bool {
+        const GLIDER: [&str; 3] = [".O.", "..O", "OOO"];
+
+        // Start with the top middle cell, since it's alone in its row and rules out most spots straight away
+        if !self.sample_wrapped(x as i32 + 1, y as i32) {
+            return false;
+        }
+
+        for dy in -1..=3_i32 {
+            for dx in -1..=3_i32 {
+                // The ring around the glider is off the ends of `GLIDER`, and has to be dead
+                let row = usize::try_from(dy).ok().and_then(|dy| GLIDER.get(dy));
+                let cell = row.and_then(|row| row.as_bytes().get(usize::try_from(dx).ok()?));
+                let expected = cell == Some(&b'O');
+
+                if self.sample_wrapped(x as i32 + dx, y as i32 + dy) != expected {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Patterns
+///
+/// The board is a torus, so patterns written near an edge wrap around to the opposite side rather than being dropped.
+/// Each writer returns [`Placed::Clipped`] when that happens, so callers can avoid spots where a pattern would be cut.
+impl Life {
+    /// Whether a `width` x `height` pattern with its top left at `(x, y)` fits on the board
+    fn placement(&self, x: i16, y: i16, width: i16, height: i16) -> Placed {
+        let fits_x = 0 <= x && x as i32 + width as i32 <= self.width() as i32;
+        let fits_y = 0 <= y && y as i32 + height as i32 <= self.height() as i32;
+
+        if fits_x && fits_y {
+            Placed::Whole
+        } else {
+            Placed::Clipped
+        }
+    }
+
+    /// Writes right-facing glider with its corner at `(x, y)`
+    ///
+    /// # Cell info
+    /// A right-facing glider looks like this:
+    /// ```txt
+    /// .O.
+    /// ..O
+    /// OOO
+    /// ```
+    ///
+    /// Where the top left is `(x, y)`.
+    #[track_caller]
+    pub fn write_right_glider(&mut self, x: i16, y: i16) -> Placed {
+        self.set(x + 0, y + 0, false);
+        self.set(x + 1, y + 0, true);
+        self.set(x + 2, y + 0, false);
+
+        self.set(x + 0, y + 1, false);
+        self.set(x + 1, y + 1, false);
+        self.set(x + 2, y + 1, true);
+
+        self.set(x + 0, y + 2, true);
+        self.set(x + 1, y + 2, true);
+        self.set(x + 2, y + 2, true);
+
+        self.placement(x, y, 3, 3)
+    }
+
+    /// Writes a right-facing glider in the corner of every `spacing` x `spacing` tile of the board
+    ///
+    /// Tiles start at `(0, 0)`, and any partial tiles along the right and bottom edges are left empty, so gliders
+    /// keep their distance across the wrapped edges too. Cells between the gliders are left alone, so call
+    /// [`Self::clear()`] first for a clean field.
+    ///
+    /// With a `spacing` of at least 5, no dead cell touches two gliders, so they all fly off together forever for a
+    /// busy, repeatable workload. Closer than that and they collide into something else entirely.
+    ///
+    /// Returns how many gliders were written.
+    ///
+    /// # Panics
+    /// Panics if `spacing` is `0`.
+    #[track_caller]
+    pub fn fill_glider_field(&mut self, spacing: usize) -> usize {
+        assert!(spacing > 0, "Gliders need a spacing of at least 1");
+
+        let across = self.width() as usize / spacing;
+        let down = self.height() as usize / spacing;
+        for ty in 0..down {
+            for tx in 0..across {
+                self.write_right_glider((tx * spacing) as i16, (ty * spacing) as i16);
+            }
+        }
+
+        across * down
+    }
+
+    /// Writes left-facing glider with its corner at `(x, y)`
+    ///
+    /// # Cell info
+    /// A left-facing glider looks like this:
+    /// ```txt
+    /// .O.
+    /// O.
+    /// OOO
+    /// ```
+    ///
+    /// Where the top left is `(x, y)`.
+    #[track_caller]
+    pub fn write_left_glider(&mut self, x: i16, y: i16) -> Placed {
+        self.set(x + 0, y + 0, false);
+        self.set(x + 1, y + 0, true);
+        self.set(x + 2, y + 0, false);
+
+        self.set(x + 0, y + 1, true);
+        self.set(x + 1, y + 1, false);
+        self.set(x + 2, y + 1, false);
+
+        self.set(x + 0, y + 2, true);
+        self.set(x + 1, y + 2, true);
+        self.set(x + 2, y + 2, true);
+
+        self.placement(x, y, 3, 3)
+    }
+
+    /// Toggles every cell that's set in `pattern`, with its top left corner at `(x, y)`
+    ///
+    /// Unlike the other writers, cells that land off the board are dropped rather than wrapped. Cells that are clear
+    /// in `pattern` are left alone, so stamping the same pattern in the same spot again restores the board.
+    pub fn xor_stamp(&mut self, x: i16, y: i16, pattern: &BitGrid) -> Placed {
+        for py in 0..pattern.height() {
+            for px in 0..pattern.width() {
+                if !pattern.get(px, py) {
+                    continue;
+                }
+
+                let (cx, cy) = (x as i32 + px as i32, y as i32 + py as i32);
+                if (0..self.width() as i32).contains(&cx) && (0..self.height() as i32).contains(&cy)
+                {
+                    let (cx, cy) = (cx as i16, cy as i16);
+                    self.set(cx, cy, !self.get(cx, cy));
+                }
+            }
+        }
+
+        self.placement(x, y, pattern.width(), pattern.height())
+    }
+
+    /// Writes a Gosper glider gun with its top left corner at `(x, y)`
+    ///
+    /// The gun takes up a 36x9 region and fires a right-facing glider down and to the right every 30 generations.
+    ///
+    /// Every cell in that region is written, dead ones included, so anything already there (like gliders from another
+    /// gun) is cleared out rather than mixed in with the gun.
+    ///
+    /// # Cell info
+    /// A Gosper glider gun looks like this:
+    /// ```txt
+    /// ........................O...........
+    /// ......................O.O...........
+    /// ............OO......OO............OO
+    /// ...........O...O....OO............OO
+    /// OO........O.....O...OO..............
+    /// OO........O...O.OO....O.O...........
+    /// ..........O.....O.......O...........
+    /// ...........O...O....................
+    /// ............OO......................
+    /// ```
+    ///
+    /// Where the top left is `(x, y)`.
+    #[track_caller]
+    pub fn write_glider_gun(&mut self, x: i16, y: i16) -> Placed {
+        #[rustfmt::skip]
+        const GUN: [&str; 9] = [
+            "........................O...........",
+            "......................O.O...........",
+            "............OO......OO............OO",
+            "...........O...O....OO............OO",
+            "OO........O.....O...OO..............",
+            "OO........O...O.OO....O.O...........",
+            "..........O.....O.......O...........",
+            "...........O...O....................",
+            "............OO......................",
+        ];
+
+        for (dy, row) in GUN.iter().enumerate() {
+            for (dx, c) in row.chars().enumerate() {
+                self.set(x + dx as i16, y + dy as i16, c == 'O');
+            }
+        }
+
+        self.placement(x, y, 36, 9)
+    }
+
+    /// Sets the live cells of the pattern called `name` in [`PATTERNS`], with its top left corner at `(x, y)`
+    ///
+    /// Cells around the pattern are left alone, so clear a spot first if it needs room to grow. Returns `false`, without
+    /// touching the board, if there's no pattern called `name`.
+    pub fn place_named(&mut self, name: &str, x: i16, y: i16) -> bool {
+        let Some((_, cells)) = PATTERNS.iter().find(|(n, _)| *n == name) else {
+            return false;
+        };
+
+        for &(dx, dy) in *cells {
+            self.set(x + dx, y + dy, true);
+        }
+
+        true
+    }
+}
+
+/// Named patterns for [`Life::place_named()`], as the `(x, y)` of each live cell relative to the top left corner
+///
+/// These are the classics, in rough order of size, for demos that want to cycle through something interesting.
+#[rustfmt::skip]
+pub const PATTERNS: &[(&str, &[(i16, i16)])] = &[
+    // Still lifes
+    ("block", &[(0, 0), (1, 0), (0, 1), (1, 1)]),
+    ("beehive", &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)]),
+
+    // Oscillators
+    ("blinker", &[(0, 0), (1, 0), (2, 0)]),
+    ("toad", &[(1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1)]),
+
+    // Spaceships
+    ("glider", &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]),
+    ("lwss", &[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)]),
+
+    // Methuselahs, which take a long time to settle down
+    ("r-pentomino", &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)]),
+    ("diehard", &[(6, 0), (0, 1), (1, 1), (1, 2), (5, 2), (6, 2), (7, 2)]),
+    ("acorn", &[(1, 0), (3, 1), (0, 2), (1, 2), (4, 2), (5, 2), (6, 2)]),
+];
+
+/// `std`-only functions
+#[cfg(feature = "std")]
+impl Life {
+    /// Prints the state of the board to `stdout`
+    pub fn print_ascii(&self) {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if self.get(x, y) {
+                    print!("O");
+                } else {
+                    print!(".");
+                }
+            }
+            println!();
+        }
+        println!();
+    }
+}
+
+/// Steps every simulation in `sims` once, spreading them across rayon's thread pool, and returns each one's `step()`
+/// result
+///
+/// The boards are independent, so this gives exactly the same results as stepping each of them in turn.
+#[cfg(feature = "rayon")]
+pub fn par_step_many(sims: &mut [Life]) -> Vec<u32> {
+    use rayon::prelude::*;
+
+    sims.par_iter_mut().map(Life::step).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rand::{rngs::SmallRng, SeedableRng};
+    use rstest::*;
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn check_par_step_many_matches_serial() {
+        let mut rng = SmallRng::seed_from_u64(0xC0FFEE);
+        let mut parallel: Vec<Life> = (0..13)
+            .map(|i| {
+                let mut life = Life::new(20 + i, 17);
+                life.clear_random(&mut rng);
+                life
+            })
+            .collect();
+        let mut serial = parallel.clone();
+
+        for _ in 0..10 {
+            let par_counts = par_step_many(&mut parallel);
+            let serial_counts: Vec<u32> = serial.iter_mut().map(|life| life.step()).collect();
+            assert_eq!(par_counts, serial_counts);
+        }
+
+        for (par, ser) in parallel.iter().zip(&serial) {
+            assert_eq!(par.as_bitgrid(), ser.as_bitgrid());
+            assert_eq!(par.generation(), ser.generation());
+        }
+
+        // Nothing to do is fine too
+        assert_eq!(par_step_many(&mut []), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn check_bool_buffer_round_trip() {
+        let mut life = Life::new(7, 4);
+        life.write_right_glider(1, 0);
+        life.set(6, 3, true);
+
+        let mut cells = vec![false; 7 * 4];
+        assert_eq!(life.copy_to_bool_buffer(&mut cells), Ok(()));
+
+        #[rustfmt::skip]
+        let expected = [
+            false, false, true,  false, false, false, false,
+            false, false, false, true,  false, false, false,
+            false, true,  true,  true,  false, false, false,
+            false, false, false, false, false, false, true,
+        ];
+        assert_eq!(cells, expected);
+
+        let mut copy = Life::new(7, 4);
+        copy.set(0, 0, true);
+        assert_eq!(copy.set_from_bool_buffer(&cells), Ok(()));
+        assert_eq!(copy.as_bitgrid(), life.as_bitgrid());
+    }
+
+    #[test]
+    fn check_bool_buffer_wrong_len() {
+        let mut life = Life::new(7, 4);
+        life.set(3, 3, true);
+
+        let mut short = vec![false; 27];
+        assert_eq!(
+            life.copy_to_bool_buffer(&mut short),
+            Err(LenMismatch {
+                expected: 28,
+                actual: 27
+            })
+        );
+
+        let long = vec![true; 29];
+        assert_eq!(
+            life.set_from_bool_buffer(&long),
+            Err(LenMismatch {
+                expected: 28,
+                actual: 29
+            })
+        );
+        assert_eq!(life.as_bitgrid().count_ones(), 1);
+    }
+
+    /// A deliberately simple Life, on a torus, to check `Life::step()` against
+    fn reference_step(cells: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let height = cells.len() as i32;
+        let width = cells[0].len() as i32;
+
+        let mut next = cells.to_vec();
+        for y in 0..height {
+            for x in 0..width {
+                let mut live_count = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if (dx, dy) == (0, 0) {
+                            continue;
+                        }
+                        let nx = (x + dx).rem_euclid(width) as usize;
+                        let ny = (y + dy).rem_euclid(height) as usize;
+                        live_count += cells[ny][nx] as u32;
+                    }
+                }
+
+                let is_alive = cells[y as usize][x as usize];
+                next[y as usize][x as usize] = matches!((is_alive, live_count), (true, 2) | (_, 3));
+            }
+        }
+
+        next
+    }
+
+    fn to_cells(life: &Life) -> Vec<Vec<bool>> {
+        (0..life.height())
+            .map(|y| (0..life.width()).map(|x| life.get(x, y)).collect())
+            .collect()
+    }
+
+    #[rstest]
+    #[case::top_left(0, 0)]
+    #[case::top_right(8, 0)]
+    #[case::bottom_left(0, 5)]
+    #[case::bottom_right(8, 5)]
+    #[case::top_edge(4, 0)]
+    #[case::bottom_edge(4, 5)]
+    #[case::left_edge(0, 3)]
+    #[case::right_edge(8, 3)]
+    fn check_step_matches_reference_at_edges(#[case] x: i16, #[case] y: i16) {
+        let mut life = Life::new(9, 6);
+
+        // A block with one more cell off its bottom right corner, straddling the cell, so it has neighbors on both
+        // sides of any edge it touches
+        for (dx, dy) in [(-1, -1), (0, -1), (-1, 0), (1, 1)] {
+            life.set(x + dx, y + dy, true);
+        }
+        life.set(x, y, true);
+
+        let mut expected = to_cells(&life);
+        for _ in 0..8 {
+            expected = reference_step(&expected);
+            life.step();
+            assert_eq!(to_cells(&life), expected);
+        }
+    }
+
+    #[test]
+    fn check_step_matches_reference_random() {
+        let mut rng = SmallRng::seed_from_u64(2469);
+        for (width, height) in [(3, 3), (8, 8), (13, 5), (1, 7)] {
+            let mut life = Life::new(width, height);
+            life.clear_random(&mut rng);
+
+            let mut expected = to_cells(&life);
+            for _ in 0..16 {
+                expected = reference_step(&expected);
+                life.step();
+                assert_eq!(to_cells(&life), expected, "{width}x{height}");
+            }
+        }
+    }
+
+    #[test]
+    fn check_cell_age() {
+        let mut life = Life::new(5, 5);
+
+        // A blinker, flipping between horizontal and vertical
+        life.set(1, 2, true);
+        life.set(2, 2, true);
+        life.set(3, 2, true);
+
+        // Without aging, ages just say whether a cell is alive
+        assert_eq!(life.cell_age(1, 2), u8::MAX);
+        assert_eq!(life.cell_age(2, 1), 0);
+
+        life.enable_aging(3);
+        assert!(life.is_aging());
+        assert_eq!(life.cell_age(1, 2), 3);
+        assert_eq!(life.cell_age(2, 1), 0);
+
+        // (2, 1) is born, and (1, 2) dies
+        life.step();
+        assert_eq!(life.cell_age(2, 1), 3);
+        assert_eq!(life.cell_age(1, 2), 2);
+        assert_eq!(life.cell_age(2, 2), 3);
+
+        // (1, 2) is born again, so its age resets, while (2, 1) starts fading
+        life.step();
+        assert_eq!(life.cell_age(1, 2), 3);
+        assert_eq!(life.cell_age(2, 1), 2);
+
+        // Once a cell is gone for good it counts down to 0 and stays there
+        life.clear();
+        assert_eq!(life.cell_age(2, 2), 0);
+        life.set(0, 0, true);
+        assert_eq!(life.cell_age(0, 0), 3);
+        assert_eq!(life.cell_age(5, 5), 3);
+        for expected in [2, 1, 0, 0] {
+            life.step();
+            assert_eq!(life.cell_age(0, 0), expected);
+        }
+
+        life.disable_aging();
+        assert!(!life.is_aging());
+        assert_eq!(life.cell_age(0, 0), 0);
+    }
+
+    #[test]
+    fn check_step_stats_blinker() {
+        let mut life = Life::new(5, 5);
+        life.set(1, 2, true);
+        life.set(2, 2, true);
+        life.set(3, 2, true);
+
+        // The ends die and the middle of the other axis is born, every step
+        for _ in 0..4 {
+            assert_eq!(
+                life.step_stats(),
+                StepStats {
+                    births: 2,
+                    deaths: 2
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn check_step_stats_dying() {
+        // A diagonal line loses its ends each step, with nothing being born
+        let mut life = Life::new(8, 8);
+        for i in 1..6 {
+            life.set(i, i, true);
+        }
+
+        assert_eq!(
+            life.step_stats(),
+            StepStats {
+                births: 0,
+                deaths: 2
+            }
+        );
+        assert_eq!(life.step(), 2);
+        assert_eq!(
+            life.step_stats(),
+            StepStats {
+                births: 0,
+                deaths: 1
+            }
+        );
+        assert_eq!(life.step_stats(), StepStats::default());
+        assert_eq!(life.as_bitgrid().count_ones(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_serde_round_trip() {
+        let mut life = Life::new(9, 7);
+        life.write_right_glider(2, 1);
+        life.step();
+        life.step();
+
+        let json = serde_json::to_string(&life).unwrap();
+        let mut round_tripped: Life = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.as_bitgrid(), life.as_bitgrid());
+        assert_eq!(round_tripped.generation(), 2);
+
+        // And it keeps going the same way
+        assert_eq!(round_tripped.step(), life.step());
+        assert_eq!(round_tripped.as_bitgrid(), life.as_bitgrid());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn check_serde_rejects_empty_board() {
+        let json = r#"{"cells":{"width":0,"height":3,"bytes":[]},"generation":0}"#;
+        let err = serde_json::from_str::<Life>(json).err().unwrap();
+        assert!(err.to_string().contains("at least one cell"), "{err}");
+    }
+
+    #[test]
+    fn check_from_luma() {
+        #[rustfmt::skip]
+        let luma = [
+            0x00, 0x10, 0x20, 0x30,
+            0x7F, 0x80, 0x81, 0xFF,
+            0xFF, 0x00, 0xFF, 0x00,
+        ];
+        let life = Life::from_luma(4, 3, &luma, 0x80).unwrap();
+
+        assert_eq!((life.width(), life.height()), (4, 3));
+        assert_eq!(life.generation(), 0);
+
+        let mut cells = [false; 12];
+        life.copy_to_bool_buffer(&mut cells).unwrap();
+        #[rustfmt::skip]
+        assert_eq!(cells, [
+            false, false, false, false,
+            false, false, true,  true,
+            true,  false, true,  false,
+        ]);
+
+        // Everything is brighter than 0 except black
+        let life = Life::from_luma(4, 3, &luma, 0).unwrap();
+        assert_eq!(life.as_bitgrid().count_ones(), 9);
+    }
+
+    #[test]
+    fn check_from_luma_wrong_len() {
+        assert_eq!(
+            Life::from_luma(4, 3, &[0; 11], 0x80).err(),
+            Some(LenMismatch {
+                expected: 12,
+                actual: 11
+            })
+        );
+    }
+
+    #[test]
+    fn check_from_bitgrid() {
+        let mut cells = BitGrid::new(5, 4);
+        cells.set(1, 2, true);
+        cells.set(4, 3, true);
+
+        let life = Life::from_bitgrid(cells.clone());
+        assert_eq!((life.width(), life.height()), (5, 4));
+        assert_eq!(life.as_bitgrid(), &cells);
+    }
+
+    #[rstest]
+    #[case::middle(5, 4, 10, 6)]
+    #[case::corner(-3, -2, 6, 5)]
+    #[case::whole_board(0, 0, 20, 12)]
+    #[case::past_the_edges(15, 9, 100, 100)]
+    fn check_step_region(#[case] x: i16, #[case] y: i16, #[case] w: usize, #[case] h: usize) {
+        let mut before = Life::new(20, 12);
+        before.clear_random(&mut SmallRng::seed_from_u64(2525));
+
+        let mut full = before.clone();
+        full.step();
+
+        let mut region = before.clone();
+        region.enable_undo(1);
+        let n_changed = region.step_region(x, y, w, h);
+        assert_eq!(region.generation(), 1);
+
+        let mut expected_changed = 0;
+        for cy in 0..12 {
+            for cx in 0..20 {
+                let inside = (x..x + w as i16).contains(&cx) && (y..y + h as i16).contains(&cy);
+                if inside {
+                    assert_eq!(
+                        region.get(cx, cy),
+                        full.get(cx, cy),
+                        "inside at ({cx}, {cy})"
+                    );
+                    expected_changed += (before.get(cx, cy) != full.get(cx, cy)) as u32;
+                } else {
+                    assert_eq!(
+                        region.get(cx, cy),
+                        before.get(cx, cy),
+                        "outside at ({cx}, {cy})"
+                    );
+                }
+            }
+        }
+        assert_eq!(n_changed, expected_changed);
+
+        assert!(region.undo());
+        assert_eq!(region.as_bitgrid(), before.as_bitgrid());
+    }
+
+    #[test]
+    fn check_step_region_reads_outside() {
+        // A blinker whose middle is the only cell in the region
+        let mut life = Life::new(5, 5);
+        life.set(1, 2, true);
+        life.set(2, 2, true);
+        life.set(3, 2, true);
+
+        assert_eq!(life.step_region(2, 1, 1, 1), 1);
+        assert!(life.get(2, 1));
+
+        // The ends are outside, so they stay put
+        assert!(life.get(1, 2) && life.get(3, 2));
+        assert!(!life.get(2, 3));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[rstest]
+    #[case::square(256, 256)]
+    #[case::ragged(37, 21)]
+    #[case::one_row(70, 1)]
+    fn check_step_parallel(#[case] width: usize, #[case] height: usize) {
+        let mut rng = SmallRng::seed_from_u64(2527);
+
+        for _ in 0..4 {
+            let mut serial = Life::new(width, height);
+            serial.clear_random(&mut rng);
+            serial.enable_aging(3);
+            serial.enable_undo(8);
+            let mut parallel = serial.clone();
+
+            for _ in 0..8 {
+                assert_eq!(parallel.step_parallel(), serial.step());
+                assert_eq!(parallel.as_bitgrid(), serial.as_bitgrid());
+                assert_eq!(parallel.generation(), serial.generation());
+            }
+            for y in 0..height as i16 {
+                for x in 0..width as i16 {
+                    assert_eq!(parallel.cell_age(x, y), serial.cell_age(x, y));
+                }
+            }
+
+            // Undo walks back through the same boards
+            while serial.undo() {
+                assert!(parallel.undo());
+                assert_eq!(parallel.as_bitgrid(), serial.as_bitgrid());
+            }
+            assert!(!parallel.undo());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn check_step_parallel_slow_motion() {
+        let mut serial = Life::new(8, 8);
+        serial.write_right_glider(1, 1);
+        serial.set_slow_motion(3);
+        let mut parallel = serial.clone();
+
+        for call in 1..=9 {
+            let n_changed = parallel.step_parallel();
+            assert_eq!(n_changed, serial.step(), "call #{call}");
+            assert_eq!(n_changed != 0, call % 3 == 0, "call #{call}");
+            assert_eq!(parallel.as_bitgrid(), serial.as_bitgrid(), "call #{call}");
+        }
+        assert_eq!(parallel.generation(), 3);
+
+        // Both kinds of call count towards the same generation
+        assert_eq!(parallel.step_parallel(), 0);
+        assert_eq!(parallel.step(), 0);
+        assert_ne!(parallel.step_parallel(), 0);
+        assert_eq!(parallel.generation(), 4);
+    }
+
+    #[test]
+    fn check_wrapping_matches_bitgrid_idx() {
+        const W: i16 = 5;
+        const H: i16 = 3;
+        let grid = BitGrid::new(W as usize, H as usize);
+        let stride = (W as usize).div_ceil(8);
+
+        for y in -3 * H..3 * H {
+            for x in -3 * W..3 * W {
+                // Where `BitGrid::idx()` puts this coordinate
+                let (idx, bit) = grid.idx(x, y);
+                let cell = (
+                    (idx % stride * 8) as i16 + bit as i16,
+                    (idx / stride) as i16,
+                );
+                assert_eq!(cell, (wrap_coord(x as i32, W), wrap_coord(y as i32, H)));
+
+                // `Life` finds that same cell however it's asked, which is also how `step()` finds neighbors
+                let mut life = Life::new(W as usize, H as usize);
+                life.enable_aging(9);
+                life.set(cell.0, cell.1, true);
+
+                assert!(life.get(x, y), "get({x}, {y})");
+                assert!(
+                    life.sample_wrapped(x as i32, y as i32),
+                    "sample_wrapped({x}, {y})"
+                );
+                assert_eq!(life.cell_age(x, y), 9, "cell_age({x}, {y})");
+                assert_eq!(life.as_bitgrid().count_ones(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn check_reseed_policy_on_freeze() {
+        // A block never changes, so it freezes on the first step
+        let mut life = Life::new(8, 8);
+        life.set(2, 2, true);
+        life.set(3, 2, true);
+        life.set(2, 3, true);
+        life.set(3, 3, true);
+
+        let policy = ReseedPolicy::new(1000);
+        let n_changed = life.step();
+        assert!(policy.should_reseed(n_changed, life.generation()));
+
+        let patient = ReseedPolicy {
+            on_freeze: false,
+            ..policy
+        };
+        assert!(!patient.should_reseed(n_changed, life.generation()));
+    }
+
+    #[test]
+    fn check_reseed_policy_max_generations() {
+        // A glider flies around forever, so only old age ends it
+        let mut life = Life::new(16, 16);
+        life.write_right_glider(4, 4);
+
+        let policy = ReseedPolicy::new(100);
+        let mut reseeded_at = None;
+        for _ in 0..200 {
+            let n_changed = life.step();
+            if policy.should_reseed(n_changed, life.generation()) {
+                reseeded_at = Some(life.generation());
+                break;
+            }
+        }
+        assert_eq!(reseeded_at, Some(100));
+
+        let forever = ReseedPolicy {
+            max_generations: None,
+            on_freeze: true,
+        };
+        assert!(!forever.should_reseed(5, u64::MAX));
+    }
+
+    #[test]
+    fn check_copy_into_bitgrid() {
+        let mut life = Life::new(21, 9);
+        life.clear_random(&mut SmallRng::seed_from_u64(2533));
+
+        let mut dst = BitGrid::new(21, 9);
+        assert_eq!(life.copy_into_bitgrid(&mut dst), Ok(()));
+        for y in 0..life.height() {
+            for x in 0..life.width() {
+                assert_eq!(dst.get(x, y), life.get(x, y), "at ({x}, {y})");
+            }
+        }
+
+        let mut wrong = BitGrid::new(9, 21);
+        assert_eq!(
+            life.copy_into_bitgrid(&mut wrong),
+            Err(DimsMismatch {
+                expected: (9, 21),
+                actual: (21, 9),
+            })
+        );
+        assert!(wrong.is_empty());
+    }
+
+    #[test]
+    fn check_sample_wrapped() {
+        let mut life = Life::new(5, 3);
+        life.set(0, 0, true);
+        life.set(4, 2, true);
+
+        assert!(life.sample_wrapped(0, 0));
+        assert!(life.sample_wrapped(4, 2));
+        assert!(!life.sample_wrapped(1, 0));
+
+        // Negative coordinates come in from the far edge
+        assert!(life.sample_wrapped(-1, -1));
+        assert!(life.sample_wrapped(-5, -3));
+        assert!(!life.sample_wrapped(-2, -1));
+
+        // Oversized coordinates land on later copies of the board, even past `i16::MAX`
+        assert!(life.sample_wrapped(5, 3));
+        assert!(life.sample_wrapped(9, 5));
+        assert!(life.sample_wrapped(100_000, 300_000));
+        assert!(!life.sample_wrapped(100_001, 300_000));
+
+        // Every tile of a viewport bigger than the board matches the board
+        for y in -6_i32..9 {
+            for x in -10_i32..15 {
+                let (bx, by) = (x.rem_euclid(5) as i16, y.rem_euclid(3) as i16);
+                assert_eq!(life.sample_wrapped(x, y), life.get(bx, by), "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn check_from_bitgrid_glider_steps() {
+        // .O.
+        // ..O
+        // OOO
+        let mut cells = BitGrid::new(8, 8);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            cells.set(x, y, true);
+        }
+
+        let mut life = Life::from_bitgrid(cells);
+        for _ in 0..4 {
+            life.step();
+        }
+
+        // A glider moves one cell down and to the right every 4 generations
+        let mut expected = Life::new(8, 8);
+        expected.write_right_glider(1, 1);
+        assert_eq!(life.as_bitgrid(), expected.as_bitgrid());
+        assert_eq!(life.generation(), 4);
+    }
+
+    #[test]
+    fn check_von_neumann_blinker() {
+        let mut moore = Life::new(5, 5);
+        moore.set(1, 2, true);
+        moore.set(2, 2, true);
+        moore.set(3, 2, true);
+
+        let mut von_neumann = moore.clone();
+        von_neumann.set_neighborhood(Neighborhood::VonNeumann);
+        assert_eq!(moore.neighborhood(), Neighborhood::Moore);
+
+        // Moore blinks forever
+        assert_eq!(moore.step(), 4);
+        assert_eq!(moore.as_bitgrid().count_ones(), 3);
+        assert!(moore.get(2, 1) && moore.get(2, 2) && moore.get(2, 3));
+
+        // Von Neumann ends only see the middle, and nothing sees the ends, so only the middle survives and then starves
+        assert_eq!(von_neumann.step(), 2);
+        assert_eq!(von_neumann.as_bitgrid().count_ones(), 1);
+        assert!(von_neumann.get(2, 2));
+
+        assert_eq!(von_neumann.step(), 1);
+        assert_eq!(von_neumann.as_bitgrid().count_ones(), 0);
+    }
+
+    #[test]
+    fn check_von_neumann_ignores_diagonals() {
+        let mut moore = Life::new(5, 5);
+        moore.set(1, 1, true);
+        moore.set(3, 1, true);
+        moore.set(1, 3, true);
+
+        let mut von_neumann = moore.clone();
+        von_neumann.set_neighborhood(Neighborhood::VonNeumann);
+
+        // The center has 3 diagonal neighbors, which is a birth for Moore and nothing for von Neumann
+        moore.step();
+        assert!(moore.get(2, 2));
+
+        von_neumann.step();
+        assert!(!von_neumann.get(2, 2));
+    }
+
+    #[test]
+    fn check_von_neumann_birth() {
+        let mut life = Life::new(5, 5);
+        life.set_neighborhood(Neighborhood::VonNeumann);
+        life.set(2, 1, true);
+        life.set(1, 2, true);
+        life.set(3, 2, true);
+
+        life.step();
+        assert!(life.get(2, 2));
+    }
+
+    #[test]
+    fn check_classify_extinct() {
+        let mut life = Life::new(8, 8);
+        assert_eq!(life.classify(10), Classification::Extinct);
+
+        // A lone cell starves after one step
+        life.set(3, 3, true);
+        assert_eq!(life.classify(10), Classification::Extinct);
+        assert_eq!(life.generation(), 1);
+    }
+
+    #[test]
+    fn check_classify_block() {
+        let mut life = Life::new(8, 8);
+        life.set(3, 3, true);
+        life.set(4, 3, true);
+        life.set(3, 4, true);
+        life.set(4, 4, true);
+
+        assert_eq!(life.classify(10), Classification::Stable(0));
+    }
+
+    #[test]
+    fn check_classify_settles_into_block() {
+        // Three corners of a square fill in the fourth and then stop
+        let mut life = Life::new(8, 8);
+        life.set(3, 3, true);
+        life.set(4, 3, true);
+        life.set(3, 4, true);
+
+        assert_eq!(life.classify(10), Classification::Stable(1));
+    }
+
+    #[test]
+    fn check_classify_counts_from_current_generation() {
+        let mut life = Life::new(8, 8);
+        life.set(3, 3, true);
+        life.set(4, 3, true);
+        life.set(3, 4, true);
+        life.set(4, 4, true);
+        for _ in 0..5 {
+            life.step();
+        }
+
+        assert_eq!(life.classify(10), Classification::Stable(0));
+
+        // Three corners fill in the fourth one step in, no matter how far along the board already was
+        life.set(4, 4, false);
+        assert_eq!(life.classify(10), Classification::Stable(1));
+    }
+
+    #[test]
+    fn check_classify_blinker() {
+        let mut life = Life::new(8, 8);
+        life.set(2, 3, true);
+        life.set(3, 3, true);
+        life.set(4, 3, true);
+
+        assert_eq!(life.classify(10), Classification::Oscillator(2));
+    }
+
+    #[test]
+    fn check_classify_glider_gun() {
+        // Big enough that no gliders wrap around before we give up
+        let mut life = Life::new(80, 60);
+        life.write_glider_gun(1, 1);
+
+        assert_eq!(life.classify(150), Classification::Growing);
+    }
+
+    #[test]
+    fn check_classify_runs_out_of_steps() {
+        // A glider doesn't grow, and can't get back to where it started in so few steps
+        let mut life = Life::new(32, 32);
+        life.write_right_glider(4, 4);
+
+        assert_eq!(life.classify(20), Classification::Unknown);
+    }
+
+    #[test]
+    fn check_classify_ignores_slow_motion() {
+        let mut life = Life::new(32, 32);
+        life.write_right_glider(4, 4);
+        life.set_slow_motion(2);
+
+        assert_eq!(life.classify(20), Classification::Unknown);
+        assert_eq!(life.generation(), 20);
+    }
+
+    #[test]
+    fn check_default() {
+        let life = Life::default();
+        assert_eq!((life.width(), life.height()), (64, 64));
+        assert_eq!(life.generation(), 0);
+        assert!(life.as_bitgrid().is_empty());
+    }
+
+    #[rstest]
+    #[case::no_width(0, 8)]
+    #[case::no_height(8, 0)]
+    #[case::nothing(0, 0)]
+    #[should_panic(expected = "Life board has no cells")]
+    fn check_new_rejects_zero_dims(#[case] width: usize, #[case] height: usize) {
+        let _ = Life::new(width, height);
+    }
+
+    #[test]
+    fn check_new_smallest() {
+        // A single cell is its own neighbor 8 times over, so it never has exactly 2 or 3 live neighbors
+        let mut life = Life::new(1, 1);
+        life.set(0, 0, true);
+        assert_eq!(life.step(), 1);
+        assert!(!life.get(0, 0));
+    }
+
+    #[test]
+    fn check_try_new() {
+        let life = Life::try_new(320, 240).unwrap();
+        assert_eq!((life.width(), life.height()), (320, 240));
+
+        assert_eq!(
+            Life::try_new(40_000, 8).err(),
+            Some(DimsError::TooLong {
+                width: 40_000,
+                height: 8
+            })
+        );
+        assert_eq!(
+            Life::try_new(0, 8).err(),
+            Some(DimsError::Empty {
+                width: 0,
+                height: 8
+            })
+        );
+        assert!(matches!(
+            Life::try_new(30_000, 30_000),
+            Err(DimsError::TooManyBytes { .. })
+        ));
+    }
 
     #[test]
     fn check_square_lives() {
@@ -239,6 +2163,558 @@ mod test {
         assert_eq!(updated, 0);
     }
 
+    #[test]
+    fn check_empty_board_skips_scan() {
+        let mut life = Life::new(8, 8);
+        life.enable_aging(3);
+        life.enable_undo(4);
+        life.set(2, 2, true);
+
+        // A lone cell dies, leaving nothing
+        assert_eq!(life.step(), 1);
+        assert_eq!(life.cell_age(2, 2), 2);
+
+        // Stepping writes every cell of the shadow board, so scribble on it to see whether it gets rewritten
+        life.shadow.invert_in_place();
+        let scribbled = life.shadow.clone();
+        for i in 2..=4 {
+            assert_eq!(life.step_stats(), StepStats::default());
+            assert_eq!(life.generation(), i);
+        }
+        assert_eq!(life.shadow, scribbled);
+        assert_eq!(life.as_bitgrid().count_ones(), 0);
+
+        // Ages still fade, and undo still steps back one generation at a time
+        assert_eq!(life.cell_age(2, 2), 0);
+        assert_eq!(life.undo_depth(), 4);
+        assert!(life.undo());
+        assert_eq!(life.generation(), 3);
+    }
+
+    #[test]
+    fn check_state_hash_detects_desync() {
+        let mut a = Life::new(32, 24);
+        let mut b = Life::new(32, 24);
+        a.clear_random(&mut SmallRng::seed_from_u64(7));
+        b.clear_random(&mut SmallRng::seed_from_u64(7));
+
+        for _ in 0..100 {
+            assert_eq!(a.state_hash(), b.state_hash());
+            a.step();
+            b.step();
+        }
+
+        // Find a dead cell to bring back, so the boards really differ
+        let (x, y) = (0..24)
+            .flat_map(|y| (0..32).map(move |x| (x, y)))
+            .find(|&(x, y)| !b.get(x, y))
+            .unwrap();
+        b.set(x, y, true);
+        assert_ne!(a.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    fn check_resize_keeps_top_left() {
+        let mut life = Life::new(6, 6);
+        life.set(0, 0, true);
+        life.set(5, 5, true);
+        life.step();
+        life.set(0, 0, true);
+        life.set(4, 1, true);
+
+        life.resize(5, 8);
+        assert_eq!(life.as_bitgrid().dims(), (5, 8));
+        assert_eq!(life.generation(), 1);
+        assert!(life.get(0, 0));
+        assert!(life.get(4, 1));
+        assert_eq!(life.as_bitgrid().count_ones(), 2);
+    }
+
+    #[rstest]
+    #[case::grow(12, 10)]
+    #[case::grow_odd(13, 11)]
+    #[case::shrink(4, 6)]
+    fn check_resize_centered_block(#[case] width: usize, #[case] height: usize) {
+        // A block is a still life, so it stays put once it's moved
+        let mut life = Life::new(8, 6);
+        for (x, y) in [(3, 2), (4, 2), (3, 3), (4, 3)] {
+            life.set(x, y, true);
+        }
+        life.enable_aging(5);
+        life.enable_undo(3);
+        life.step();
+
+        life.resize_centered(width, height);
+        assert_eq!(life.as_bitgrid().dims(), (width as i16, height as i16));
+        assert_eq!(life.undo_depth(), 0);
+        assert!(life.is_aging());
+
+        // Same distance from the left and right edges, and from the top and bottom (give or take the odd cell)
+        let cells = life.as_bitgrid();
+        let xs: Vec<i16> = (0..life.width())
+            .filter(|&x| (0..life.height()).any(|y| cells.get(x, y)))
+            .collect();
+        let ys: Vec<i16> = (0..life.height())
+            .filter(|&y| (0..life.width()).any(|x| cells.get(x, y)))
+            .collect();
+        assert_eq!(xs.len(), 2);
+        assert_eq!(ys.len(), 2);
+        let (left, right) = (xs[0], life.width() - 1 - xs[1]);
+        let (top, bottom) = (ys[0], life.height() - 1 - ys[1]);
+        assert_eq!(right - left, width as i16 % 2, "{xs:?}");
+        assert_eq!(bottom - top, height as i16 % 2, "{ys:?}");
+
+        assert_eq!(life.step(), 0);
+        assert_eq!(life.cell_age(xs[0], ys[0]), 5);
+    }
+
+    #[test]
+    fn check_resize_centered_round_trip() {
+        let mut life = Life::new(9, 7);
+        life.write_right_glider(3, 2);
+        let original = life.as_bitgrid().clone();
+
+        // Growing and shrinking back by the same amount puts everything back
+        life.resize_centered(20, 15);
+        life.resize_centered(9, 7);
+        assert_eq!(life.as_bitgrid(), &original);
+    }
+
+    #[test]
+    fn check_generation_counter() {
+        let mut life = Life::new(8, 8);
+        assert_eq!(life.generation(), 0);
+
+        // Counts steps even when nothing changes
+        for i in 1..=5 {
+            life.step();
+            assert_eq!(life.generation(), i);
+        }
+
+        life.clear();
+        assert_eq!(life.generation(), 0);
+
+        life.step();
+        life.step();
+        assert_eq!(life.generation(), 2);
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        life.clear_random(&mut rng);
+        assert_eq!(life.generation(), 0);
+    }
+
+    #[rstest]
+    #[case::right_edge(7, 0)]
+    #[case::bottom_edge(0, 5)]
+    #[case::corner(7, 5)]
+    #[case::far_negative(-17, -13)]
+    fn check_glider_wraps(#[case] x: i16, #[case] y: i16) {
+        let mut life = Life::new(8, 6);
+        assert_eq!(life.write_right_glider(x, y), Placed::Clipped);
+
+        #[rustfmt::skip]
+        let expected = [
+            [false, true,  false],
+            [false, false, true ],
+            [true,  true,  true ],
+        ];
+
+        // Check all nine cells of the glider, reading through wrapped coordinates
+        for (dy, row) in expected.iter().enumerate() {
+            for (dx, &is_alive) in row.iter().enumerate() {
+                let cx = (x + dx as i16).rem_euclid(life.width());
+                let cy = (y + dy as i16).rem_euclid(life.height());
+                assert_eq!(
+                    life.get(cx, cy),
+                    is_alive,
+                    "glider cell ({dx}, {dy}) at ({cx}, {cy})"
+                );
+            }
+        }
+        assert_eq!(life.as_bitgrid().count_ones(), 5);
+    }
+
+    #[rstest]
+    #[case::top_left(0, 0, Placed::Whole)]
+    #[case::bottom_right(5, 3, Placed::Whole)]
+    #[case::right_edge(6, 0, Placed::Clipped)]
+    #[case::bottom_edge(2, 4, Placed::Clipped)]
+    #[case::negative(-1, 2, Placed::Clipped)]
+    fn check_glider_placement(#[case] x: i16, #[case] y: i16, #[case] expected: Placed) {
+        let mut life = Life::new(8, 6);
+        assert_eq!(life.write_right_glider(x, y), expected);
+        assert_eq!(life.write_left_glider(x, y), expected);
+    }
+
+    #[test]
+    fn check_xor_stamp_twice_restores() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut life = Life::new(10, 8);
+        life.clear_random(&mut rng);
+        let original = life.as_bitgrid().clone();
+
+        let mut pattern = BitGrid::new(3, 3);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            pattern.set(x, y, true);
+        }
+
+        assert_eq!(life.xor_stamp(2, 3, &pattern), Placed::Whole);
+        assert_ne!(life.as_bitgrid(), &original);
+        for (x, y) in [(3, 3), (4, 4), (2, 5), (3, 5), (4, 5)] {
+            assert_ne!(life.get(x, y), original.get(x, y), "({x}, {y})");
+        }
+        // Clear cells in the pattern don't touch the board
+        assert_eq!(life.get(2, 3), original.get(2, 3));
+
+        assert_eq!(life.xor_stamp(2, 3, &pattern), Placed::Whole);
+        assert_eq!(life.as_bitgrid(), &original);
+    }
+
+    #[test]
+    fn check_xor_stamp_clips() {
+        let mut life = Life::new(4, 4);
+        let mut pattern = BitGrid::new(3, 3);
+        pattern.invert_in_place();
+
+        assert_eq!(life.xor_stamp(2, -1, &pattern), Placed::Clipped);
+
+        // Only the 2x2 in bounds is toggled, nothing wraps around
+        let mut expected = BitGrid::new(4, 4);
+        for (x, y) in [(2, 0), (3, 0), (2, 1), (3, 1)] {
+            expected.set(x, y, true);
+        }
+        assert_eq!(life.as_bitgrid(), &expected);
+    }
+
+    #[test]
+    fn check_glider_gun_placement() {
+        let mut life = Life::new(40, 10);
+        assert_eq!(life.write_glider_gun(4, 1), Placed::Whole);
+        assert!(life.write_glider_gun(5, 1).is_clipped());
+    }
+
+    #[test]
+    fn check_glider_gun_clears_its_region() {
+        let mut rng = SmallRng::seed_from_u64(2452);
+        let mut life = Life::new(48, 16);
+        life.clear_random(&mut rng);
+        let before = life.as_bitgrid().clone();
+
+        life.write_glider_gun(4, 2);
+
+        let mut gun = Life::new(36, 9);
+        gun.write_glider_gun(0, 0);
+        for y in 0..life.height() {
+            for x in 0..life.width() {
+                let expected = if (4..40).contains(&x) && (2..11).contains(&y) {
+                    gun.get(x - 4, y - 2)
+                } else {
+                    before.get(x, y)
+                };
+                assert_eq!(life.get(x, y), expected, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::sparse(0.25)]
+    #[case::dense(0.75)]
+    fn check_clear_random_region(#[case] density: f32) {
+        let mut rng = SmallRng::seed_from_u64(2543);
+        let mut life = Life::new(64, 48);
+        // Start from a mess, to make sure the margins get cleared
+        life.clear_random(&mut rng);
+
+        life.clear_random_region(&mut rng, 16, 8, 32, 32, density);
+        assert_eq!(life.generation(), 0);
+
+        let mut inside = 0;
+        for y in 0..life.height() {
+            for x in 0..life.width() {
+                if (16..48).contains(&x) && (8..40).contains(&y) {
+                    inside += life.get(x, y) as u32;
+                } else {
+                    assert!(!life.get(x, y), "({x}, {y}) is outside the region");
+                }
+            }
+        }
+
+        let fill = inside as f32 / (32 * 32) as f32;
+        assert!((fill - density).abs() < 0.05, "{fill} filled");
+    }
+
+    #[test]
+    fn check_clear_random_region_clips() {
+        let mut rng = SmallRng::seed_from_u64(2543);
+        let mut life = Life::new(16, 16);
+
+        // Hanging off the bottom right corner doesn't wrap around to the top left
+        life.clear_random_region(&mut rng, 12, 12, 100, 100, 1.0);
+        assert_eq!(life.as_bitgrid().count_ones(), 4 * 4);
+        assert!(life.get(15, 15));
+        assert!(!life.get(0, 0));
+    }
+
+    #[test]
+    fn check_slow_motion() {
+        let mut life = Life::new(8, 8);
+        life.write_right_glider(1, 1);
+        life.set_slow_motion(3);
+        assert_eq!(life.slow_motion(), 3);
+
+        let mut expected = life.clone();
+        expected.set_slow_motion(1);
+
+        for call in 1..=9 {
+            let before = life.as_bitgrid().clone();
+            let n_changed = life.step();
+
+            if call % 3 == 0 {
+                assert_ne!(n_changed, 0, "call #{call}");
+                assert_eq!(n_changed, expected.step(), "call #{call}");
+            } else {
+                assert_eq!(n_changed, 0, "call #{call}");
+                assert_eq!(life.as_bitgrid(), &before, "call #{call}");
+            }
+            assert_eq!(life.as_bitgrid(), expected.as_bitgrid(), "call #{call}");
+        }
+        assert_eq!(life.generation(), 3);
+
+        // Back to full speed
+        life.set_slow_motion(1);
+        assert_ne!(life.step(), 0);
+        assert_eq!(life.generation(), 4);
+    }
+
+    #[test]
+    fn check_slow_motion_survives_resize() {
+        let mut life = Life::new(8, 8);
+        life.write_right_glider(1, 1);
+        life.set_slow_motion(3);
+        life.step();
+
+        life.resize(12, 12);
+        assert_eq!(life.slow_motion(), 3);
+        life.resize_centered(16, 16);
+        assert_eq!(life.slow_motion(), 3);
+
+        // Still one call in, so the third call steps
+        assert_eq!(life.step(), 0);
+        assert_ne!(life.step(), 0);
+        assert_eq!(life.generation(), 1);
+    }
+
+    #[test]
+    fn check_place_named() {
+        let mut by_name = Life::new(10, 10);
+        assert!(by_name.place_named("glider", 3, 4));
+
+        let mut expected = Life::new(10, 10);
+        expected.write_right_glider(3, 4);
+        assert_eq!(by_name.as_bitgrid(), expected.as_bitgrid());
+
+        // Unknown names leave the board alone
+        assert!(!by_name.place_named("glider gun", 0, 0));
+        assert!(!by_name.place_named("", 0, 0));
+        assert_eq!(by_name.as_bitgrid(), expected.as_bitgrid());
+    }
+
+    #[test]
+    fn check_patterns_are_all_placeable() {
+        for (i, &(name, cells)) in PATTERNS.iter().enumerate() {
+            assert!(
+                PATTERNS[..i].iter().all(|&(other, _)| other != name),
+                "{name:?} is listed twice"
+            );
+
+            let mut life = Life::new(16, 16);
+            assert!(life.place_named(name, 2, 2), "{name:?}");
+            assert_eq!(
+                life.as_bitgrid().count_ones(),
+                cells.len() as i32,
+                "{name:?}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::evenly(32, 16, 8, 4 * 2)]
+    #[case::uneven(30, 10, 8, 3 * 1)]
+    #[case::tight(15, 17, 5, 3 * 3)]
+    #[case::too_small(4, 8, 5, 0)]
+    fn check_fill_glider_field(
+        #[case] width: usize,
+        #[case] height: usize,
+        #[case] spacing: usize,
+        #[case] expected: usize,
+    ) {
+        let mut life = Life::new(width, height);
+        assert_eq!(life.fill_glider_field(spacing), expected);
+        assert_eq!(life.as_bitgrid().count_ones(), 5 * expected as i32);
+
+        // Every glider is intact, and matches one written on its own
+        let mut lone = Life::new(width, height);
+        for y in (0..height / spacing).map(|ty| (ty * spacing) as i16) {
+            for x in (0..width / spacing).map(|tx| (tx * spacing) as i16) {
+                lone.clear();
+                lone.write_right_glider(x, y);
+                for (cx, cy) in (0..3).flat_map(|dy| (0..3).map(move |dx| (x + dx, y + dy))) {
+                    assert_eq!(life.get(cx, cy), lone.get(cx, cy), "glider at ({x}, {y})");
+                }
+            }
+        }
+
+        // Gliders that don't touch keep flying as gliders, even after wrapping around
+        for _ in 0..4 * width.max(height) {
+            life.step();
+        }
+        assert_eq!(life.as_bitgrid().count_ones(), 5 * expected as i32);
+    }
+
+    #[test]
+    fn check_undo_glider() {
+        let mut life = Life::new(32, 32);
+        life.write_right_glider(4, 4);
+        life.enable_undo(8);
+        assert_eq!(life.undo_depth(), 0);
+        assert!(!life.undo());
+
+        let mut boards = vec![life.as_bitgrid().clone()];
+        for _ in 0..5 {
+            life.step();
+            boards.push(life.as_bitgrid().clone());
+        }
+        assert_eq!(life.undo_depth(), 5);
+
+        // A glider only ever changes a handful of cells, out of 1024
+        let diffs = life.undo_cell_count();
+        assert!(diffs <= 5 * 8, "{diffs} cells remembered");
+
+        for generation in (0..5).rev() {
+            assert!(life.undo());
+            assert_eq!(life.generation(), generation as u64);
+            assert_eq!(life.as_bitgrid(), &boards[generation]);
+        }
+        assert!(!life.undo());
+        assert_eq!(life.undo_cell_count(), 0);
+
+        // Undone steps play out the same way again
+        life.step();
+        assert_eq!(life.as_bitgrid(), &boards[1]);
+    }
+
+    #[test]
+    fn check_undo_is_bounded() {
+        let mut life = Life::new(32, 32);
+        life.write_glider_gun(1, 1);
+        life.enable_undo(3);
+
+        let mut boards = vec![];
+        for _ in 0..10 {
+            boards.push(life.as_bitgrid().clone());
+            life.step();
+        }
+        assert_eq!(life.undo_depth(), 3);
+
+        for _ in 0..3 {
+            assert!(life.undo());
+        }
+        assert!(!life.undo());
+        assert_eq!(life.as_bitgrid(), &boards[7]);
+        assert_eq!(life.generation(), 7);
+
+        // Clearing forgets everything, since those steps can't be undone anymore
+        life.step();
+        life.clear();
+        assert_eq!(life.undo_depth(), 0);
+
+        life.disable_undo();
+        life.step();
+        assert!(!life.undo());
+    }
+
+    #[test]
+    fn check_undo_zero_steps() {
+        let mut life = Life::new(32, 32);
+        life.write_glider_gun(1, 1);
+        life.enable_undo(0);
+
+        for _ in 0..10 {
+            life.step();
+        }
+        assert_eq!(life.undo_depth(), 0);
+        assert_eq!(life.undo_cell_count(), 0);
+        assert!(!life.undo());
+        assert_eq!(life.generation(), 10);
+    }
+
+    #[test]
+    fn check_glider_gun_fires() {
+        let mut life = Life::new(64, 64);
+        life.write_glider_gun(1, 1);
+        let population = life.as_bitgrid().count_ones();
+        assert_eq!(population, 36);
+
+        // The gun has a period of 30, and each volley adds a 5-cell glider
+        for _ in 0..60 {
+            life.step();
+        }
+        assert_eq!(life.as_bitgrid().count_ones(), population + 2 * 5);
+    }
+
+    #[test]
+    fn check_glider_counter_counts_gun_volleys() {
+        // Big enough that no glider wraps back around into the gun during the test
+        let mut life = Life::new(128, 128);
+        life.write_glider_gun(1, 1);
+        let mut counter = GliderCounter::new(48);
+
+        let mut counted_at = vec![];
+        for _ in 0..400 {
+            life.step();
+            for _ in 0..counter.update(&life) {
+                counted_at.push(life.generation());
+            }
+
+            // Checking the same board again doesn't count anything twice
+            assert_eq!(counter.update(&life), 0);
+        }
+
+        // Gliders take a while to reach the line, and then arrive once per period of the gun
+        assert_eq!(counter.count(), counted_at.len() as u32);
+        assert!(counted_at.len() >= 5, "{counted_at:?}");
+        for pair in counted_at.windows(2) {
+            assert_eq!(pair[1] - pair[0], 30, "{counted_at:?}");
+        }
+    }
+
+    #[test]
+    fn check_is_lone_right_glider() {
+        let mut life = Life::new(8, 8);
+        life.write_right_glider(2, 2);
+        assert!(life.is_lone_right_glider(2, 2));
+        assert!(!life.is_lone_right_glider(1, 2));
+        assert!(!life.is_lone_right_glider(2, 1));
+
+        // Other phases don't count
+        life.step();
+        assert!(!(0..8).any(|y| (0..8).any(|x| life.is_lone_right_glider(x, y))));
+        life.step();
+        life.step();
+        life.step();
+        assert!(life.is_lone_right_glider(3, 3));
+
+        // Neither do gliders touching something
+        life.set(6, 4, true);
+        assert!(!life.is_lone_right_glider(3, 3));
+
+        // And it wraps like the rest of the board
+        let mut life = Life::new(8, 8);
+        life.write_right_glider(6, 7);
+        assert!(life.is_lone_right_glider(6, 7));
+        assert!(life.is_lone_right_glider(-2, -1));
+    }
+
     #[test]
     fn check_spinner_spins() {
         let mut life = Life::new(5, 5);
@@ -0,0 +1,24 @@
+//! The shapes `BitGrid` and `Life` take when serialized
+//!
+//! Deserializing goes through these so that we can check the buffers match the dimensions before building the real
+//! thing.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BitGrid;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct BitGridRepr {
+    pub width: i16,
+    pub height: i16,
+    /// Packed the same way as [`BitGrid::as_bytes()`]
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct LifeRepr {
+    pub cells: BitGrid,
+    pub generation: u64,
+}
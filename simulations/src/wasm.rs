@@ -0,0 +1,152 @@
+//! A tiny C ABI over [`Life`], for driving it from a browser canvas without `wasm-bindgen`
+//!
+//! The JS side owns a `*mut Life` handle from [`life_new()`], steps it, and reads the cells straight out of the
+//! module's memory through [`life_bytes_ptr()`]. Cells are packed 8 to a byte, least significant bit first, and each
+//! row starts on a new byte, so a row is `ceil(width / 8)` bytes long.
+//!
+//! Build it with:
+//! ```sh
+//! cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//! ```
+
+use alloc::boxed::Box;
+
+use crate::Life;
+
+/// Creates a new board where every cell is **dead**. Free it with [`life_free()`].
+///
+/// Returns null if there can't be a `width` x `height` board (see [`Life::try_new()`]), like when either is `0`.
+#[no_mangle]
+pub extern "C" fn life_new(width: u32, height: u32) -> *mut Life {
+    Life::try_new(width as usize, height as usize)
+        .map_or(core::ptr::null_mut(), |life| Box::into_raw(Box::new(life)))
+}
+
+/// Frees a board created by [`life_new()`]
+///
+/// # Safety
+/// `life` must have come from [`life_new()`] and not been freed yet. Passing null does nothing.
+#[no_mangle]
+pub unsafe extern "C" fn life_free(life: *mut Life) {
+    if !life.is_null() {
+        drop(Box::from_raw(life));
+    }
+}
+
+/// See [`Life::step()`]
+///
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_step(life: *mut Life) -> u32 {
+    (*life).step()
+}
+
+/// See [`Life::set()`]. Coordinates wrap around, however far off the board they are.
+///
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_set(life: *mut Life, x: i32, y: i32, is_alive: bool) {
+    let life = &mut *life;
+
+    // Wrap while we still have all of the bits, since casting to `i16` first would wrap around 65536 instead
+    let x = x.rem_euclid(life.width() as i32);
+    let y = y.rem_euclid(life.height() as i32);
+    life.set(x as i16, y as i16, is_alive);
+}
+
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_width(life: *const Life) -> u32 {
+    (*life).width() as u32
+}
+
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_height(life: *const Life) -> u32 {
+    (*life).height() as u32
+}
+
+/// Points at the packed cells. See the module docs for the layout, and [`life_bytes_len()`] for the length.
+///
+/// The pointer is only valid until the next call to [`life_step()`] or [`life_free()`].
+///
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_bytes_ptr(life: *const Life) -> *const u8 {
+    (*life).as_bitgrid().as_bytes().as_ptr()
+}
+
+/// The number of bytes behind [`life_bytes_ptr()`]
+///
+/// # Safety
+/// `life` must be a live pointer from [`life_new()`].
+#[no_mangle]
+pub unsafe extern "C" fn life_bytes_len(life: *const Life) -> usize {
+    (*life).as_bitgrid().as_bytes().len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_bytes_ptr() {
+        unsafe {
+            let life = life_new(12, 3);
+            assert_eq!(life_width(life), 12);
+            assert_eq!(life_height(life), 3);
+            assert_eq!(life_bytes_len(life), 2 * 3);
+
+            // A blinker across the middle row
+            life_set(life, 3, 1, true);
+            life_set(life, 4, 1, true);
+            life_set(life, 5, 1, true);
+
+            let bytes = core::slice::from_raw_parts(life_bytes_ptr(life), life_bytes_len(life));
+            assert_eq!(bytes, [0x00, 0x00, 0b0011_1000, 0x00, 0x00, 0x00]);
+
+            // Which turns vertical, and the pointer sees the new cells
+            assert_eq!(life_step(life), 4);
+            let bytes = core::slice::from_raw_parts(life_bytes_ptr(life), life_bytes_len(life));
+            assert_eq!(
+                bytes,
+                [0b0001_0000, 0x00, 0b0001_0000, 0x00, 0b0001_0000, 0x00]
+            );
+
+            life_free(life);
+            life_free(core::ptr::null_mut());
+
+            // Empty boards aren't allowed
+            assert!(life_new(0, 3).is_null());
+            assert!(life_new(12, 0).is_null());
+
+            // Neither are ones too big for a BitGrid, rather than panicking across the FFI boundary
+            assert!(life_new(40_000, 1).is_null());
+            assert!(life_new(u32::MAX, u32::MAX).is_null());
+        }
+    }
+
+    #[test]
+    fn check_set_wraps_far_coordinates() {
+        unsafe {
+            let life = life_new(12, 3);
+
+            // 65541 is 5 as an i16, but 65541 % 12 is 9
+            life_set(life, 65_541, 0, true);
+            life_set(life, -1, -100_000, true);
+
+            assert!((*life).get(9, 0));
+            assert!(!(*life).get(5, 0));
+            // -100_000 is 2 mod 3
+            assert!((*life).get(11, 2));
+            assert_eq!((*life).as_bitgrid().count_ones(), 2);
+
+            life_free(life);
+        }
+    }
+}